@@ -0,0 +1,88 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+use cw2::set_contract_version;
+use rhaki_cw_plus::traits::{IntoAddr, IntoBinaryResult};
+
+use crate::error::ContractError;
+use crate::execute::{bond, claim, distribute_rewards, sweep, unbond, update_config, withdraw};
+use crate::query::{qy_config, qy_pending_rewards, qy_staker, qy_total_bonded};
+use crate::state::{CONFIG, TOTAL_BONDED};
+
+use ratatouille_pkg::cookie_staking::definitions::Config;
+use ratatouille_pkg::cookie_staking::msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use ratatouille_pkg::flambe_factory::{
+    definitions::Config as FactoryConfig, msgs::QueryMsg as FactoryQueryMsg,
+};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let factory = msg.factory.into_addr(deps.api)?;
+
+    let factory_config: FactoryConfig = deps
+        .querier
+        .query_wasm_smart(&factory, &FactoryQueryMsg::Config {})?;
+
+    let config = Config {
+        owner: msg.owner.into_addr(deps.api)?,
+        factory,
+        cookie_denom: factory_config.cookie_token.denom,
+        unbonding_period: msg.unbonding_period,
+    };
+
+    config.validate()?;
+
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_BONDED.save(deps.storage, &Uint128::zero())?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("cookie_staking", "start.cooking"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Bond {} => bond(deps, env, info),
+        ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+        ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::Distribute {} => distribute_rewards(deps, env),
+        ExecuteMsg::UpdateConfig { unbonding_period } => {
+            update_config(deps, info, unbonding_period)
+        }
+        ExecuteMsg::Sweep { denom, recipient } => sweep(deps, env, info, denom, recipient),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => qy_config(deps).into_binary(),
+        QueryMsg::Staker { address } => qy_staker(deps, address).into_binary(),
+        QueryMsg::PendingRewards { address } => {
+            qy_pending_rewards(deps, env, address).into_binary()
+        }
+        QueryMsg::TotalBonded {} => qy_total_bonded(deps).into_binary(),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
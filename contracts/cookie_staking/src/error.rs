@@ -0,0 +1,38 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid Empty Update")]
+    InvalidEmptyUpdate,
+
+    #[error("Invalid Amount: amount can't be 0")]
+    InvalidZeroAmount {},
+
+    #[error("Insufficient Bonded: bonded {bonded}, requested {requested}")]
+    InsufficientBonded { bonded: Uint128, requested: Uint128 },
+
+    #[error("Too Many Unbondings: max {max}")]
+    TooManyUnbondings { max: usize },
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Nothing to sweep")]
+    NothingToSweep {},
+
+    #[error("Invalid Sweep Denom: {denom} belongs to the stakers")]
+    InvalidSweepDenom { denom: String },
+}
@@ -0,0 +1,268 @@
+use cosmwasm_std::{attr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use ratatouille_pkg::cookie_staking::definitions::Unbonding;
+use rhaki_cw_plus::{asset::only_one_coin, traits::IntoAddr};
+
+use crate::{
+    functions::{distribute, reward_denoms, update_staker_rewards},
+    state::{CONFIG, MAX_UNBONDINGS, REWARD_INDEXES, STAKERS, STAKER_REWARDS, TOTAL_BONDED},
+    ContractError,
+};
+
+pub fn bond(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let received = only_one_coin(&info.funds, Some(config.cookie_denom.clone()))?;
+
+    if received.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let indexes = distribute(deps.branch(), &env, &config)?;
+
+    let mut staker = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    update_staker_rewards(deps.storage, &info.sender, staker.bonded, &indexes)?;
+
+    staker.bonded += received.amount;
+    STAKERS.save(deps.storage, &info.sender, &staker)?;
+
+    TOTAL_BONDED.update(deps.storage, |total| -> Result<_, ContractError> {
+        Ok(total.checked_add(received.amount)?)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "bond")
+        .add_attribute("staker", info.sender)
+        .add_attribute("amount", received.amount))
+}
+
+pub fn unbond(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut staker = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    if staker.bonded < amount {
+        return Err(ContractError::InsufficientBonded {
+            bonded: staker.bonded,
+            requested: amount,
+        });
+    }
+
+    if staker.unbondings.len() >= MAX_UNBONDINGS {
+        return Err(ContractError::TooManyUnbondings {
+            max: MAX_UNBONDINGS,
+        });
+    }
+
+    let indexes = distribute(deps.branch(), &env, &config)?;
+
+    update_staker_rewards(deps.storage, &info.sender, staker.bonded, &indexes)?;
+
+    let release_at = env.block.time.seconds() + config.unbonding_period;
+
+    staker.bonded -= amount;
+    staker.unbondings.push(Unbonding { amount, release_at });
+    STAKERS.save(deps.storage, &info.sender, &staker)?;
+
+    TOTAL_BONDED.update(deps.storage, |total| -> Result<_, ContractError> {
+        Ok(total.checked_sub(amount)?)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unbond")
+        .add_attribute("staker", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("release_at", release_at.to_string()))
+}
+
+pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut staker = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    let (released, unbondings): (Vec<Unbonding>, Vec<Unbonding>) = staker
+        .unbondings
+        .into_iter()
+        .partition(|unbonding| unbonding.release_at <= env.block.time.seconds());
+
+    let amount = released
+        .iter()
+        .fold(Uint128::zero(), |acc, unbonding| acc + unbonding.amount);
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    staker.unbondings = unbondings;
+
+    if staker.bonded.is_zero() && staker.unbondings.is_empty() {
+        STAKERS.remove(deps.storage, &info.sender);
+    } else {
+        STAKERS.save(deps.storage, &info.sender, &staker)?;
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount.u128(), config.cookie_denom)],
+        })
+        .add_attribute("action", "withdraw")
+        .add_attribute("staker", info.sender)
+        .add_attribute("amount", amount))
+}
+
+pub fn claim(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let bonded = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .bonded;
+
+    let indexes = distribute(deps.branch(), &env, &config)?;
+
+    let rewards = update_staker_rewards(deps.storage, &info.sender, bonded, &indexes)?;
+
+    let mut claimed = vec![];
+
+    for (denom, mut reward) in rewards {
+        if reward.pending.is_zero() {
+            continue;
+        }
+
+        let mut index = REWARD_INDEXES.load(deps.storage, &denom)?;
+        index.distributed = index.distributed.checked_sub(reward.pending)?;
+        REWARD_INDEXES.save(deps.storage, &denom, &index)?;
+
+        claimed.push(Coin::new(reward.pending.u128(), denom.clone()));
+
+        reward.pending = Uint128::zero();
+        STAKER_REWARDS.save(deps.storage, (&info.sender, &denom), &reward)?;
+    }
+
+    if claimed.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: claimed.clone(),
+        })
+        .add_attribute("action", "claim")
+        .add_attribute("staker", info.sender)
+        .add_attribute(
+            "claimed",
+            claimed
+                .iter()
+                .map(|coin| coin.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ))
+}
+
+pub fn distribute_rewards(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let indexes = distribute(deps, &env, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "distribute")
+        .add_attributes(
+            indexes
+                .into_iter()
+                .map(|index| attr(index.denom, index.index.to_string())),
+        ))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    unbonding_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![];
+
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+        attrs.push(attr("unbonding_period", unbonding_period.to_string()));
+    }
+
+    if attrs.is_empty() {
+        return Err(ContractError::InvalidEmptyUpdate);
+    }
+
+    config.validate()?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attributes(attrs))
+}
+
+pub fn sweep(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The rewards of the denoms removed from the factory settings are still claimable
+    if denom == config.cookie_denom
+        || REWARD_INDEXES.has(deps.storage, &denom)
+        || reward_denoms(deps.as_ref(), &config)?.contains(&denom)
+    {
+        return Err(ContractError::InvalidSweepDenom { denom });
+    }
+
+    let amount = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToSweep {});
+    }
+
+    let recipient = recipient
+        .map(|val| val.into_addr(deps.api))
+        .transpose()?
+        .unwrap_or(config.owner);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(amount.u128(), &denom)],
+        })
+        .add_attribute("action", "sweep")
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount)
+        .add_attribute("recipient", recipient))
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{
+    Addr, Decimal256, Deps, DepsMut, Env, Order, StdResult, Storage, Uint128, Uint256,
+};
+use ratatouille_pkg::{
+    cookie_staking::definitions::{Config, RewardIndex, StakerReward},
+    flambe_factory::{definitions::Config as FactoryConfig, msgs::QueryMsg as FactoryQueryMsg},
+};
+
+use crate::state::{REWARD_INDEXES, STAKER_REWARDS, TOTAL_BONDED};
+
+/// Reward denoms, the `pair_denom` of the factory flambe settings
+pub fn reward_denoms(deps: Deps, config: &Config) -> StdResult<Vec<String>> {
+    let factory_config: FactoryConfig = deps
+        .querier
+        .query_wasm_smart(&config.factory, &FactoryQueryMsg::Config {})?;
+
+    let mut denoms: Vec<String> = factory_config
+        .flambe_settings
+        .into_iter()
+        .map(|setting| setting.pair_denom)
+        .filter(|denom| denom != &config.cookie_denom)
+        .collect();
+
+    denoms.sort();
+    denoms.dedup();
+
+    Ok(denoms)
+}
+
+/// Reward indexes updated with the rewards received since the last distribution.
+///
+/// Denoms removed from the factory settings are kept, so the pending rewards can still be claimed.
+/// Without bonded cookies the rewards are left undistributed until the next bond.
+pub fn updated_reward_indexes(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+) -> StdResult<Vec<RewardIndex>> {
+    let mut indexes = REWARD_INDEXES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<String, RewardIndex>>>()?;
+
+    for denom in reward_denoms(deps, config)? {
+        indexes.entry(denom.clone()).or_insert_with(|| RewardIndex {
+            denom,
+            index: Decimal256::zero(),
+            distributed: Uint128::zero(),
+        });
+    }
+
+    let total_bonded = TOTAL_BONDED.load(deps.storage)?;
+
+    if total_bonded.is_zero() {
+        return Ok(indexes.into_values().collect());
+    }
+
+    for index in indexes.values_mut() {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &index.denom)?
+            .amount;
+
+        let received = balance.saturating_sub(index.distributed);
+
+        if received.is_zero() {
+            continue;
+        }
+
+        let increment = Decimal256::from_ratio(received, total_bonded);
+
+        // The rounding dust is left to the next distribution
+        index.index += increment;
+        index.distributed += Uint128::try_from(Uint256::from(total_bonded).mul_floor(increment))?;
+    }
+
+    Ok(indexes.into_values().collect())
+}
+
+/// Update and save the reward indexes, returning them
+pub fn distribute(deps: DepsMut, env: &Env, config: &Config) -> StdResult<Vec<RewardIndex>> {
+    let indexes = updated_reward_indexes(deps.as_ref(), env, config)?;
+
+    for index in &indexes {
+        REWARD_INDEXES.save(deps.storage, &index.denom, index)?;
+    }
+
+    Ok(indexes)
+}
+
+/// Rewards of the staker accrued on `bonded` up to the current `index`
+pub fn accrue_reward(
+    reward: StakerReward,
+    bonded: Uint128,
+    index: &RewardIndex,
+) -> StdResult<StakerReward> {
+    let accrued = Uint256::from(bonded).mul_floor(index.index - reward.index);
+
+    Ok(StakerReward {
+        index: index.index,
+        pending: reward.pending + Uint128::try_from(accrued)?,
+    })
+}
+
+/// Accrue and save the rewards of the staker, it must be called before any change of its bonded amount
+pub fn update_staker_rewards(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    bonded: Uint128,
+    indexes: &[RewardIndex],
+) -> StdResult<Vec<(String, StakerReward)>> {
+    indexes
+        .iter()
+        .map(|index| {
+            let reward = STAKER_REWARDS
+                .may_load(storage, (staker, &index.denom))?
+                .unwrap_or_default();
+
+            let reward = accrue_reward(reward, bonded, index)?;
+
+            STAKER_REWARDS.save(storage, (staker, &index.denom), &reward)?;
+
+            Ok((index.denom.clone(), reward))
+        })
+        .collect()
+}
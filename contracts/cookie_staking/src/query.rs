@@ -0,0 +1,50 @@
+use cosmwasm_std::{Coin, Deps, Env, StdResult, Uint128};
+use ratatouille_pkg::cookie_staking::definitions::{Config, Staker};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::{
+    functions::{accrue_reward, updated_reward_indexes},
+    state::{CONFIG, STAKERS, STAKER_REWARDS, TOTAL_BONDED},
+};
+
+pub fn qy_config(deps: Deps) -> StdResult<Config> {
+    CONFIG.load(deps.storage)
+}
+
+pub fn qy_staker(deps: Deps, address: String) -> StdResult<Staker> {
+    Ok(STAKERS
+        .may_load(deps.storage, &address.into_addr(deps.api)?)?
+        .unwrap_or_default())
+}
+
+pub fn qy_pending_rewards(deps: Deps, env: Env, address: String) -> StdResult<Vec<Coin>> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = address.into_addr(deps.api)?;
+
+    let bonded = STAKERS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default()
+        .bonded;
+
+    let mut rewards = updated_reward_indexes(deps, &env, &config)?
+        .into_iter()
+        .map(|index| {
+            let reward = STAKER_REWARDS
+                .may_load(deps.storage, (&address, &index.denom))?
+                .unwrap_or_default();
+
+            Ok(Coin::new(
+                accrue_reward(reward, bonded, &index)?.pending.u128(),
+                index.denom,
+            ))
+        })
+        .collect::<StdResult<Vec<Coin>>>()?;
+
+    rewards.retain(|coin| !coin.amount.is_zero());
+
+    Ok(rewards)
+}
+
+pub fn qy_total_bonded(deps: Deps) -> StdResult<Uint128> {
+    TOTAL_BONDED.load(deps.storage)
+}
@@ -0,0 +1,17 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use ratatouille_pkg::cookie_staking::definitions::{Config, RewardIndex, Staker, StakerReward};
+
+pub const CONFIG: Item<Config> = Item::new("config_key");
+pub const TOTAL_BONDED: Item<Uint128> = Item::new("total_bonded");
+
+/// Max pending unbondings for each staker
+pub const MAX_UNBONDINGS: usize = 10;
+
+pub const STAKERS: Map<&Addr, Staker> = Map::new("stakers");
+
+/// Global reward index, keyed by reward denom
+pub const REWARD_INDEXES: Map<&str, RewardIndex> = Map::new("reward_indexes");
+
+/// Rewards of each staker, keyed by `(staker, reward denom)`
+pub const STAKER_REWARDS: Map<(&Addr, &str), StakerReward> = Map::new("staker_rewards");
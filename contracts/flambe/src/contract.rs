@@ -1,22 +1,40 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
+use cosmwasm_std::{
+    attr, Binary, Decimal, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    Uint128,
+};
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::UserPositionsRequest;
 
+use cw2::set_contract_version;
 use rhaki_cw_plus::traits::IntoBinaryResult;
 
 use crate::error::ContractError;
-use crate::execute::{check_to_pending, deploy, swap};
+use crate::execute::{
+    check_to_pending, claim_creator_fees, collect_fees, create_pool, deploy, fail, refund,
+    set_paused, swap, swap_exact_out, update_config, update_owner,
+};
 
-use crate::query::{qy_config, qy_info, qy_simulate};
-use crate::reply::{reply_pool_creation, reply_position_creation};
-use crate::state::{ReplyIds, CONFIG};
-use ratatouille_pkg::flambe::definitions::{Config, FlambeStatus};
+use crate::migration::assert_and_set_version;
+use crate::query::{
+    qy_candles, qy_config, qy_holders, qy_info, qy_position_lock, qy_simulate, qy_simulate_reverse,
+    qy_trades, qy_trades_by_user, qy_twap,
+};
+use crate::reply::{
+    reply_astroport_pair_creation, reply_balancer_pool_creation, reply_pool_creation,
+    reply_position_creation,
+};
+use crate::state::{ReplyIds, CL_POOL_ID, CL_POSITION_ID, CONFIG, PRICE_OBSERVATIONS};
+use ratatouille_pkg::flambe::definitions::{Config, FlambeStatus, PriceObservation};
 use ratatouille_pkg::flambe::msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -28,6 +46,10 @@ pub fn instantiate(
 
     let virtual_reserve = coin.amount * msg.flambe_setting.initial_price;
 
+    if msg.keeper_bounty > Decimal::one() {
+        return Err(ContractError::InvalidFee {});
+    }
+
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
         factory: deps.api.addr_validate(&msg.factory)?,
@@ -39,10 +61,37 @@ pub fn instantiate(
         creator: deps.api.addr_validate(&msg.creator)?,
         virtual_reserve,
         status: FlambeStatus::OPEN,
+        keeper_bounty: msg.keeper_bounty,
+        accrued_bounty: Uint128::zero(),
+        accrued_creator_fees: Uint128::zero(),
+        burned: Uint128::zero(),
+        cookie_staking: msg
+            .cookie_staking
+            .map(|val| deps.api.addr_validate(&val))
+            .transpose()?,
+        fee_split: msg.fee_split,
+        auto_graduation: msg.auto_graduation,
+        paused: false,
+        launched_at: env.block.time.seconds(),
     };
 
+    config.fee_split.validate(&config.cookie_staking)?;
+
     CONFIG.save(deps.storage, &config)?;
 
+    // The twap starts from the initial price
+    PRICE_OBSERVATIONS.save(
+        deps.storage,
+        env.block.time.seconds(),
+        &PriceObservation {
+            timestamp: env.block.time.seconds(),
+            cumulative_price: Decimal256::zero(),
+            price: config.flambe_setting.initial_price,
+        },
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new().add_attribute("flambè", "start.cooking"))
 }
 
@@ -58,8 +107,25 @@ pub fn execute(
             min_amount_out,
             user,
         } => swap(deps, info, env, user, min_amount_out),
-        ExecuteMsg::Deploy(msg) => deploy(deps, info, env, msg),
-        ExecuteMsg::CheckToPending => check_to_pending(deps, env, info.sender),
+        ExecuteMsg::SwapExactOut {
+            amount_out,
+            max_amount_in,
+            user,
+        } => swap_exact_out(deps, info, env, user, amount_out, max_amount_in),
+        ExecuteMsg::Deploy { keeper, swap_msg } => deploy(deps, info, env, keeper, swap_msg),
+        ExecuteMsg::CheckToPending { keeper } => check_to_pending(deps, env, info.sender, keeper),
+        ExecuteMsg::CreatePool {} => create_pool(deps, env, info.sender),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, info, paused),
+        ExecuteMsg::UpdateOwner { owner } => update_owner(deps, info, owner),
+        ExecuteMsg::Fail {} => fail(deps, info, env),
+        ExecuteMsg::Refund {} => refund(deps, info, env),
+        ExecuteMsg::CollectFees {} => collect_fees(deps, env),
+        ExecuteMsg::ClaimCreatorFees {} => claim_creator_fees(deps, info),
+        ExecuteMsg::UpdateConfig {
+            burner_addr,
+            fee_collector,
+            swap_fee,
+        } => update_config(deps, info, burner_addr, fee_collector, swap_fee),
     }
 }
 
@@ -69,6 +135,28 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => qy_config(deps).into_binary(),
         QueryMsg::Info {} => qy_info(deps, env).into_binary(),
         QueryMsg::Simulate { offer, amount } => qy_simulate(deps, env, offer, amount).into_binary(),
+        QueryMsg::SimulateReverse { ask, amount } => {
+            qy_simulate_reverse(deps, env, ask, amount).into_binary()
+        }
+        QueryMsg::Trades { start_after, limit } => {
+            qy_trades(deps, start_after, limit).into_binary()
+        }
+        QueryMsg::TradesByUser {
+            user,
+            start_after,
+            limit,
+        } => qy_trades_by_user(deps, user, start_after, limit).into_binary(),
+        QueryMsg::Candles {
+            resolution,
+            from,
+            to,
+            limit,
+        } => qy_candles(deps, env, resolution, from, to, limit).into_binary(),
+        QueryMsg::Twap { window_seconds } => qy_twap(deps, env, window_seconds).into_binary(),
+        QueryMsg::Holders { limit, start_after } => {
+            qy_holders(deps, limit, start_after).into_binary()
+        }
+        QueryMsg::PositionLock {} => qy_position_lock(deps, env).into_binary(),
     }
 }
 
@@ -77,10 +165,78 @@ pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Contract
     match ReplyIds::from_repr(reply.id).ok_or(ContractError::InvalidReplyId(reply.id))? {
         ReplyIds::PoolCreation => reply_pool_creation(deps, env, reply),
         ReplyIds::PositionCreation => reply_position_creation(deps, env, reply),
+        ReplyIds::BalancerPoolCreation => reply_balancer_pool_creation(deps, env, reply),
+        ReplyIds::AstroportPairCreation => reply_astroport_pair_creation(deps, env, reply),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    assert_and_set_version(deps.storage)?;
+
+    let mut attrs = vec![];
+
+    match msg {
+        MigrateMsg::Version {} => {}
+        MigrateMsg::MigrateConfig {
+            auto_graduation,
+            keeper_bounty,
+        } => {
+            let mut config = CONFIG.load(deps.storage)?;
+
+            if let Some(auto_graduation) = auto_graduation {
+                config.auto_graduation = auto_graduation;
+                attrs.push(attr("auto_graduation", auto_graduation.to_string()));
+            }
+
+            if let Some(keeper_bounty) = keeper_bounty {
+                if keeper_bounty > Decimal::one() {
+                    return Err(ContractError::InvalidFee {});
+                }
+
+                config.keeper_bounty = keeper_bounty;
+                attrs.push(attr("keeper_bounty", keeper_bounty.to_string()));
+            }
+
+            CONFIG.save(deps.storage, &config)?;
+        }
+        MigrateMsg::BackfillPosition {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            // The position created at graduation is the one holding the main token
+            let position = UserPositionsRequest {
+                address: env.contract.address.to_string(),
+                pool_id: 0,
+                pagination: None,
+            }
+            .query(&deps.querier)?
+            .positions
+            .into_iter()
+            .find(|val| {
+                [&val.asset0, &val.asset1].iter().any(|asset| {
+                    asset
+                        .as_ref()
+                        .is_some_and(|coin| coin.denom == config.main_denom)
+                })
+            })
+            .and_then(|val| val.position);
+
+            // Skip the flambes without a CL position, so the whole batch can be migrated
+            match (CL_POSITION_ID.may_load(deps.storage)?, position) {
+                (None, Some(position)) => {
+                    CL_POOL_ID.save(deps.storage, &position.pool_id)?;
+                    CL_POSITION_ID.save(deps.storage, &position.position_id)?;
+
+                    attrs.push(attr("pool_id", position.pool_id.to_string()));
+                    attrs.push(attr("position_id", position.position_id.to_string()));
+                }
+                _ => attrs.push(attr("backfill", "skipped")),
+            }
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_attributes(attrs))
 }
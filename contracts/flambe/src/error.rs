@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,9 +24,51 @@ pub enum ContractError {
     #[error("Pump Open")]
     PumpOpen {},
 
+    #[error("Invalid Denom: {0}")]
+    InvalidDenom(String),
+
     #[error("Invalid Fee")]
     InvalidFee {},
 
+    #[error("Invalid Empty Update")]
+    InvalidEmptyUpdate {},
+
+    #[error("Flambè Expired")]
+    FlambeExpired {},
+
+    #[error("Deadline Not Reached")]
+    DeadlineNotReached {},
+
+    #[error("Status not in Failed")]
+    NotFailed {},
+
+    #[error("No CL position to collect fees from")]
+    NoPosition {},
+
+    #[error("No fees to collect")]
+    NoFeesToCollect {},
+
+    #[error("No creator fees to claim")]
+    NoCreatorFees {},
+
+    #[error("Flambè Paused")]
+    FlambePaused {},
+
+    #[error("Max Buy Per Tx Exceeded during the launch window: max {max}")]
+    MaxBuyPerTxExceeded { max: Uint128 },
+
+    #[error("Max Holding Exceeded during the launch window: max {max}")]
+    MaxHoldingExceeded { max: Uint128 },
+
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Invalid Contract Name: expected {expected}, found {found}")]
+    InvalidContractName { expected: String, found: String },
+
+    #[error("Migration Downgrade: stored {stored}, new {new}")]
+    MigrationDowngrade { stored: String, new: String },
+
     #[error("Invalid ReplyId:{0}")]
     InvalidReplyId(u64),
 }
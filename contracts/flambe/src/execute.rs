@@ -1,23 +1,58 @@
+use std::{
+    cmp::{max, min},
+    str::FromStr,
+};
+
+use astroport::{
+    asset::PairInfo,
+    factory::{
+        ExecuteMsg as AstroportFactoryExecuteMsg, PairType, QueryMsg as AstroportFactoryQueryMsg,
+    },
+    pair_concentrated::ConcentratedPoolParams,
+};
 use cosmwasm_std::{
-    Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response, SubMsg, Uint128, WasmMsg,
+    attr, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
+use cw_storage_plus::Bound;
 use osmosis_std::types::osmosis::{
-    concentratedliquidity::poolmodel::concentrated::v1beta1::MsgCreateConcentratedPool,
+    concentratedliquidity::{
+        poolmodel::concentrated::v1beta1::MsgCreateConcentratedPool,
+        v1beta1::{MsgCollectIncentives, MsgCollectSpreadRewards, PositionByIdRequest},
+    },
+    gamm::{
+        poolmodels::balancer::v1beta1::MsgCreateBalancerPool,
+        v1beta1::{PoolAsset, PoolParams},
+    },
     poolmanager::v1beta1::MsgSwapExactAmountOut,
 };
 use ratatouille_pkg::{
     flambe::{
-        definitions::{FlambeStatus, SwapResponse},
+        definitions::{
+            Candle, CandleResolution, Config, FlambeStatus, Holder, PriceObservation,
+            ReverseSwapResponse, SwapResponse, Trade, TradeSide, MAX_TWAP_WINDOW,
+        },
         msgs::ExecuteMsg,
     },
-    flambe_factory::msgs::{EndFlambeMsg, ExecuteMsg as FactoryExecuteMsg},
+    flambe_factory::{
+        definitions::{Config as FactoryConfig, GraduationTarget},
+        msgs::{EndFlambeSwapMsg, ExecuteMsg as FactoryExecuteMsg, QueryMsg as FactoryQueryMsg},
+    },
 };
-use rhaki_cw_plus::{math::IntoUint, wasm::WasmMsgBuilder};
+use rhaki_cw_plus::{math::IntoUint, traits::IntoBinary, wasm::WasmMsgBuilder};
 
 use crate::{
     error::ContractError,
-    functions::{compute_swap, get_pair_amount},
-    state::{ReplyIds, CONFIG},
+    functions::{
+        astroport_asset_infos, compute_buy_and_burn, compute_price, compute_swap,
+        compute_swap_exact_out, cumulative_price_at, get_main_amount, get_pair_amount,
+        graduation_liquidity, pool_creation_fee_amount_in_pair, pool_creation_fee_in_pair,
+    },
+    reply::provide_astroport_liquidity,
+    state::{
+        holders, trades, ReplyIds, CANDLES, CL_POSITION_ID, CONFIG, HOLDERS_COUNT,
+        PRICE_OBSERVATIONS, TRADES_COUNTER,
+    },
 };
 
 pub fn swap(
@@ -27,19 +62,11 @@ pub fn swap(
     user: String,
     min_amount_out: Uint128,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-
-    if info.sender != config.factory {
-        return Err(ContractError::Unauthorized {});
-    }
-
-    if config.status != FlambeStatus::OPEN {
-        return Err(ContractError::PumpClosed {});
-    }
-
     // user send amount_in native coin
     let offer = rhaki_cw_plus::asset::only_one_coin(&info.funds, None)?;
 
+    let config = assert_swap_allowed(deps.as_ref(), &env, &info, &offer.denom)?;
+
     let SwapResponse {
         return_amount,
         swap_fee,
@@ -50,49 +77,556 @@ pub fn swap(
         return Err(ContractError::SlippageError {});
     }
 
-    let fee_msg = if swap_fee.amount > Uint128::zero() {
-        Some(CosmosMsg::Bank(BankMsg::Send {
-            to_address: config.fee_collector.to_string(),
-            amount: vec![swap_fee.clone()],
-        }))
+    // The buy of the remaining supply is capped, the unused part of the offer is refunded
+    let (offer, swap_fee, refund) = if offer.denom == config.flambe_setting.pair_denom
+        && return_amount.amount == get_main_amount(deps.as_ref(), &env, &config)?
+    {
+        let ReverseSwapResponse {
+            offer_amount,
+            swap_fee,
+            ..
+        } = compute_swap_exact_out(
+            deps.as_ref(),
+            &env,
+            &config,
+            return_amount.clone(),
+            offer.amount,
+        )?;
+
+        let refund = Coin::new(
+            offer
+                .amount
+                .checked_sub(offer_amount.amount)
+                .map_err(StdError::from)?
+                .u128(),
+            offer.denom,
+        );
+
+        (offer_amount, swap_fee, Some(refund))
     } else {
-        None
+        (offer, swap_fee, None)
     };
 
+    settle_swap(
+        deps,
+        env,
+        config,
+        user,
+        offer,
+        return_amount,
+        swap_fee,
+        refund.clone(),
+    )
+    .map(|response| {
+        response
+            .add_attribute("action", "swap")
+            .add_attributes(refund.map(|refund| attr("refund_amount", refund.amount)))
+    })
+}
+
+pub fn swap_exact_out(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    user: String,
+    amount_out: Uint128,
+    max_amount_in: Uint128,
+) -> Result<Response, ContractError> {
+    let received = rhaki_cw_plus::asset::only_one_coin(&info.funds, None)?;
+
+    let config = assert_swap_allowed(deps.as_ref(), &env, &info, &received.denom)?;
+
+    let ask_denom = if received.denom == config.main_denom {
+        config.flambe_setting.pair_denom.clone()
+    } else {
+        config.main_denom.clone()
+    };
+
+    let ReverseSwapResponse {
+        offer_amount,
+        swap_fee,
+        ..
+    } = compute_swap_exact_out(
+        deps.as_ref(),
+        &env,
+        &config,
+        Coin::new(amount_out.u128(), ask_denom.clone()),
+        received.amount,
+    )?;
+
+    if offer_amount.denom != received.denom {
+        return Err(ContractError::InvalidDenom(received.denom));
+    }
+
+    if offer_amount.amount > max_amount_in {
+        return Err(ContractError::SlippageError {});
+    }
+
+    if offer_amount.amount > received.amount {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    let refund = Coin::new(
+        (received.amount - offer_amount.amount).u128(),
+        received.denom,
+    );
+
+    settle_swap(
+        deps,
+        env,
+        config,
+        user,
+        offer_amount,
+        Coin::new(amount_out.u128(), ask_denom),
+        swap_fee,
+        Some(refund.clone()),
+    )
+    .map(|response| {
+        response
+            .add_attribute("action", "swap_exact_out")
+            .add_attribute("refund_amount", refund.amount)
+    })
+}
+
+/// After the deadline only the sells are allowed, to exit along the curve
+fn assert_swap_allowed(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    offer_denom: &str,
+) -> Result<Config, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if config.paused {
+        return Err(ContractError::FlambePaused {});
+    }
+
+    match config.status {
+        FlambeStatus::OPEN if !is_expired(env, &config) => Ok(config),
+        FlambeStatus::OPEN | FlambeStatus::FAILED => {
+            if offer_denom == config.main_denom {
+                Ok(config)
+            } else {
+                Err(ContractError::FlambeExpired {})
+            }
+        }
+        _ => Err(ContractError::PumpClosed {}),
+    }
+}
+
+fn is_expired(env: &Env, config: &Config) -> bool {
+    config
+        .flambe_setting
+        .deadline
+        .is_some_and(|deadline| env.block.time.seconds() >= config.launched_at + deadline)
+}
+
+/// Pay the fee, send the output to the user, record the trade and check the graduation
+#[allow(clippy::too_many_arguments)]
+fn settle_swap(
+    deps: DepsMut,
+    env: Env,
+    mut config: Config,
+    user: String,
+    offer: Coin,
+    return_amount: Coin,
+    swap_fee: Coin,
+    refund: Option<Coin>,
+) -> Result<Response, ContractError> {
+    // Part of the fee is retained for the graduation keeper, while the flambe can graduate
+    let bounty = if config.status == FlambeStatus::OPEN {
+        swap_fee.amount * config.keeper_bounty
+    } else {
+        Uint128::zero()
+    };
+
+    let fee = swap_fee.amount - bounty;
+
+    let creator_fee = fee * config.fee_split.creator;
+
+    let stakers_fee = if config.cookie_staking.is_some() {
+        fee * config.fee_split.stakers
+    } else {
+        Uint128::zero()
+    };
+
+    // The buy and burn happens only while the flambe is trading along the curve
+    let burn_fee = if config.status == FlambeStatus::OPEN {
+        fee * config.fee_split.burn
+    } else {
+        Uint128::zero()
+    };
+
+    let protocol_fee = fee - creator_fee - stakers_fee - burn_fee;
+
+    let fee_msgs: Vec<CosmosMsg> = [
+        (Some(&config.fee_collector), protocol_fee),
+        (config.cookie_staking.as_ref(), stakers_fee),
+    ]
+    .into_iter()
+    .filter_map(|(receiver, amount)| {
+        receiver
+            .filter(|_| amount > Uint128::zero())
+            .map(|receiver| {
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: receiver.to_string(),
+                    amount: vec![Coin::new(amount.u128(), swap_fee.denom.clone())],
+                })
+            })
+    })
+    .collect();
+
     let send_msg = CosmosMsg::Bank(BankMsg::Send {
         to_address: user.clone(),
         amount: vec![return_amount.clone()],
     });
 
+    let refund = refund.filter(|refund| refund.amount > Uint128::zero());
+
+    let refund_msg = refund.clone().map(|refund| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.clone(),
+            amount: vec![refund],
+        })
+    });
+
+    // Balances after the outgoing transfers
+    let mut main_amount = get_main_amount(deps.as_ref(), &env, &config)?;
+    let mut pair_amount = get_pair_amount(deps.as_ref(), &env, &config)?;
+
+    for coin in [Some(&return_amount), refund.as_ref()]
+        .into_iter()
+        .flatten()
+    {
+        if coin.denom == config.main_denom {
+            main_amount -= coin.amount;
+        } else {
+            pair_amount -= coin.amount;
+        }
+    }
+
+    pair_amount -= protocol_fee + stakers_fee;
+
+    // The burn share stays in the reserve, buying the main token that is sent to the burner
+    let burned = compute_buy_and_burn(&config, main_amount, pair_amount, burn_fee)?;
+    main_amount -= burned;
+
+    if bounty > Uint128::zero() || creator_fee > Uint128::zero() || burned > Uint128::zero() {
+        config.accrued_bounty += bounty;
+        config.accrued_creator_fees += creator_fee;
+        config.burned += burned;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let burn_msg = if burned > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.burner_addr.to_string(),
+            amount: vec![Coin::new(burned.u128(), config.main_denom.clone())],
+        }))
+    } else {
+        None
+    };
+
+    let price = compute_price(&config, main_amount, pair_amount);
+
+    let volume = if offer.denom == config.main_denom {
+        return_amount.amount + swap_fee.amount
+    } else {
+        offer.amount
+    };
+
+    let user_addr = deps.api.addr_validate(&user)?;
+
+    if offer.denom == config.main_denom {
+        update_holder(deps.storage, &user_addr, Uint128::zero(), offer.amount)?;
+    } else {
+        assert_launch_caps(
+            deps.as_ref(),
+            &env,
+            &config,
+            &user_addr,
+            &offer,
+            &return_amount,
+        )?;
+        update_holder(
+            deps.storage,
+            &user_addr,
+            return_amount.amount,
+            Uint128::zero(),
+        )?;
+    }
+
+    update_price_observations(deps.storage, &env, price)?;
+
+    update_candles(
+        deps.storage,
+        &env,
+        config.flambe_setting.initial_price,
+        price,
+        volume,
+    )?;
+
+    record_trade(
+        deps.storage,
+        config.flambe_setting.trades_retention,
+        Trade {
+            id: 0,
+            user: user_addr,
+            side: if offer.denom == config.main_denom {
+                TradeSide::Sell
+            } else {
+                TradeSide::Buy
+            },
+            offer_amount: offer.amount,
+            return_amount: return_amount.amount,
+            fee_amount: swap_fee.amount,
+            price,
+            block_height: env.block.height,
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+
     let msg_update_liquidity = WasmMsg::build_execute(
         &config.factory,
         FactoryExecuteMsg::UpdateFlambeLiquidity,
         vec![],
     )?;
 
-    let msg_check_to_pending =
-        WasmMsg::build_execute(&env.contract.address, ExecuteMsg::CheckToPending, vec![])?;
+    let msg_check_to_pending = WasmMsg::build_execute(
+        &env.contract.address,
+        ExecuteMsg::CheckToPending {
+            keeper: user.clone(),
+        },
+        vec![],
+    )?;
 
     Ok(Response::new()
-        .add_messages(fee_msg)
+        .add_messages(fee_msgs)
+        .add_messages(burn_msg)
         .add_message(send_msg)
+        .add_messages(refund_msg)
         .add_message(msg_update_liquidity)
         .add_message(msg_check_to_pending)
-        .add_attribute("action", "swap")
         .add_attribute("input_denom", offer.denom)
         .add_attribute("input_amount", offer.amount)
         .add_attribute("return_denom", return_amount.denom)
         .add_attribute("return_amount", return_amount.amount)
         .add_attribute("fee_denom", swap_fee.denom)
         .add_attribute("fee_amount", swap_fee.amount)
+        .add_attribute("creator_fee_amount", creator_fee)
+        .add_attribute("burned_amount", burned)
         .add_attribute("user", user.to_string()))
 }
 
+/// Enforce the buy caps of the `FlambeSetting` during the launch window
+fn assert_launch_caps(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    user: &Addr,
+    offer: &Coin,
+    return_amount: &Coin,
+) -> Result<(), ContractError> {
+    let setting = &config.flambe_setting;
+
+    if env.block.time.seconds() >= config.launched_at + setting.launch_window {
+        return Ok(());
+    }
+
+    if let Some(max) = setting.max_buy_per_tx {
+        if offer.amount > max {
+            return Err(ContractError::MaxBuyPerTxExceeded { max });
+        }
+    }
+
+    if let Some(max_holding) = setting.max_holding {
+        let max = setting.initial_supply * max_holding;
+
+        let balance = holders()
+            .may_load(deps.storage, user.clone())?
+            .map(|holder| holder.balance)
+            .unwrap_or_default();
+
+        if balance + return_amount.amount > max {
+            return Err(ContractError::MaxHoldingExceeded { max });
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the net bought balance of `user`, removing the holder when it reaches zero.
+/// Sells of tokens received outside the curve saturate at zero
+fn update_holder(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    bought: Uint128,
+    sold: Uint128,
+) -> StdResult<()> {
+    let previous = holders()
+        .may_load(storage, user.clone())?
+        .map(|holder| holder.balance)
+        .unwrap_or_default();
+
+    let balance = (previous + bought).saturating_sub(sold);
+
+    if balance.is_zero() {
+        holders().remove(storage, user.clone())?;
+    } else {
+        holders().save(
+            storage,
+            user.clone(),
+            &Holder {
+                address: user.clone(),
+                balance,
+            },
+        )?;
+    }
+
+    if previous.is_zero() != balance.is_zero() {
+        let count = HOLDERS_COUNT.may_load(storage)?.unwrap_or_default();
+        let count = if balance.is_zero() {
+            count - 1
+        } else {
+            count + 1
+        };
+        HOLDERS_COUNT.save(storage, &count)?;
+    }
+
+    Ok(())
+}
+
+/// Store the trade, removing the ones out of the `trades_retention` window
+fn record_trade(storage: &mut dyn Storage, retention: u64, mut trade: Trade) -> StdResult<()> {
+    if retention == 0 {
+        return Ok(());
+    }
+
+    let id = TRADES_COUNTER.may_load(storage)?.unwrap_or_default();
+
+    trade.id = id;
+    trades().save(storage, id, &trade)?;
+    TRADES_COUNTER.save(storage, &(id + 1))?;
+
+    if let Some(first_kept) = (id + 1).checked_sub(retention) {
+        let expired = trades()
+            .keys(
+                storage,
+                None,
+                Some(Bound::exclusive(first_kept)),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<u64>>>()?;
+
+        for id in expired {
+            trades().remove(storage, id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Accumulate the price of the previous observation up to now and store the new price.
+/// The observations older than `MAX_TWAP_WINDOW` are removed, except the last one of them
+fn update_price_observations(
+    storage: &mut dyn Storage,
+    env: &Env,
+    price: Decimal,
+) -> StdResult<()> {
+    let now = env.block.time.seconds();
+
+    let cumulative_price = PRICE_OBSERVATIONS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, last)| cumulative_price_at(&last, now))
+        .unwrap_or_default();
+
+    PRICE_OBSERVATIONS.save(
+        storage,
+        now,
+        &PriceObservation {
+            timestamp: now,
+            cumulative_price,
+            price,
+        },
+    )?;
+
+    let expired = PRICE_OBSERVATIONS
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(now.saturating_sub(MAX_TWAP_WINDOW))),
+            Order::Descending,
+        )
+        .skip(1)
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    for timestamp in expired {
+        PRICE_OBSERVATIONS.remove(storage, timestamp);
+    }
+
+    Ok(())
+}
+
+/// Update the candle of each resolution with the post-trade price.
+/// A new candle opens at the last close, or at the initial price on the first trade
+fn update_candles(
+    storage: &mut dyn Storage,
+    env: &Env,
+    initial_price: Decimal,
+    price: Decimal,
+    volume: Uint128,
+) -> StdResult<()> {
+    let now = env.block.time.seconds();
+
+    for resolution in CandleResolution::ALL {
+        let key = (resolution.seconds(), resolution.bucket_start(now));
+
+        let candle = match CANDLES.may_load(storage, key)? {
+            Some(mut candle) => {
+                candle.high = max(candle.high, price);
+                candle.low = min(candle.low, price);
+                candle.close = price;
+                candle.volume += volume;
+                candle
+            }
+            None => {
+                let open = CANDLES
+                    .prefix(key.0)
+                    .range(storage, None, None, Order::Descending)
+                    .next()
+                    .transpose()?
+                    .map(|(_, candle)| candle.close)
+                    .unwrap_or(initial_price);
+
+                Candle {
+                    start: key.1,
+                    open,
+                    high: max(open, price),
+                    low: min(open, price),
+                    close: price,
+                    volume,
+                }
+            }
+        };
+
+        CANDLES.save(storage, key, &candle)?;
+    }
+
+    Ok(())
+}
+
 pub fn deploy(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
-    msg: EndFlambeMsg,
+    keeper: String,
+    swap_msg: Option<EndFlambeSwapMsg>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -100,44 +634,295 @@ pub fn deploy(
         return Err(ContractError::Unauthorized {});
     }
 
+    if config.paused {
+        return Err(ContractError::FlambePaused {});
+    }
+
     if config.status != FlambeStatus::PENDING {
         return Err(ContractError::NotPending {});
     }
 
-    config.status = FlambeStatus::CLOSED;
+    graduate(deps, env, &mut config, keeper, swap_msg)
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.paused = paused;
     CONFIG.save(deps.storage, &config)?;
 
-    let spread_factor =
-        config.flambe_setting.pool_creation_info.spread_factor * 10_u128.pow(18).into_uint128();
+    Ok(Response::new()
+        .add_attribute("action", "set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
 
-    let msg_swap_fee = if let Some(swap_msg) = msg.swap_msg {
-        Some(MsgSwapExactAmountOut {
-            sender: env.contract.address.to_string(),
-            routes: swap_msg.routes,
-            token_in_max_amount: swap_msg.token_in_max_amount.to_string(),
-            token_out: Some(swap_msg.token_out.into()),
-        })
+pub fn update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.owner = deps.api.addr_validate(&owner)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_owner")
+        .add_attribute("owner", owner))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    burner_addr: Option<String>,
+    fee_collector: Option<String>,
+    swap_fee: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.factory {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![];
+
+    if let Some(burner_addr) = burner_addr {
+        config.burner_addr = deps.api.addr_validate(&burner_addr)?;
+        attrs.push(attr("burner_addr", burner_addr));
+    }
+
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+        attrs.push(attr("fee_collector", fee_collector));
+    }
+
+    if let Some(swap_fee) = swap_fee {
+        if swap_fee > Decimal::one() {
+            return Err(ContractError::InvalidFee {});
+        }
+
+        config.swap_fee = swap_fee;
+        attrs.push(attr("swap_fee", swap_fee.to_string()));
+    }
+
+    if attrs.is_empty() {
+        return Err(ContractError::InvalidEmptyUpdate {});
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attributes(attrs))
+}
+
+pub fn fail(deps: DepsMut, info: MessageInfo, env: Env) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.status != FlambeStatus::OPEN {
+        return Err(ContractError::PumpClosed {});
+    }
+
+    if !is_expired(&env, &config) {
+        return Err(ContractError::DeadlineNotReached {});
+    }
+
+    let bounty = config.accrued_bounty;
+
+    config.status = FlambeStatus::FAILED;
+    config.accrued_bounty = Uint128::zero();
+    CONFIG.save(deps.storage, &config)?;
+
+    let msg_bounty = if bounty > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(
+                bounty.u128(),
+                config.flambe_setting.pair_denom.clone(),
+            )],
+        }))
     } else {
         None
     };
 
-    let msg_create_pool = MsgCreateConcentratedPool {
+    let msg_update_status = WasmMsg::build_execute(
+        &config.factory,
+        FactoryExecuteMsg::UpdateFlambeStatus {
+            status: FlambeStatus::FAILED,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(msg_update_status)
+        .add_messages(msg_bounty)
+        .add_attribute("action", "fail")
+        .add_attribute("keeper", info.sender)
+        .add_attribute("bounty", bounty))
+}
+
+pub fn refund(deps: DepsMut, info: MessageInfo, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.paused {
+        return Err(ContractError::FlambePaused {});
+    }
+
+    if config.status != FlambeStatus::FAILED {
+        return Err(ContractError::NotFailed {});
+    }
+
+    let offer = rhaki_cw_plus::asset::only_one_coin(&info.funds, Some(config.main_denom.clone()))?;
+
+    // The offer is already on the contract
+    let main_amount = get_main_amount(deps.as_ref(), &env, &config)? - offer.amount;
+    let pair_amount = get_pair_amount(deps.as_ref(), &env, &config)?;
+
+    // The burned tokens left the reserve without being sold
+    let sold = config
+        .flambe_setting
+        .initial_supply
+        .checked_sub(main_amount + config.burned)
+        .map_err(StdError::from)?;
+
+    let refund_amount = pair_amount.multiply_ratio(offer.amount, sold);
+
+    update_holder(deps.storage, &info.sender, Uint128::zero(), offer.amount)?;
+
+    let msg_refund = if refund_amount > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(
+                refund_amount.u128(),
+                config.flambe_setting.pair_denom.clone(),
+            )],
+        }))
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(msg_refund)
+        .add_attribute("action", "refund")
+        .add_attribute("user", info.sender)
+        .add_attribute("returned_amount", offer.amount)
+        .add_attribute("refund_amount", refund_amount))
+}
+
+pub fn collect_fees(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let position_id = CL_POSITION_ID
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPosition {})?;
+
+    let position = PositionByIdRequest { position_id }
+        .query(&deps.querier)?
+        .position
+        .ok_or(StdError::generic_err("Position not found"))?;
+
+    // Everything claimable is collected in this same tx
+    let mut collected: Vec<Coin> = vec![];
+
+    for coin in position
+        .claimable_spread_rewards
+        .iter()
+        .chain(position.claimable_incentives.iter())
+    {
+        let amount = Uint128::from_str(&coin.amount)?;
+
+        match collected.iter_mut().find(|val| val.denom == coin.denom) {
+            Some(val) => val.amount += amount,
+            None => collected.push(Coin::new(amount.u128(), &coin.denom)),
+        }
+    }
+
+    collected.retain(|coin| !coin.amount.is_zero());
+
+    if collected.is_empty() {
+        return Err(ContractError::NoFeesToCollect {});
+    }
+
+    let split = deps
+        .querier
+        .query_wasm_smart::<FactoryConfig>(&config.factory, &FactoryQueryMsg::Config {})?
+        .lp_fees_split;
+
+    let mut creator_coins = vec![];
+    let mut protocol_coins = vec![];
+    let mut burn_coins = vec![];
+
+    for coin in &collected {
+        let creator_amount = coin.amount * split.creator;
+        let burn_amount = coin.amount * split.burn;
+        let protocol_amount = coin.amount - creator_amount - burn_amount;
+
+        for (coins, amount) in [
+            (&mut creator_coins, creator_amount),
+            (&mut protocol_coins, protocol_amount),
+            (&mut burn_coins, burn_amount),
+        ] {
+            if !amount.is_zero() {
+                coins.push(Coin::new(amount.u128(), &coin.denom));
+            }
+        }
+    }
+
+    let msgs_split = [
+        (&config.creator, creator_coins),
+        (&config.fee_collector, protocol_coins),
+        (&config.burner_addr, burn_coins),
+    ]
+    .into_iter()
+    .filter(|(_, coins)| !coins.is_empty())
+    .map(|(to_address, amount)| BankMsg::Send {
+        to_address: to_address.to_string(),
+        amount,
+    });
+
+    let msg_collect_spread_rewards = MsgCollectSpreadRewards {
+        position_ids: vec![position_id],
+        sender: env.contract.address.to_string(),
+    };
+
+    let msg_collect_incentives = MsgCollectIncentives {
+        position_ids: vec![position_id],
         sender: env.contract.address.to_string(),
-        denom0: config.main_denom,
-        denom1: config.flambe_setting.pair_denom,
-        tick_spacing: config.flambe_setting.pool_creation_info.tick_spacing,
-        spread_factor: spread_factor.to_string(),
     };
 
     Ok(Response::new()
-        .add_messages(msg_swap_fee)
-        .add_submessage(SubMsg::reply_on_success(
-            msg_create_pool,
-            ReplyIds::PoolCreation.repr(),
-        )))
+        .add_message(msg_collect_spread_rewards)
+        .add_message(msg_collect_incentives)
+        .add_messages(msgs_split)
+        .add_attribute("action", "collect_fees")
+        .add_attribute(
+            "collected",
+            collected
+                .iter()
+                .map(|coin| coin.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ))
 }
 
-pub fn check_to_pending(deps: DepsMut, env: Env, sender: Addr) -> Result<Response, ContractError> {
+pub fn check_to_pending(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    keeper: String,
+) -> Result<Response, ContractError> {
     if sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
     }
@@ -145,7 +930,7 @@ pub fn check_to_pending(deps: DepsMut, env: Env, sender: Addr) -> Result<Respons
     let mut config = CONFIG.load(deps.storage)?;
 
     let pair_amout = get_pair_amount(deps.as_ref(), &env, &config)?;
-    if pair_amout >= config.flambe_setting.threshold {
+    if config.status == FlambeStatus::OPEN && pair_amout >= config.flambe_setting.threshold {
         config.status = FlambeStatus::PENDING;
         CONFIG.save(deps.storage, &config)?;
 
@@ -156,10 +941,228 @@ pub fn check_to_pending(deps: DepsMut, env: Env, sender: Addr) -> Result<Respons
             },
             vec![],
         )?;
-        Ok(Response::new()
+
+        let response = Response::new()
             .add_message(msg)
-            .add_attribute("updated_status", FlambeStatus::PENDING.to_string()))
+            .add_attribute("updated_status", FlambeStatus::PENDING.to_string());
+
+        if config.auto_graduation && pool_creation_fee_in_pair(deps.as_ref(), &config)? {
+            let graduation = graduate(deps, env, &mut config, keeper, None)?;
+
+            Ok(response
+                .add_submessages(graduation.messages)
+                .add_attributes(graduation.attributes))
+        } else {
+            Ok(response)
+        }
     } else {
         Ok(Response::new())
     }
 }
+
+/// Close the flambe, pay the keeper bounty and create the pool
+fn graduate(
+    deps: DepsMut,
+    env: Env,
+    config: &mut Config,
+    keeper: String,
+    swap_msg: Option<EndFlambeSwapMsg>,
+) -> Result<Response, ContractError> {
+    let keeper = deps.api.addr_validate(&keeper)?;
+
+    let bounty = config.accrued_bounty;
+
+    config.status = FlambeStatus::CLOSED;
+    config.accrued_bounty = Uint128::zero();
+    CONFIG.save(deps.storage, config)?;
+
+    let msg_bounty = if bounty > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: keeper.to_string(),
+            amount: vec![Coin::new(
+                bounty.u128(),
+                config.flambe_setting.pair_denom.clone(),
+            )],
+        }))
+    } else {
+        None
+    };
+
+    let msg_swap_fee = if let Some(swap_msg) = swap_msg {
+        Some(MsgSwapExactAmountOut {
+            sender: env.contract.address.to_string(),
+            routes: swap_msg.routes,
+            token_in_max_amount: swap_msg.token_in_max_amount.to_string(),
+            token_out: Some(swap_msg.token_out.into()),
+        })
+    } else {
+        None
+    };
+
+    // The pool is created after the swap, when the balances to deploy are known
+    let msg_create_pool =
+        WasmMsg::build_execute(&env.contract.address, ExecuteMsg::CreatePool {}, vec![])?;
+
+    Ok(Response::new()
+        .add_messages(msg_bounty)
+        .add_messages(msg_swap_fee)
+        .add_message(msg_create_pool)
+        .add_attribute("action", "graduate")
+        .add_attribute("keeper", keeper)
+        .add_attribute("keeper_bounty", bounty))
+}
+
+pub fn create_pool(deps: DepsMut, env: Env, sender: Addr) -> Result<Response, ContractError> {
+    if sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // Anyone can create the Astroport pair beforehand, in this case the liquidity is provided to it
+    if let GraduationTarget::AstroportXyk { factory }
+    | GraduationTarget::AstroportPcl { factory, .. } = &config.flambe_setting.graduation_target
+    {
+        let pair_info: StdResult<PairInfo> = deps.querier.query_wasm_smart(
+            factory,
+            &AstroportFactoryQueryMsg::Pair {
+                asset_infos: astroport_asset_infos(&config),
+            },
+        );
+
+        if let Ok(pair_info) = pair_info {
+            return Ok(
+                provide_astroport_liquidity(deps.as_ref(), &env, &config, pair_info)?
+                    .add_attribute("action", "create_pool"),
+            );
+        }
+    }
+
+    let (msg_create_pool, reply_id): (CosmosMsg, ReplyIds) = match &config
+        .flambe_setting
+        .graduation_target
+    {
+        GraduationTarget::OsmosisCL => {
+            let spread_factor = config.flambe_setting.pool_creation_info.spread_factor
+                * 10_u128.pow(18).into_uint128();
+
+            (
+                MsgCreateConcentratedPool {
+                    sender: env.contract.address.to_string(),
+                    denom0: config.main_denom.clone(),
+                    denom1: config.flambe_setting.pair_denom.clone(),
+                    tick_spacing: config.flambe_setting.pool_creation_info.tick_spacing,
+                    spread_factor: spread_factor.to_string(),
+                }
+                .into(),
+                ReplyIds::PoolCreation,
+            )
+        }
+        GraduationTarget::OsmosisBalancer { swap_fee } => {
+            let reserved = pool_creation_fee_amount_in_pair(deps.as_ref(), &config)?;
+
+            let (main_amount, pair_amount, _) =
+                graduation_liquidity(deps.as_ref(), &env, &config, reserved)?;
+
+            (
+                MsgCreateBalancerPool {
+                    sender: env.contract.address.to_string(),
+                    pool_params: Some(PoolParams {
+                        swap_fee: (*swap_fee * 10_u128.pow(18).into_uint128()).to_string(),
+                        exit_fee: "0".to_string(),
+                        smooth_weight_change_params: None,
+                    }),
+                    pool_assets: vec![
+                        PoolAsset {
+                            token: Some(Coin::new(main_amount.u128(), &config.main_denom).into()),
+                            weight: "1".to_string(),
+                        },
+                        PoolAsset {
+                            token: Some(
+                                Coin::new(pair_amount.u128(), &config.flambe_setting.pair_denom)
+                                    .into(),
+                            ),
+                            weight: "1".to_string(),
+                        },
+                    ],
+                    future_pool_governor: "".to_string(),
+                }
+                .into(),
+                ReplyIds::BalancerPoolCreation,
+            )
+        }
+        GraduationTarget::AstroportXyk { factory } => (
+            msg_create_astroport_pair(&config, factory, PairType::Xyk {}, None)?,
+            ReplyIds::AstroportPairCreation,
+        ),
+        GraduationTarget::AstroportPcl { factory, params } => {
+            let (_, _, price) =
+                graduation_liquidity(deps.as_ref(), &env, &config, Uint128::zero())?;
+
+            let params = ConcentratedPoolParams {
+                price_scale: price,
+                ..params.clone()
+            };
+
+            (
+                msg_create_astroport_pair(
+                    &config,
+                    factory,
+                    PairType::Custom("concentrated".to_string()),
+                    Some(params.into_binary()?),
+                )?,
+                ReplyIds::AstroportPairCreation,
+            )
+        }
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(msg_create_pool, reply_id.repr()))
+        .add_attribute("action", "create_pool"))
+}
+
+fn msg_create_astroport_pair(
+    config: &Config,
+    factory: &str,
+    pair_type: PairType,
+    init_params: Option<Binary>,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(WasmMsg::build_execute(
+        factory,
+        AstroportFactoryExecuteMsg::CreatePair {
+            pair_type,
+            asset_infos: astroport_asset_infos(config),
+            init_params,
+        },
+        vec![],
+    )?
+    .into())
+}
+
+pub fn claim_creator_fees(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = config.accrued_creator_fees;
+
+    if amount.is_zero() {
+        return Err(ContractError::NoCreatorFees {});
+    }
+
+    config.accrued_creator_fees = Uint128::zero();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: config.creator.to_string(),
+            amount: vec![Coin::new(
+                amount.u128(),
+                config.flambe_setting.pair_denom.clone(),
+            )],
+        })
+        .add_attribute("action", "claim_creator_fees")
+        .add_attribute("amount", amount))
+}
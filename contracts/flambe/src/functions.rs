@@ -1,6 +1,15 @@
+use std::{cmp::min, str::FromStr};
+
+use astroport::asset::AssetInfo;
 use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Env, StdError, StdResult, Uint128, Uint256};
-use ratatouille_pkg::flambe::definitions::{Config, SwapResponse};
-use rhaki_cw_plus::traits::IntoStdResult;
+use osmosis_std::types::{
+    cosmos::base::v1beta1::Coin as ProtoCoin, osmosis::poolmanager::v1beta1::ParamsRequest,
+};
+use ratatouille_pkg::{
+    curve::{div_ceil, to_price, BondingCurve, CurveState},
+    flambe::definitions::{Config, PriceObservation, ReverseSwapResponse, SwapResponse},
+    flambe_factory::definitions::CurveType,
+};
 
 pub fn get_main_amount(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
     Ok(deps
@@ -10,16 +19,139 @@ pub fn get_main_amount(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint
         .unwrap_or_default())
 }
 
+/// Pair balance of the contract, excluding the bounty retained for the keeper and the creator fees
 pub fn get_pair_amount(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
     Ok(deps
         .querier
         .query_balance(&env.contract.address, &config.flambe_setting.pair_denom)
-        .map(|val| val.amount)
+        .map(|val| {
+            val.amount
+                .saturating_sub(config.accrued_bounty + config.accrued_creator_fees)
+        })
+        .unwrap_or_default())
+}
+
+fn pool_creation_fee(deps: Deps) -> StdResult<Vec<ProtoCoin>> {
+    Ok(ParamsRequest {}
+        .query(&deps.querier)?
+        .params
+        .map(|val| val.pool_creation_fee)
         .unwrap_or_default())
 }
 
-pub fn get_pair_amount_with_reserve(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
-    Ok(get_pair_amount(deps, env, config)? + config.virtual_reserve)
+/// The pool creation fee can be paid without any swap
+pub fn pool_creation_fee_in_pair(deps: Deps, config: &Config) -> StdResult<bool> {
+    if !config.flambe_setting.graduation_target.is_osmosis() {
+        return Ok(true);
+    }
+
+    Ok(pool_creation_fee(deps)?
+        .iter()
+        .all(|coin| coin.denom == config.flambe_setting.pair_denom))
+}
+
+/// Part of the pool creation fee paid in the pair denom
+pub fn pool_creation_fee_amount_in_pair(deps: Deps, config: &Config) -> StdResult<Uint128> {
+    pool_creation_fee(deps)?
+        .into_iter()
+        .filter(|coin| coin.denom == config.flambe_setting.pair_denom)
+        .try_fold(Uint128::zero(), |acc, coin| {
+            Ok(acc + Uint128::from_str(&coin.amount)?)
+        })
+}
+
+/// Main and pair amounts deployed as liquidity at graduation, with the graduation price.
+/// `reserved` is the part of the pair balance not deployed
+pub fn graduation_liquidity(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    reserved: Uint128,
+) -> StdResult<(Uint128, Uint128, Decimal)> {
+    let pair_amount = get_pair_amount(deps, env, config)?.checked_sub(reserved)?;
+
+    let main_balance = get_main_amount(deps, env, config)?;
+
+    let price = compute_price(config, main_balance, pair_amount);
+
+    if price.is_zero() {
+        return Err(StdError::generic_err("Invalid graduation price"));
+    }
+
+    let main_amount = min(pair_amount * (Decimal::one() / price), main_balance);
+
+    Ok((main_amount, pair_amount, price))
+}
+
+/// Astroport assets of the flambe, main denom first
+pub fn astroport_asset_infos(config: &Config) -> Vec<AssetInfo> {
+    vec![
+        AssetInfo::NativeToken {
+            denom: config.main_denom.clone(),
+        },
+        AssetInfo::NativeToken {
+            denom: config.flambe_setting.pair_denom.clone(),
+        },
+    ]
+}
+
+/// Cumulative price of `observation` accumulated up to `time`
+pub fn cumulative_price_at(observation: &PriceObservation, time: u64) -> Decimal256 {
+    observation.cumulative_price
+        + Decimal256::from(observation.price)
+            * Decimal256::from_ratio(time - observation.timestamp, 1u8)
+}
+
+/// Curve state from the current balances of the flambe
+pub fn get_curve_state(deps: Deps, env: &Env, config: &Config) -> StdResult<CurveState> {
+    Ok(CurveState::new(
+        config,
+        get_main_amount(deps, env, config)?,
+        get_pair_amount(deps, env, config)?,
+    ))
+}
+
+/// Marginal price of the curve, zero if it can't be computed
+pub fn compute_price(config: &Config, main_amount: Uint128, pair_amount: Uint128) -> Decimal {
+    config
+        .flambe_setting
+        .curve
+        .price(&CurveState::new(config, main_amount, pair_amount))
+        .and_then(to_price)
+        .unwrap_or_default()
+}
+
+/// Swap fee following the fee schedule of the flambe setting, if any
+pub fn current_swap_fee(env: &Env, config: &Config) -> Decimal {
+    match &config.flambe_setting.fee_schedule {
+        Some(schedule) => schedule.fee_at(
+            config.swap_fee,
+            env.block.time.seconds().saturating_sub(config.launched_at),
+        ),
+        None => config.swap_fee,
+    }
+}
+
+/// Main amount bought along the curve with `burn_amount`, already included in `pair_amount`
+pub fn compute_buy_and_burn(
+    config: &Config,
+    main_amount: Uint128,
+    pair_amount: Uint128,
+    burn_amount: Uint128,
+) -> StdResult<Uint128> {
+    if burn_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let state = CurveState::new(config, main_amount, pair_amount.checked_sub(burn_amount)?);
+
+    let burned: Uint128 = config
+        .flambe_setting
+        .curve
+        .buy_out(&state, burn_amount.into())?
+        .try_into()?;
+
+    Ok(min(burned, main_amount))
 }
 
 pub fn compute_swap(
@@ -29,83 +161,152 @@ pub fn compute_swap(
     offer: Coin,
     is_simulation: bool,
 ) -> StdResult<SwapResponse> {
-    let balance_main = get_main_amount(deps, env, config)?;
-    let balance_pair = get_pair_amount_with_reserve(deps, env, config)?;
-
-    let (ask_qta, mut offer_qta, ask_denom, is_buy): (Uint256, Uint256, String, bool) =
-        if offer.denom == config.main_denom {
-            (
-                balance_pair.into(),
-                balance_main.into(),
-                config.flambe_setting.pair_denom.clone(),
-                false,
-            )
-        } else if offer.denom == config.flambe_setting.pair_denom {
-            (
-                balance_main.into(),
-                balance_pair.into(),
-                config.main_denom.clone(),
-                true,
-            )
-        } else {
-            return Err(StdError::generic_err(format!(
-                "Invalid denom: {}",
-                offer.denom
-            )));
-        };
+    let curve = &config.flambe_setting.curve;
+    let swap_fee_rate = current_swap_fee(env, config);
+    let mut state = get_curve_state(deps, env, config)?;
 
-    // Deduct from offer_qta the offer.amount because tokens are alredy on the contract
+    let (ask_denom, is_buy) = if offer.denom == config.main_denom {
+        (config.flambe_setting.pair_denom.clone(), false)
+    } else if offer.denom == config.flambe_setting.pair_denom {
+        (config.main_denom.clone(), true)
+    } else {
+        return Err(StdError::generic_err(format!(
+            "Invalid denom: {}",
+            offer.denom
+        )));
+    };
+
+    // Deduct the offer.amount from the state because tokens are alredy on the contract
     if !is_simulation {
-        offer_qta -= Into::<Uint256>::into(offer.amount);
+        if is_buy {
+            state.pair_amount = state.pair_amount.checked_sub(offer.amount.into())?;
+        } else {
+            state.main_amount = state.main_amount.checked_sub(offer.amount.into())?;
+        }
     }
 
-    let mut swap_fee: Uint128 = Uint128::zero();
+    let (return_amount, swap_fee, state_after) = if is_buy {
+        let swap_fee = offer.amount * swap_fee_rate;
+        let offer_amount: Uint256 = (offer.amount - swap_fee).into();
+        let return_amount = curve.buy_out(&state, offer_amount)?;
 
-    let offer_amount: Uint256 = if is_buy {
-        swap_fee = offer.amount * config.swap_fee;
-        (offer.amount - swap_fee).into()
+        (
+            return_amount,
+            swap_fee,
+            state.after_buy(offer_amount, return_amount)?,
+        )
     } else {
-        offer.amount.into()
+        let return_amount: Uint128 = curve.sell_out(&state, offer.amount.into())?.try_into()?;
+        let swap_fee = return_amount * swap_fee_rate;
+        let return_amount: Uint256 = (return_amount - swap_fee).into();
+
+        (
+            return_amount,
+            swap_fee,
+            state.after_sell(offer.amount.into(), return_amount)?,
+        )
     };
 
-    let mut return_amount: Uint256 = (Decimal256::from_ratio(ask_qta, 1u8)
-        - Decimal256::from_ratio(offer_qta * ask_qta, offer_qta + offer_amount))
-        * Uint256::from(1u8);
+    let price_impact = compute_price_impact(curve, &state, &state_after)?;
+
+    let return_amount: Uint128 = return_amount.try_into()?;
+
+    Ok(SwapResponse {
+        return_amount: Coin::new(return_amount.u128(), ask_denom),
+        swap_fee: Coin::new(swap_fee.u128(), config.flambe_setting.pair_denom.clone()),
+        price_impact,
+    })
+}
+
+/// Inverse of `compute_swap`: the offer needed to receive exactly `ask.amount`.
+///
+/// `deposited` is the amount of the offer denom already sent to the contract.
+/// The rounding is always in favour of the curve.
+pub fn compute_swap_exact_out(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    ask: Coin,
+    deposited: Uint128,
+) -> StdResult<ReverseSwapResponse> {
+    let curve = &config.flambe_setting.curve;
+    let mut state = get_curve_state(deps, env, config)?;
 
-    if !is_buy {
-        swap_fee = TryInto::<Uint128>::try_into(return_amount)? * config.swap_fee;
-        return_amount -= Into::<Uint256>::into(swap_fee);
+    let (offer_denom, is_buy) = if ask.denom == config.main_denom {
+        state.pair_amount = state.pair_amount.checked_sub(deposited.into())?;
+        (config.flambe_setting.pair_denom.clone(), true)
+    } else if ask.denom == config.flambe_setting.pair_denom {
+        state.main_amount = state.main_amount.checked_sub(deposited.into())?;
+        (config.main_denom.clone(), false)
+    } else {
+        return Err(StdError::generic_err(format!(
+            "Invalid denom: {}",
+            ask.denom
+        )));
     };
 
-    let price_impact = if is_buy {
-        let price_pre: Decimal = Decimal256::from_ratio(offer_qta, ask_qta)
-            .try_into()
-            .into_std_result()?;
+    let swap_fee_rate = current_swap_fee(env, config);
 
-        let price_post: Decimal =
-            Decimal256::from_ratio(offer_qta + offer_amount, ask_qta - return_amount)
-                .try_into()
-                .into_std_result()?;
+    if swap_fee_rate >= Decimal::one() {
+        return Err(StdError::generic_err("Swap fee must be lower then 1"));
+    }
 
-        price_post / price_pre
+    let fee_complement = Uint256::from((Decimal::one() - swap_fee_rate).atomics());
+    let fee_precision = Uint256::from(Decimal::one().atomics());
+
+    // On a sell the fee is taken from the output
+    let gross_out: Uint256 = if is_buy {
+        ask.amount.into()
     } else {
-        let price_pre: Decimal = Decimal256::from_ratio(ask_qta, offer_qta)
-            .try_into()
-            .into_std_result()?;
+        div_ceil(Uint256::from(ask.amount) * fee_precision, fee_complement)?
+    };
+
+    if gross_out.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "Invalid amount out: {}",
+            ask.amount
+        )));
+    }
 
-        let price_post: Decimal =
-            Decimal256::from_ratio(ask_qta - return_amount, offer_qta + offer_amount)
-                .try_into()
-                .into_std_result()?;
+    let (offer_amount, state_after) = if is_buy {
+        let offer_amount = curve.buy_in(&state, gross_out)?;
+        (offer_amount, state.after_buy(offer_amount, gross_out)?)
+    } else {
+        let offer_amount = curve.sell_in(&state, gross_out)?;
+        (offer_amount, state.after_sell(offer_amount, gross_out)?)
+    };
 
-        price_post / price_pre
+    // On a buy the fee is taken from the input
+    let (offer_amount_with_fee, swap_fee): (Uint128, Uint128) = if is_buy {
+        let offer_amount_with_fee = div_ceil(offer_amount * fee_precision, fee_complement)?;
+        (
+            offer_amount_with_fee.try_into()?,
+            (offer_amount_with_fee - offer_amount).try_into()?,
+        )
+    } else {
+        (
+            offer_amount.try_into()?,
+            (gross_out - Uint256::from(ask.amount)).try_into()?,
+        )
     };
 
-    let return_amount: Uint128 = return_amount.try_into()?;
+    let price_impact = compute_price_impact(curve, &state, &state_after)?;
 
-    Ok(SwapResponse {
-        return_amount: Coin::new(return_amount.u128(), ask_denom),
+    Ok(ReverseSwapResponse {
+        offer_amount: Coin::new(offer_amount_with_fee.u128(), offer_denom),
         swap_fee: Coin::new(swap_fee.u128(), config.flambe_setting.pair_denom.clone()),
         price_impact,
     })
 }
+
+/// Ratio between the price after and before the swap
+fn compute_price_impact(
+    curve: &CurveType,
+    state: &CurveState,
+    state_after: &CurveState,
+) -> StdResult<Decimal> {
+    let price_pre = to_price(curve.price(state)?)?;
+    let price_post = to_price(curve.price(state_after)?)?;
+
+    Ok(price_post / price_pre)
+}
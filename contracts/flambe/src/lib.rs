@@ -1,7 +1,8 @@
 pub mod contract;
 mod error;
 mod execute;
-mod state;
 mod functions;
+mod migration;
 mod query;
-mod reply;
\ No newline at end of file
+mod reply;
+mod state;
@@ -0,0 +1,33 @@
+use cosmwasm_std::Storage;
+use cw2::{set_contract_version, CONTRACT};
+use semver::Version;
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    error::ContractError,
+};
+
+/// Check the stored cw2 version against the new one and update it.
+///
+/// Flambes instantiated before the cw2 versioning have no version stored and are always migrated.
+pub fn assert_and_set_version(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if let Some(stored) = CONTRACT.may_load(storage)? {
+        if stored.contract != CONTRACT_NAME {
+            return Err(ContractError::InvalidContractName {
+                expected: CONTRACT_NAME.to_string(),
+                found: stored.contract,
+            });
+        }
+
+        if stored.version.parse::<Version>()? > CONTRACT_VERSION.parse::<Version>()? {
+            return Err(ContractError::MigrationDowngrade {
+                stored: stored.version,
+                new: CONTRACT_VERSION.to_string(),
+            });
+        }
+    }
+
+    set_contract_version(storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(())
+}
@@ -1,9 +1,26 @@
-use cosmwasm_std::{Coin, Decimal, Deps, Env, StdResult, Uint128};
-use ratatouille_pkg::flambe::definitions::{Config, FlambeInfo, SwapResponse};
+use std::cmp::min;
+
+use cosmwasm_std::{Coin, Decimal256, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::Bound;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::PositionByIdRequest;
+use ratatouille_pkg::flambe::definitions::{
+    Candle, CandleResolution, Config, FlambeInfo, Holder, PositionLockResponse, PriceObservation,
+    ReverseSwapResponse, SwapResponse, Trade, TwapResponse, MAX_TWAP_WINDOW,
+};
+use rhaki_cw_plus::{
+    storage::multi_index::{get_items, get_multi_index_values, multi_map_value},
+    traits::{IntoAddr, IntoStdResult},
+};
 
 use crate::{
-    functions::{compute_swap, get_main_amount, get_pair_amount},
-    state::CONFIG,
+    functions::{
+        compute_price, compute_swap, compute_swap_exact_out, cumulative_price_at, get_main_amount,
+        get_pair_amount,
+    },
+    state::{
+        holders, trades, CANDLES, CL_POOL_ID, CL_POSITION_ID, CONFIG, HOLDERS_COUNT,
+        PRICE_OBSERVATIONS,
+    },
 };
 
 pub fn qy_config(deps: Deps) -> StdResult<Config> {
@@ -15,6 +32,7 @@ pub fn qy_info(deps: Deps, env: Env) -> StdResult<FlambeInfo> {
     let config = CONFIG.load(deps.storage)?;
     let main_amount = get_main_amount(deps, &env, &config)?;
     let pair_amount = get_pair_amount(deps, &env, &config)?;
+    let price = compute_price(&config, main_amount, pair_amount);
 
     Ok(FlambeInfo {
         virtual_reserve: config.virtual_reserve,
@@ -22,8 +40,8 @@ pub fn qy_info(deps: Deps, env: Env) -> StdResult<FlambeInfo> {
         main_denom: config.main_denom,
         pair_amount,
         pair_denom: config.flambe_setting.pair_denom,
-        price: Decimal::checked_from_ratio(pair_amount + config.virtual_reserve, main_amount)
-            .unwrap_or_default(),
+        price,
+        holders: HOLDERS_COUNT.may_load(deps.storage)?.unwrap_or_default(),
     })
 }
 
@@ -37,3 +55,212 @@ pub fn qy_simulate(
     let config = CONFIG.load(deps.storage)?;
     compute_swap(deps, &env, &config, coin, true)
 }
+
+pub fn qy_simulate_reverse(
+    deps: Deps,
+    env: Env,
+    ask: String,
+    amount: Uint128,
+) -> StdResult<ReverseSwapResponse> {
+    let coin = Coin::new(amount.u128(), ask);
+    let config = CONFIG.load(deps.storage)?;
+    compute_swap_exact_out(deps, &env, &config, coin, Uint128::zero())
+}
+
+pub fn qy_trades(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Trade>> {
+    get_items(
+        deps.storage,
+        trades(),
+        Order::Descending,
+        limit,
+        start_after,
+        multi_map_value,
+    )
+}
+
+pub fn qy_trades_by_user(
+    deps: Deps,
+    user: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Trade>> {
+    get_multi_index_values(
+        deps.storage,
+        user.into_addr(deps.api)?,
+        trades().idx.user,
+        Order::Descending,
+        start_after,
+        limit,
+        multi_map_value,
+    )
+}
+
+pub fn qy_holders(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<(Uint128, String)>,
+) -> StdResult<Vec<Holder>> {
+    let max_bound = start_after
+        .map(|(balance, address)| -> StdResult<_> {
+            Ok(Bound::exclusive((
+                balance.u128(),
+                address.into_addr(deps.api)?,
+            )))
+        })
+        .transpose()?;
+
+    holders()
+        .idx
+        .balance
+        .range(deps.storage, None, max_bound, Order::Descending)
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect()
+}
+
+pub fn qy_candles(
+    deps: Deps,
+    env: Env,
+    resolution: CandleResolution,
+    from: u64,
+    to: u64,
+    limit: Option<u32>,
+) -> StdResult<Vec<Candle>> {
+    let seconds = resolution.seconds();
+    let from = resolution.bucket_start(from);
+    let to = resolution.bucket_start(min(to, env.block.time.seconds()));
+    let limit = min(MAX_CANDLES_LIMIT, limit.unwrap_or(DEFAULT_CANDLES_LIMIT)) as usize;
+
+    // Close of the last candle before `from`, used to fill the first gaps
+    let mut last_close = CANDLES
+        .prefix(seconds)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::exclusive(from)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, candle)| candle.close);
+
+    let stored = CANDLES
+        .prefix(seconds)
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(from)),
+            Some(Bound::inclusive(to)),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, candle)| candle))
+        .collect::<StdResult<Vec<Candle>>>()?;
+
+    let mut stored = stored.into_iter().peekable();
+
+    // Without previous trades the first candle is the first stored one
+    let mut start = match (last_close, stored.peek()) {
+        (Some(_), _) => from,
+        (None, Some(first)) => first.start,
+        (None, None) => return Ok(vec![]),
+    };
+
+    let mut candles = vec![];
+
+    while start <= to && candles.len() < limit {
+        let Some(candle) = stored.next_if(|candle| candle.start == start).or_else(|| {
+            last_close.map(|close| Candle {
+                start,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: Uint128::zero(),
+            })
+        }) else {
+            break;
+        };
+
+        last_close = Some(candle.close);
+        candles.push(candle);
+
+        start += seconds;
+    }
+
+    Ok(candles)
+}
+
+pub fn qy_twap(deps: Deps, env: Env, window_seconds: u64) -> StdResult<TwapResponse> {
+    if window_seconds == 0 || window_seconds > MAX_TWAP_WINDOW {
+        return Err(StdError::generic_err(format!(
+            "Invalid twap window: {window_seconds}, max {MAX_TWAP_WINDOW}"
+        )));
+    }
+
+    let end = env.block.time.seconds();
+    let start = end.saturating_sub(window_seconds);
+
+    let last_observation = |max: u64| -> StdResult<Option<PriceObservation>> {
+        PRICE_OBSERVATIONS
+            .range(
+                deps.storage,
+                None,
+                Some(Bound::inclusive(max)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()
+            .map(|val| val.map(|(_, observation)| observation))
+    };
+
+    let end_observation =
+        last_observation(end)?.ok_or_else(|| StdError::generic_err("No price observations"))?;
+
+    let start_observation = last_observation(start)?
+        .ok_or_else(|| StdError::generic_err("Twap window exceeds the price history"))?;
+
+    let price = (cumulative_price_at(&end_observation, end)
+        - cumulative_price_at(&start_observation, start))
+        / Decimal256::from_ratio(window_seconds, 1u8);
+
+    Ok(TwapResponse {
+        price: price.try_into().into_std_result()?,
+        start,
+        end,
+    })
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+const DEFAULT_CANDLES_LIMIT: u32 = 100;
+const MAX_CANDLES_LIMIT: u32 = 1_000;
+
+pub fn qy_position_lock(deps: Deps, env: Env) -> StdResult<PositionLockResponse> {
+    let pool_id = CL_POOL_ID.load(deps.storage)?;
+    let position_id = CL_POSITION_ID.load(deps.storage)?;
+
+    let owner = PositionByIdRequest { position_id }
+        .query(&deps.querier)?
+        .position
+        .and_then(|val| val.position)
+        .ok_or(StdError::generic_err("Position not found"))?
+        .address;
+
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(&env.contract.address)?
+        .admin;
+
+    Ok(PositionLockResponse {
+        pool_id,
+        position_id,
+        locked: owner == env.contract.address.as_str() && admin.is_none(),
+        owner,
+        admin,
+    })
+}
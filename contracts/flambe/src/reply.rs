@@ -1,17 +1,31 @@
+use std::cmp::min;
+
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::QueryMsg as AstroportFactoryQueryMsg,
+    pair::{
+        ExecuteMsg as AstroportPairExecuteMsg, PoolResponse, QueryMsg as AstroportPairQueryMsg,
+        SimulationResponse,
+    },
+};
 use cosmwasm_std::{
-    BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Reply, Response, StdError, SubMsgResult,
-    Uint128, WasmMsg,
+    Addr, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env, Isqrt, Reply,
+    Response, StdError, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
 use osmosis_std::types::{
     cosmos::base::v1beta1::Coin as ProtoCoin,
-    osmosis::concentratedliquidity::{
-        poolmodel::concentrated::v1beta1::MsgCreateConcentratedPoolResponse,
-        v1beta1::MsgCreatePosition,
+    osmosis::{
+        concentratedliquidity::{
+            poolmodel::concentrated::v1beta1::MsgCreateConcentratedPoolResponse,
+            v1beta1::{MsgCreatePosition, MsgCreatePositionResponse},
+        },
+        gamm::poolmodels::balancer::v1beta1::MsgCreateBalancerPoolResponse,
     },
 };
 use prost::Message;
 use ratatouille_pkg::{
-    flambe::definitions::FlambeStatus, flambe_factory::msgs::ExecuteMsg as FactoryExecuteMsg,
+    flambe::definitions::{Config, FlambeStatus},
+    flambe_factory::{definitions::GraduationTarget, msgs::ExecuteMsg as FactoryExecuteMsg},
 };
 use rhaki_cw_plus::{
     traits::Wrapper,
@@ -20,10 +34,13 @@ use rhaki_cw_plus::{
 
 use crate::{
     error::ContractError,
-    functions::{get_main_amount, get_pair_amount},
-    state::{ReplyIds, CONFIG},
+    functions::{astroport_asset_infos, get_main_amount, graduation_liquidity},
+    state::{ReplyIds, CL_POOL_ID, CL_POSITION_ID, CONFIG},
 };
 
+/// Max deviation allowed between the graduation price and the price of an Astroport pair
+const GRADUATION_SLIPPAGE_TOLERANCE: Decimal = Decimal::percent(1);
+
 pub fn reply_pool_creation(
     deps: DepsMut,
     env: Env,
@@ -49,12 +66,10 @@ pub fn reply_pool_creation(
     })?
     .pool_id;
 
-    let paired_balance = get_pair_amount(deps.as_ref(), &env, &config)?;
+    CL_POOL_ID.save(deps.storage, &pool_id)?;
 
-    let main_balance = get_main_amount(deps.as_ref(), &env, &config)?;
-
-    let price = Decimal::from_ratio(paired_balance + config.virtual_reserve, main_balance);
-    let deploy_balance = paired_balance * (Decimal::one() / price);
+    let (deploy_balance, paired_balance, _) =
+        graduation_liquidity(deps.as_ref(), &env, &config, Uint128::zero())?;
 
     let create_position_msg: CosmosMsg = MsgCreatePosition {
         pool_id,
@@ -76,13 +91,7 @@ pub fn reply_pool_creation(
     }
     .into();
 
-    let msg_update_status = WasmMsg::build_execute(
-        &config.factory,
-        FactoryExecuteMsg::UpdateFlambeStatus {
-            status: FlambeStatus::CLOSED,
-        },
-        vec![],
-    )?;
+    let msg_update_status = msg_update_status_closed(&config)?;
 
     Ok(Response::new()
         .add_submessage(
@@ -96,7 +105,7 @@ pub fn reply_position_creation(
     env: Env,
     reply: Reply,
 ) -> Result<Response, ContractError> {
-    let _data = if let SubMsgResult::Ok(result) = reply.result {
+    let data = if let SubMsgResult::Ok(result) = reply.result {
         result.data
     } else {
         return Err(StdError::generic_err("Unexpected error on reply").into());
@@ -104,27 +113,22 @@ pub fn reply_position_creation(
 
     let config = CONFIG.load(deps.storage)?;
 
-    // --- Migrate position to burn address ---
-    // This is not working because is not possible to transfer a position if it's the only one
-    // This will left commented for now
-
-    // let position_id = MsgCreatePositionResponse::decode(
-    //     data.ok_or(StdError::generic_err("Unexpected empty reply data"))?
-    //         .as_slice(),
-    // )
-    // .map_err(|err| {
-    //     StdError::generic_err(format!(
-    //         "reply data in not MsgCreateConcentratedPoolResponse: {}",
-    //         err
-    //     ))
-    // })?
-    // .position_id;
-
-    // let msg_migrate_position = MsgTransferPositions {
-    //     position_ids: vec![position_id],
-    //     sender: env.contract.address.to_string(),
-    //     new_owner: config.burner_addr.to_string(),
-    // };
+    // The position can't be transferred to the burner, as it's the only one of the pool.
+    // It stays locked in the flambe: there is no withdraw path and the factory
+    // clears the flambe admin once it's closed
+    let position_id = MsgCreatePositionResponse::decode(
+        data.ok_or(StdError::generic_err("Unexpected empty reply data"))?
+            .as_slice(),
+    )
+    .map_err(|err| {
+        StdError::generic_err(format!(
+            "reply data in not MsgCreatePositionResponse: {}",
+            err
+        ))
+    })?
+    .position_id;
+
+    CL_POSITION_ID.save(deps.storage, &position_id)?;
 
     // --- Brun remaining tokens ---
     let burn_amount = get_main_amount(deps.as_ref(), &env, &config)?;
@@ -140,6 +144,289 @@ pub fn reply_position_creation(
     };
 
     Ok(Response::new()
-        // .add_message(msg_migrate_position)
-        .add_messages(burn_msg))
+        .add_messages(burn_msg)
+        .add_attribute("position_id", position_id.to_string()))
+}
+
+pub fn reply_balancer_pool_creation(
+    deps: DepsMut,
+    env: Env,
+    reply: Reply,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let data = if let SubMsgResult::Ok(result) = reply.result {
+        result.data
+    } else {
+        return Err(StdError::generic_err("Unexpected error on reply").into());
+    };
+
+    let pool_id = MsgCreateBalancerPoolResponse::decode(
+        data.ok_or(StdError::generic_err("Unexpected empty reply data"))?
+            .as_slice(),
+    )
+    .map_err(|err| {
+        StdError::generic_err(format!(
+            "reply data in not MsgCreateBalancerPoolResponse: {}",
+            err
+        ))
+    })?
+    .pool_id;
+
+    // --- Burn the LP shares and the remaining tokens ---
+    let burn_coins: Vec<Coin> = vec![
+        deps.querier
+            .query_balance(&env.contract.address, format!("gamm/pool/{pool_id}"))?,
+        deps.querier
+            .query_balance(&env.contract.address, &config.main_denom)?,
+    ]
+    .into_iter()
+    .filter(|coin| !coin.amount.is_zero())
+    .collect();
+
+    let burn_msg = if !burn_coins.is_empty() {
+        BankMsg::Send {
+            to_address: config.burner_addr.to_string(),
+            amount: burn_coins,
+        }
+        .wrap_some()
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(burn_msg)
+        .add_message(msg_update_status_closed(&config)?)
+        .add_attribute("pool_id", pool_id.to_string()))
+}
+
+pub fn reply_astroport_pair_creation(
+    deps: DepsMut,
+    env: Env,
+    _reply: Reply,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let factory = match &config.flambe_setting.graduation_target {
+        GraduationTarget::AstroportXyk { factory }
+        | GraduationTarget::AstroportPcl { factory, .. } => factory,
+        _ => return Err(StdError::generic_err("Graduation target is not Astroport").into()),
+    };
+
+    let pair_info: PairInfo = deps.querier.query_wasm_smart(
+        factory,
+        &AstroportFactoryQueryMsg::Pair {
+            asset_infos: astroport_asset_infos(&config),
+        },
+    )?;
+
+    provide_astroport_liquidity(deps.as_ref(), &env, &config, pair_info)
+}
+
+/// Provide the graduation liquidity into an Astroport pair, burning the remaining main tokens.
+/// The pair may have been created and seeded by someone else beforehand, in this case it's first
+/// swapped to the graduation price, so the deposit passes the slippage tolerance.
+pub fn provide_astroport_liquidity(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    pair_info: PairInfo,
+) -> Result<Response, ContractError> {
+    let (_, pair_balance, price) = graduation_liquidity(deps, env, config, Uint128::zero())?;
+    let main_balance = get_main_amount(deps, env, config)?;
+
+    let (swap_msg, main_balance, pair_balance, price) = msg_rebalance_astroport_pair(
+        deps,
+        config,
+        &pair_info.contract_addr,
+        price,
+        main_balance,
+        pair_balance,
+    )?;
+
+    let main_amount = min(pair_balance * (Decimal::one() / price), main_balance);
+
+    let mut funds = vec![
+        Coin::new(main_amount.u128(), &config.main_denom),
+        Coin::new(pair_balance.u128(), &config.flambe_setting.pair_denom),
+    ];
+
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    // The LP tokens are minted directly to the burner
+    let provide_msg = WasmMsg::build_execute(
+        &pair_info.contract_addr,
+        AstroportPairExecuteMsg::ProvideLiquidity {
+            assets: funds
+                .iter()
+                .map(|coin| Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: coin.denom.clone(),
+                    },
+                    amount: coin.amount,
+                })
+                .collect(),
+            slippage_tolerance: Some(GRADUATION_SLIPPAGE_TOLERANCE),
+            auto_stake: None,
+            receiver: Some(config.burner_addr.to_string()),
+        },
+        funds,
+    )?;
+
+    // --- Brun remaining tokens ---
+    let burn_amount = main_balance - main_amount;
+
+    let burn_msg = if burn_amount > Uint128::zero() {
+        BankMsg::Send {
+            to_address: config.burner_addr.to_string(),
+            amount: vec![Coin::new(burn_amount.u128(), &config.main_denom)],
+        }
+        .wrap_some()
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(swap_msg)
+        .add_message(provide_msg)
+        .add_messages(burn_msg)
+        .add_message(msg_update_status_closed(config)?)
+        .add_attribute("pair", pair_info.contract_addr))
+}
+
+/// Swap bringing an Astroport pair with liquidity to `price`, approximating it with a constant product.
+/// Returns the main and pair balances of the flambe and the price of the pair after the swap
+fn msg_rebalance_astroport_pair(
+    deps: Deps,
+    config: &Config,
+    pair: &Addr,
+    price: Decimal,
+    main_balance: Uint128,
+    pair_balance: Uint128,
+) -> Result<(Option<CosmosMsg>, Uint128, Uint128, Decimal), ContractError> {
+    let pool: PoolResponse = deps
+        .querier
+        .query_wasm_smart(pair, &AstroportPairQueryMsg::Pool {})?;
+
+    if pool.total_share.is_zero() {
+        return Ok((None, main_balance, pair_balance, price));
+    }
+
+    let reserve = |denom: &str| {
+        pool.assets
+            .iter()
+            .find(|asset| asset.info.to_string() == denom)
+            .map(|asset| asset.amount)
+            .unwrap_or_default()
+    };
+
+    let main_reserve = reserve(&config.main_denom);
+    let pair_reserve = reserve(&config.flambe_setting.pair_denom);
+
+    let k = Uint256::from(main_reserve) * Uint256::from(pair_reserve);
+    let target_price = Decimal256::from(price);
+
+    // Reserves of the pair at the graduation price, keeping the same product
+    let target_pair_reserve =
+        Uint128::try_from(k.mul_floor(target_price).isqrt()).map_err(StdError::from)?;
+    let target_main_reserve = Uint128::try_from(
+        k.multiply_ratio(Decimal256::one().atomics(), target_price.atomics())
+            .isqrt(),
+    )
+    .map_err(StdError::from)?;
+
+    let (offer, available) = if target_pair_reserve > pair_reserve {
+        (
+            Coin::new(
+                (target_pair_reserve - pair_reserve).u128(),
+                &config.flambe_setting.pair_denom,
+            ),
+            pair_balance,
+        )
+    } else if target_main_reserve > main_reserve {
+        (
+            Coin::new(
+                (target_main_reserve - main_reserve).u128(),
+                &config.main_denom,
+            ),
+            main_balance,
+        )
+    } else {
+        return Ok((None, main_balance, pair_balance, price));
+    };
+
+    if offer.amount > available {
+        return Err(StdError::generic_err(
+            "Astroport pair can't be brought to the graduation price",
+        )
+        .into());
+    }
+
+    let offer_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: offer.denom.clone(),
+        },
+        amount: offer.amount,
+    };
+
+    let simulation: SimulationResponse = deps.querier.query_wasm_smart(
+        pair,
+        &AstroportPairQueryMsg::Simulation {
+            offer_asset: offer_asset.clone(),
+            ask_asset_info: None,
+        },
+    )?;
+
+    if simulation.return_amount.is_zero() {
+        return Ok((None, main_balance, pair_balance, price));
+    }
+
+    // The swap is executed in the same tx, at the simulated price
+    let swap_msg = WasmMsg::build_execute(
+        pair,
+        AstroportPairExecuteMsg::Swap {
+            offer_asset,
+            ask_asset_info: None,
+            belief_price: Some(Decimal::from_ratio(offer.amount, simulation.return_amount)),
+            max_spread: Some(GRADUATION_SLIPPAGE_TOLERANCE),
+            to: None,
+        },
+        vec![offer.clone()],
+    )?;
+
+    // The commission stays in the pair, the liquidity is provided at its reserves ratio
+    let (main_balance, pair_balance, main_reserve, pair_reserve) =
+        if offer.denom == config.main_denom {
+            (
+                main_balance - offer.amount,
+                pair_balance + simulation.return_amount,
+                main_reserve + offer.amount,
+                pair_reserve - simulation.return_amount,
+            )
+        } else {
+            (
+                main_balance + simulation.return_amount,
+                pair_balance - offer.amount,
+                main_reserve - simulation.return_amount,
+                pair_reserve + offer.amount,
+            )
+        };
+
+    Ok((
+        Some(swap_msg.into()),
+        main_balance,
+        pair_balance,
+        Decimal::from_ratio(pair_reserve, main_reserve),
+    ))
+}
+
+fn msg_update_status_closed(config: &Config) -> Result<CosmosMsg, ContractError> {
+    Ok(WasmMsg::build_execute(
+        &config.factory,
+        FactoryExecuteMsg::UpdateFlambeStatus {
+            status: FlambeStatus::CLOSED,
+        },
+        vec![],
+    )?
+    .into())
 }
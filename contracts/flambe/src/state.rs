@@ -1,11 +1,63 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{index_list, IndexedMap, Item, Map, MultiIndex};
 use enum_repr::EnumRepr;
-use ratatouille_pkg::flambe::definitions::Config;
+use ratatouille_pkg::flambe::definitions::{Candle, Config, Holder, PriceObservation, Trade};
 
 pub const CONFIG: Item<Config> = Item::new("config_key");
 
+/// Id of the next trade
+pub const TRADES_COUNTER: Item<u64> = Item::new("trades_counter");
+
+/// Keyed by (resolution seconds, bucket start)
+pub const CANDLES: Map<(u64, u64), Candle> = Map::new("candles");
+
+/// Keyed by timestamp
+pub const PRICE_OBSERVATIONS: Map<u64, PriceObservation> = Map::new("price_observations");
+
+/// Number of entries in `holders`
+pub const HOLDERS_COUNT: Item<u64> = Item::new("holders_count");
+
+/// Concentrated pool created at graduation
+pub const CL_POOL_ID: Item<u64> = Item::new("cl_pool_id");
+
+/// Concentrated position created at graduation, locked in the flambe
+pub const CL_POSITION_ID: Item<u64> = Item::new("cl_position_id");
+
 #[EnumRepr(type = "u64")]
+#[allow(clippy::enum_variant_names)]
 pub enum ReplyIds {
     PoolCreation = 1,
     PositionCreation = 2,
+    BalancerPoolCreation = 3,
+    AstroportPairCreation = 4,
+}
+
+#[index_list(Trade)]
+pub struct TradeIndexes<'a> {
+    pub user: MultiIndex<'a, Addr, Trade, u64>,
+}
+
+pub fn trades<'a>() -> IndexedMap<'a, u64, Trade, TradeIndexes<'a>> {
+    let indexes = TradeIndexes {
+        user: MultiIndex::new(|_, trade| trade.user.clone(), "trades", "trades_by_user"),
+    };
+
+    IndexedMap::new("trades", indexes)
+}
+
+#[index_list(Holder)]
+pub struct HolderIndexes<'a> {
+    pub balance: MultiIndex<'a, u128, Holder, Addr>,
+}
+
+pub fn holders<'a>() -> IndexedMap<'a, Addr, Holder, HolderIndexes<'a>> {
+    let indexes = HolderIndexes {
+        balance: MultiIndex::new(
+            |_, holder| holder.balance.u128(),
+            "holders",
+            "holders_by_balance",
+        ),
+    };
+
+    IndexedMap::new("holders", indexes)
 }
@@ -1,12 +1,14 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{attr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
+use cw2::set_contract_version;
 use rhaki_cw_plus::asset::only_one_coin;
 
 use crate::error::ContractError;
 use crate::execute::{
-    create_token_factory, end_flambe, request_dump, request_pump, update_config,
-    update_flambe_liquidity, update_flambe_status,
+    accept_ownership, cancel_ownership_proposal, create_token_factory, end_flambe, migrate_flambes,
+    pause, propagate_config, propagate_owner, propose_new_owner, request_dump, request_pump,
+    request_swap_exact_out, unpause, update_config, update_flambe_liquidity, update_flambe_status,
 };
 use crate::helper::{
     create_mint_msg_to_receiver, create_set_denom_metadata, derive_denom_from_subdenom,
@@ -14,15 +16,20 @@ use crate::helper::{
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgChangeAdmin, MsgCreateDenom};
 
 use ratatouille_pkg::flambe_factory::definitions::Config;
-use rhaki_cw_plus::traits::IntoBinaryResult;
+use rhaki_cw_plus::traits::{IntoAddr, IntoBinaryResult};
 
-use crate::query::{qy_config, qy_flambe, qy_flambes};
-use crate::state::CONFIG;
+use crate::migration::{assert_and_set_version, rebuild_price_liquidity_indexes};
+use crate::query::{qy_config, qy_cookie_emission, qy_flambe, qy_flambes, qy_ownership_proposal};
+use crate::reply::reply_flambe_instantiate;
+use crate::state::{ReplyIds, CONFIG};
 
 use ratatouille_pkg::flambe_factory::msgs::{
     ExecuteMsg, FlambeFilter, InstantiateMsg, MigrateMsg, QueryMsg,
 };
 
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -95,12 +102,29 @@ pub fn instantiate(
         cookie_token,
         cook_token,
         counter_flambe: 0,
+        guardian: msg
+            .guardian
+            .map(|val| deps.api.addr_validate(&val))
+            .transpose()?,
+        paused: false,
+        keeper_bounty: msg.keeper_bounty,
+        auto_graduation: msg.auto_graduation,
+        lp_fees_split: msg.lp_fees_split,
+        fee_split: msg.fee_split,
+        cookie_staking: msg
+            .cookie_staking
+            .map(|val| deps.api.addr_validate(&val))
+            .transpose()?,
+        cookie_emission: msg.cookie_emission,
+        cookie_sell_ratio: msg.cookie_sell_ratio,
     };
 
-    config.validate(deps.querier)?;
+    config.validate(deps.api, deps.querier)?;
 
     CONFIG.save(deps.storage, &config)?;
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new()
         // Cook
         .add_message(create_cook_msg)
@@ -121,14 +145,37 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if CONFIG.load(deps.storage)?.paused
+        && matches!(
+            msg,
+            ExecuteMsg::CreateFactory { .. }
+                | ExecuteMsg::Swap { .. }
+                | ExecuteMsg::SwapExactOut { .. }
+                | ExecuteMsg::EndFlambe(_)
+        )
+    {
+        return Err(ContractError::ProtocolPaused {});
+    }
+
     match msg {
-        ExecuteMsg::UpdatedConfig(msg) => update_config(deps, info.sender, msg),
+        ExecuteMsg::UpdatedConfig(msg) => update_config(deps, env, info.sender, msg),
         ExecuteMsg::CreateFactory {
             subdenom,
             flambe_threshold_index,
             msg,
-        } => create_token_factory(deps, info, env, subdenom, flambe_threshold_index, msg),
-        ExecuteMsg::UpdateFlambeStatus { status } => update_flambe_status(deps, info, status),
+            initial_buy,
+            min_amount_out,
+        } => create_token_factory(
+            deps,
+            info,
+            env,
+            subdenom,
+            flambe_threshold_index,
+            msg,
+            initial_buy,
+            min_amount_out,
+        ),
+        ExecuteMsg::UpdateFlambeStatus { status } => update_flambe_status(deps, env, info, status),
         ExecuteMsg::UpdateFlambeLiquidity => update_flambe_liquidity(deps, info.sender),
         ExecuteMsg::Swap {
             flambe_addr,
@@ -141,27 +188,123 @@ pub fn execute(
             )?;
 
             if received.denom == flambe.token.denom {
-                request_dump(flambe, info.sender, received, min_amount_out)
+                request_dump(deps, env, info.sender, received, flambe, min_amount_out)
             } else if received.denom == flambe.flambe_setting.pair_denom {
                 request_pump(deps, env, info.sender, received, flambe, min_amount_out)
             } else {
                 Err(ContractError::InvalidFlambeDenom {})
             }
         }
-        ExecuteMsg::EndFlambe(msg) => end_flambe(deps, env, info, msg),
+        ExecuteMsg::SwapExactOut {
+            flambe_addr,
+            amount_out,
+            max_amount_in,
+        } => {
+            let received = only_one_coin(&info.funds, None)?;
+            let flambe = qy_flambe(deps.as_ref(), FlambeFilter::ByFlambeAddr(flambe_addr))?;
+
+            if received.denom != flambe.token.denom
+                && received.denom != flambe.flambe_setting.pair_denom
+            {
+                return Err(ContractError::InvalidFlambeDenom {});
+            }
+
+            request_swap_exact_out(
+                deps,
+                env,
+                info.sender,
+                received,
+                flambe,
+                amount_out,
+                max_amount_in,
+            )
+        }
+        ExecuteMsg::EndFlambe(msg) => end_flambe(deps, info, msg),
+        ExecuteMsg::Pause { flambe_addr } => pause(deps, info, flambe_addr),
+        ExecuteMsg::Unpause { flambe_addr } => unpause(deps, info, flambe_addr),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            propose_new_owner(deps, env, info, owner, expires_in)
+        }
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => cancel_ownership_proposal(deps, info),
+        ExecuteMsg::PropagateOwner { start_after, limit } => {
+            propagate_owner(deps, info, start_after, limit)
+        }
+        ExecuteMsg::MigrateFlambes {
+            code_id,
+            msg,
+            limit,
+            start_after,
+        } => migrate_flambes(deps, env, info, code_id, msg, start_after, limit),
+        ExecuteMsg::PropagateConfig {
+            fields,
+            start_after,
+            limit,
+        } => propagate_config(deps, info, fields, start_after, limit),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => qy_config(deps).into_binary(),
+        QueryMsg::OwnershipProposal {} => qy_ownership_proposal(deps).into_binary(),
         QueryMsg::Flambe { filter } => qy_flambe(deps, filter).into_binary(),
         QueryMsg::Flambes { limit, filter } => qy_flambes(deps, limit, filter).into_binary(),
+        QueryMsg::CookieEmission {} => qy_cookie_emission(deps, env).into_binary(),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    match ReplyIds::from_repr(reply.id).ok_or(ContractError::InvalidReplyId(reply.id))? {
+        ReplyIds::FlambeInstantiate => reply_flambe_instantiate(deps, env),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    assert_and_set_version(deps.storage)?;
+
+    let mut attrs = vec![];
+
+    match msg {
+        MigrateMsg::Version {} => {}
+        MigrateMsg::RebuildIndexes {} => {
+            let rebuilt = rebuild_price_liquidity_indexes(deps.storage)?;
+            attrs.push(attr("rebuilt_indexes", rebuilt.to_string()));
+        }
+        MigrateMsg::MigrateConfig {
+            auto_graduation,
+            guardian,
+            keeper_bounty,
+        } => {
+            let mut config = CONFIG.load(deps.storage)?;
+
+            if let Some(auto_graduation) = auto_graduation {
+                config.auto_graduation = auto_graduation;
+                attrs.push(attr("auto_graduation", auto_graduation.to_string()));
+            }
+
+            if let Some(guardian) = guardian {
+                let guardian = guardian.into_addr(deps.api)?;
+                attrs.push(attr("guardian", guardian.to_string()));
+                config.guardian = Some(guardian);
+            }
+
+            if let Some(keeper_bounty) = keeper_bounty {
+                config.keeper_bounty = keeper_bounty;
+                attrs.push(attr("keeper_bounty", keeper_bounty.to_string()));
+            }
+
+            config.validate(deps.api, deps.querier)?;
+
+            CONFIG.save(deps.storage, &config)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_attributes(attrs))
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Coin, StdError};
+use cosmwasm_std::{Coin, CoinsError, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +6,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Coins(#[from] CoinsError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -26,4 +29,31 @@ pub enum ContractError {
 
     #[error("Insufficient Fee - requested {0} ")]
     InsufficientFee(Coin),
+
+    #[error("Invalid Initial Buy - requested {0}")]
+    InvalidInitialBuy(Coin),
+
+    #[error("Protocol Paused")]
+    ProtocolPaused {},
+
+    #[error("Invalid Ownership Proposal Expiry")]
+    InvalidExpiry {},
+
+    #[error("Ownership Proposal Not Found")]
+    OwnershipProposalNotFound {},
+
+    #[error("Ownership Proposal Expired")]
+    OwnershipProposalExpired {},
+
+    #[error("{0}")]
+    Semver(#[from] semver::Error),
+
+    #[error("Invalid Contract Name: expected {expected}, found {found}")]
+    InvalidContractName { expected: String, found: String },
+
+    #[error("Migration Downgrade: stored {stored}, new {new}")]
+    MigrationDowngrade { stored: String, new: String },
+
+    #[error("Invalid ReplyId:{0}")]
+    InvalidReplyId(u64),
 }
@@ -1,16 +1,33 @@
-use std::cmp::{self, min};
+use std::cmp::min;
 
+use crate::{
+    helper::{
+        create_cookie_msg, create_mint_msg_to_self, create_pump_msgs, create_set_denom_metadata,
+        creation_fees, derive_denom_from_subdenom,
+    },
+    query::{qy_flambe, DEFAULT_LIMIT, MAX_LIMIT},
+    state::{tokens, ReplyIds, CONFIG, COOKIE_EMITTED, OWNERSHIP_PROPOSAL, TMP_INITIAL_BUY},
+    ContractError,
+};
 use cosmwasm_std::{
-    attr, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    Uint128, WasmMsg,
+    attr, Addr, BankMsg, Coin, Coins, CosmosMsg, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
+use cw_storage_plus::Bound;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgChangeAdmin, MsgCreateDenom};
 use ratatouille_pkg::{
     flambe::{
-        definitions::FlambeStatus,
-        msgs::{ExecuteMsg, InstantiateMsg as FlambeInstantiateMsg},
+        definitions::{FlambeStatus, ReverseSwapResponse, SwapResponse},
+        msgs::{
+            ExecuteMsg, InstantiateMsg as FlambeInstantiateMsg, MigrateMsg as FlambeMigrateMsg,
+            QueryMsg as FlambeQueryMsg,
+        },
     },
     flambe_factory::{
-        definitions::{CreateFactoryInput, FlambeBaseInfo, FlambeFullInfo},
+        definitions::{
+            Config, CreateFactoryInput, FlambeBaseInfo, FlambeFullInfo, OwnershipProposal,
+            PropagatedField, TmpInitialBuy,
+        },
         msgs::{EndFlambeMsg, FlambeFilter, UpdateConfigMsg},
     },
 };
@@ -20,17 +37,9 @@ use rhaki_cw_plus::{
     wasm::{build_instantiate_2, WasmMsgBuilder},
 };
 
-use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgChangeAdmin, MsgCreateDenom};
-
-use crate::{
-    helper::{create_mint_msg_to_self, create_set_denom_metadata, derive_denom_from_subdenom},
-    query::qy_flambe,
-    state::{tokens, CONFIG},
-    ContractError,
-};
-
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     sender: Addr,
     msg: UpdateConfigMsg,
 ) -> Result<Response, ContractError> {
@@ -39,8 +48,15 @@ pub fn update_config(
         return Err(ContractError::Unauthorized {});
     }
 
+    let previous_emission = config.cookie_emission.clone();
+
     let mut attrs = vec![];
 
+    if let Some(auto_graduation) = msg.auto_graduation {
+        config.auto_graduation = auto_graduation;
+        attrs.push(attr("auto_graduation", config.auto_graduation.to_string()))
+    }
+
     if let Some(burner) = msg.burner {
         config.burner = deps.api.addr_validate(&burner)?;
         attrs.push(attr("burner", config.burner.clone()))
@@ -51,6 +67,11 @@ pub fn update_config(
         attrs.push(attr("cookie_ratio", config.cookie_ratio.to_string()))
     }
 
+    if let Some(cookie_emission) = msg.cookie_emission {
+        attrs.push(attr("cookie_emission", format!("{:?}", cookie_emission)));
+        config.cookie_emission = Some(cookie_emission);
+    }
+
     if let Some(cookie_owner_reward) = msg.cookie_owner_reward {
         config.cookie_owner_reward = cookie_owner_reward;
         attrs.push(attr(
@@ -59,11 +80,30 @@ pub fn update_config(
         ))
     }
 
+    if let Some(cookie_sell_ratio) = msg.cookie_sell_ratio {
+        config.cookie_sell_ratio = cookie_sell_ratio;
+        attrs.push(attr(
+            "cookie_sell_ratio",
+            config.cookie_sell_ratio.to_string(),
+        ))
+    }
+
+    if let Some(cookie_staking) = msg.cookie_staking {
+        let cookie_staking = cookie_staking.into_addr(deps.api)?;
+        attrs.push(attr("cookie_staking", cookie_staking.to_string()));
+        config.cookie_staking = Some(cookie_staking);
+    }
+
     if let Some(fee_collector) = msg.fee_collector {
         config.fee_collector = fee_collector.into_addr(deps.api)?;
         attrs.push(attr("fee_collector", config.fee_collector.to_string()))
     }
 
+    if let Some(fee_split) = msg.fee_split {
+        attrs.push(attr("fee_split", format!("{:?}", fee_split)));
+        config.fee_split = fee_split;
+    }
+
     if let Some(flambe_code_id) = msg.flambe_code_id {
         config.flambe_code_id = flambe_code_id;
         attrs.push(attr("flambe_code_id", config.flambe_code_id.to_string()))
@@ -77,9 +117,33 @@ pub fn update_config(
         ))
     }
 
-    if let Some(owner) = msg.owner {
-        config.owner = owner.into_addr(deps.api)?;
-        attrs.push(attr("owner", config.owner.to_string()))
+    match (msg.guardian, msg.remove_guardian.unwrap_or_default()) {
+        (Some(_), true) => {
+            return Err(StdError::generic_err(
+                "Can't set and remove the guardian in the same update",
+            )
+            .into())
+        }
+        (Some(guardian), false) => {
+            let guardian = guardian.into_addr(deps.api)?;
+            attrs.push(attr("guardian", guardian.to_string()));
+            config.guardian = Some(guardian);
+        }
+        (None, true) => {
+            attrs.push(attr("guardian", "none"));
+            config.guardian = None;
+        }
+        (None, false) => {}
+    }
+
+    if let Some(keeper_bounty) = msg.keeper_bounty {
+        config.keeper_bounty = keeper_bounty;
+        attrs.push(attr("keeper_bounty", config.keeper_bounty.to_string()))
+    }
+
+    if let Some(lp_fees_split) = msg.lp_fees_split {
+        attrs.push(attr("lp_fees_split", format!("{:?}", lp_fees_split)));
+        config.lp_fees_split = lp_fees_split;
     }
 
     if let Some(swap_fee) = msg.swap_fee {
@@ -91,13 +155,39 @@ pub fn update_config(
         return Err(ContractError::InvalidEmptyUpdate);
     }
 
-    config.validate(deps.querier)?;
+    config.validate(deps.api, deps.querier)?;
+
+    // The cookies emitted during the running epoch are carried over to the new schedule,
+    // so the epoch budget can't be restored by updating the emission
+    if config.cookie_emission != previous_emission {
+        let now = env.block.time.seconds();
+
+        let emitted = match (
+            previous_emission.and_then(|emission| emission.epoch(now)),
+            COOKIE_EMITTED.may_load(deps.storage)?,
+        ) {
+            (Some(epoch), Some((emitted_epoch, emitted))) if epoch == emitted_epoch => emitted,
+            _ => Uint128::zero(),
+        };
+
+        match config
+            .cookie_emission
+            .as_ref()
+            .and_then(|emission| emission.epoch(now))
+        {
+            Some(epoch) if !emitted.is_zero() => {
+                COOKIE_EMITTED.save(deps.storage, &(epoch, emitted))?
+            }
+            _ => COOKIE_EMITTED.remove(deps.storage),
+        }
+    }
 
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_token_factory(
     deps: DepsMut,
     info: MessageInfo,
@@ -105,6 +195,8 @@ pub fn create_token_factory(
     subdenom: String,
     flambe_setting_index: u8,
     factory_input: CreateFactoryInput,
+    initial_buy: Option<Uint128>,
+    min_amount_out: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -116,6 +208,16 @@ pub fn create_token_factory(
         })?
         .clone();
 
+    let mut funds = Coins::try_from(info.funds)?;
+
+    for fee in creation_fees(deps.as_ref(), &config)? {
+        if funds.amount_of(&fee.denom) < fee.amount {
+            return Err(ContractError::InsufficientFee(fee));
+        }
+
+        funds.sub(fee)?;
+    }
+
     let msg_create_denom = MsgCreateDenom {
         sender: env.contract.address.to_string(),
         subdenom: subdenom.clone(),
@@ -144,7 +246,8 @@ pub fn create_token_factory(
         deps.as_ref(),
         &env.contract.address,
         config.counter_flambe.into_binary()?,
-        Some(config.owner.to_string()),
+        // The factory is the admin to migrate the flambes with `MigrateFlambes`
+        Some(env.contract.address.to_string()),
         config.flambe_code_id,
         FlambeInstantiateMsg {
             owner: config.owner.to_string(),
@@ -155,6 +258,13 @@ pub fn create_token_factory(
             creator: info.sender.to_string(),
             // osmo_fee_creation: config.osmo_pool_fee_creation,
             burner_addr: config.burner.to_string(),
+            keeper_bounty: config.keeper_bounty,
+            auto_graduation: config.auto_graduation,
+            fee_split: flambe_setting
+                .fee_split
+                .clone()
+                .unwrap_or(config.fee_split.clone()),
+            cookie_staking: config.cookie_staking.as_ref().map(|val| val.to_string()),
         },
         vec![Coin::new(
             flambe_setting.initial_supply.u128(),
@@ -172,6 +282,44 @@ pub fn create_token_factory(
         None
     };
 
+    // Without an explicit initial buy, the surplus in the pair denom is invested
+    let initial_buy = initial_buy.unwrap_or_else(|| funds.amount_of(&flambe_setting.pair_denom));
+
+    // The initial buy is executed on the flambe instantiate reply
+    let flambe_init = if !initial_buy.is_zero() {
+        let offer = Coin::new(initial_buy.u128(), flambe_setting.pair_denom.clone());
+
+        if funds.amount_of(&offer.denom) < offer.amount {
+            return Err(ContractError::InvalidInitialBuy(offer));
+        }
+
+        funds.sub(offer.clone())?;
+
+        TMP_INITIAL_BUY.save(
+            deps.storage,
+            &TmpInitialBuy {
+                creator: info.sender.clone(),
+                flambe_address: flambe_address.clone(),
+                offer,
+                min_amount_out: min_amount_out.unwrap_or_default(),
+            },
+        )?;
+
+        SubMsg::reply_on_success(flambe_init, ReplyIds::FlambeInstantiate.repr())
+    } else {
+        SubMsg::new(flambe_init)
+    };
+
+    // Any surplus is refunded
+    let msg_refund = if !funds.is_empty() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: funds.into_vec(),
+        }))
+    } else {
+        None
+    };
+
     tokens().save(
         deps.storage,
         flambè_token.denom.clone(),
@@ -195,8 +343,9 @@ pub fn create_token_factory(
         .add_message(msg_mint)
         .add_message(msg_set_metadata)
         .add_message(msg_change_admin)
-        .add_message(flambe_init)
+        .add_submessage(flambe_init)
         .add_messages(msg_fee_creation)
+        .add_messages(msg_refund)
         .add_attribute("new_denom", flambè_token.denom)
         .add_attribute("flambe_addr", flambe_address))
 }
@@ -211,66 +360,125 @@ pub fn request_pump(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    // Mint cookie to user
-    let cookie_to_send = received.amount * config.swap_fee * config.cookie_ratio;
+    let (pump_msg, send_cookie_msg) = create_pump_msgs(
+        deps,
+        &env,
+        &config,
+        &flambe.flambe_address,
+        &sender,
+        received,
+        min_amount_out,
+    )?;
+
+    Ok(Response::new()
+        .add_message(pump_msg)
+        .add_messages(send_cookie_msg))
+}
 
-    let cookies_left = deps
-        .querier
-        .query_balance(env.contract.address, config.cookie_token.denom.clone())?;
+pub fn request_dump(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    received: Coin,
+    flambe: FlambeFullInfo,
+    min_amount_out: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
 
-    let cookie_to_send = cmp::min(cookies_left.amount, cookie_to_send);
+    // The fee of a dump is paid in pair denom on the amount returned
+    let SwapResponse { swap_fee, .. } = deps.querier.query_wasm_smart(
+        &flambe.flambe_address,
+        &FlambeQueryMsg::Simulate {
+            offer: received.denom.clone(),
+            amount: received.amount,
+        },
+    )?;
 
-    let send_cookie_msg = if cookie_to_send > Uint128::zero() {
-        Some(CosmosMsg::Bank(BankMsg::Send {
-            to_address: sender.to_string(),
-            amount: vec![Coin {
-                denom: config.cookie_token.denom,
-                amount: cookie_to_send,
-            }],
-        }))
-    } else {
-        None
-    };
+    let send_cookie_msg = create_cookie_msg(
+        deps,
+        &env,
+        &config,
+        &sender,
+        swap_fee.amount,
+        config.cookie_sell_ratio,
+    )?;
 
-    let pump_msg = WasmMsg::build_execute(
+    let dump_msg = WasmMsg::build_execute(
         flambe.flambe_address,
         ExecuteMsg::Swap {
             min_amount_out,
             user: sender.to_string(),
         },
-        vec![received.clone()],
+        vec![received],
     )?;
 
     Ok(Response::new()
-        .add_message(pump_msg)
+        .add_message(dump_msg)
         .add_messages(send_cookie_msg))
 }
 
-pub fn request_dump(
-    flambe: FlambeFullInfo,
+pub fn request_swap_exact_out(
+    deps: DepsMut,
+    env: Env,
     sender: Addr,
     received: Coin,
-    min_amount_out: Uint128,
+    flambe: FlambeFullInfo,
+    amount_out: Uint128,
+    max_amount_in: Uint128,
 ) -> Result<Response, ContractError> {
-    let dump_msg = WasmMsg::build_execute(
+    let config = CONFIG.load(deps.storage)?;
+
+    let is_pump = received.denom == flambe.flambe_setting.pair_denom;
+
+    let ReverseSwapResponse { swap_fee, .. } = deps.querier.query_wasm_smart(
+        &flambe.flambe_address,
+        &FlambeQueryMsg::SimulateReverse {
+            ask: if is_pump {
+                flambe.token.denom.clone()
+            } else {
+                flambe.flambe_setting.pair_denom.clone()
+            },
+            amount: amount_out,
+        },
+    )?;
+
+    // Cookies are rewarded only on the fee actually paid, the surplus is refunded
+    let send_cookie_msg = create_cookie_msg(
+        deps,
+        &env,
+        &config,
+        &sender,
+        swap_fee.amount,
+        if is_pump {
+            config.cookie_ratio
+        } else {
+            config.cookie_sell_ratio
+        },
+    )?;
+
+    let swap_msg = WasmMsg::build_execute(
         flambe.flambe_address,
-        ExecuteMsg::Swap {
-            min_amount_out,
+        ExecuteMsg::SwapExactOut {
+            amount_out,
+            max_amount_in,
             user: sender.to_string(),
         },
         vec![received],
     )?;
 
-    Ok(
-        Response::new().add_message(dump_msg),
-    )
+    Ok(Response::new()
+        .add_message(swap_msg)
+        .add_messages(send_cookie_msg))
 }
 
 pub fn update_flambe_status(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     status: FlambeStatus,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
     let mut token = get_unique_value(
         deps.storage,
         info.sender,
@@ -282,12 +490,63 @@ pub fn update_flambe_status(
 
     tokens().save(deps.storage, token.main_token.denom.clone(), &token)?;
 
-    Ok(Response::new().add_attribute("update_flambe_status", "success"))
+    // Reward the creator once the flambe is graduated
+    let msg_reward = if token.status == FlambeStatus::CLOSED {
+        let balance_cookie = deps
+            .querier
+            .query_balance(&env.contract.address, config.cookie_token.denom.clone())
+            .map(|val| val.amount)
+            .unwrap_or_default();
+
+        let reward = min(balance_cookie, config.cookie_owner_reward);
+
+        if reward > Uint128::zero() {
+            Some(CosmosMsg::Bank(BankMsg::Send {
+                to_address: token.creator.to_string(),
+                amount: vec![Coin {
+                    denom: config.cookie_token.denom,
+                    amount: reward,
+                }],
+            }))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let mut attrs = vec![];
+
+    // A closed flambe can't be migrated anymore, locking its liquidity.
+    // The admin can be cleared only by the factory, otherwise the lock is incomplete
+    let msg_clear_admin = if token.status == FlambeStatus::CLOSED {
+        let admin = deps
+            .querier
+            .query_wasm_contract_info(&token.flambe_address)?
+            .admin;
+
+        if admin.as_deref() == Some(env.contract.address.as_str()) {
+            Some(WasmMsg::ClearAdmin {
+                contract_addr: token.flambe_address.to_string(),
+            })
+        } else {
+            attrs.push(attr("position_lock", "incomplete"));
+            attrs.push(attr("admin", admin.unwrap_or("none".to_string())));
+            None
+        }
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(msg_reward)
+        .add_messages(msg_clear_admin)
+        .add_attribute("update_flambe_status", "success")
+        .add_attributes(attrs))
 }
 
 pub fn end_flambe(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
     msg: EndFlambeMsg,
 ) -> Result<Response, ContractError> {
@@ -295,7 +554,8 @@ pub fn end_flambe(
 
     let config = CONFIG.load(deps.storage)?;
 
-    if info.sender != config.owner {
+    // Anyone can graduate a flambe, but only the owner can route the pool creation fee swap
+    if msg.swap_msg.is_some() && info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -308,36 +568,19 @@ pub fn end_flambe(
         return Err(ContractError::InvalidFlambeStatus {});
     }
 
-    let balance_cookie = deps
-        .querier
-        .query_balance(env.contract.address, config.cookie_token.denom.clone())
-        .map(|val| val.amount)
-        .unwrap_or_default();
-
-    let reward = min(balance_cookie, config.cookie_owner_reward);
-
-    let msg_reward = if reward > Uint128::zero() {
-        Some(CosmosMsg::Bank(BankMsg::Send {
-            to_address: flambe.creator.to_string(),
-            amount: vec![Coin {
-                denom: config.cookie_token.denom,
-                amount: reward,
-            }],
-        }))
-    } else {
-        None
-    };
-
     let end_flambe_msg = WasmMsg::build_execute(
         flambe.flambe_address,
-        ratatouille_pkg::flambe::msgs::ExecuteMsg::Deploy(msg),
+        ratatouille_pkg::flambe::msgs::ExecuteMsg::Deploy {
+            keeper: info.sender.to_string(),
+            swap_msg: msg.swap_msg,
+        },
         vec![],
     )?;
 
     Ok(Response::new()
         .add_message(end_flambe_msg)
-        .add_messages(msg_reward)
-        .add_attribute("end_flambe", "success"))
+        .add_attribute("end_flambe", "success")
+        .add_attribute("keeper", info.sender))
 }
 
 pub fn update_flambe_liquidity(deps: DepsMut, sender: Addr) -> Result<Response, ContractError> {
@@ -355,3 +598,356 @@ pub fn update_flambe_liquidity(deps: DepsMut, sender: Addr) -> Result<Response,
 
     Ok(Response::new().add_attribute("action", "update_flmabe_liquidity"))
 }
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    flambe_addr: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner && Some(info.sender.clone()) != config.guardian {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    set_paused(deps, config, flambe_addr, true)
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    flambe_addr: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    set_paused(deps, config, flambe_addr, false)
+}
+
+fn set_paused(
+    deps: DepsMut,
+    mut config: Config,
+    flambe_addr: Option<String>,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    if let Some(flambe_addr) = flambe_addr {
+        let flambe = get_unique_value(
+            deps.storage,
+            flambe_addr.into_addr(deps.api)?,
+            tokens().idx.flambe_addr,
+            unique_map_value,
+        )?;
+
+        let msg = WasmMsg::build_execute(
+            &flambe.flambe_address,
+            ExecuteMsg::SetPaused { paused },
+            vec![],
+        )?;
+
+        Ok(Response::new()
+            .add_message(msg)
+            .add_attribute("action", "set_paused")
+            .add_attribute("flambe_addr", flambe.flambe_address)
+            .add_attribute("paused", paused.to_string()))
+    } else {
+        config.paused = paused;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_paused")
+            .add_attribute("paused", paused.to_string()))
+    }
+}
+
+pub fn propose_new_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    expires_in: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if expires_in == 0 {
+        return Err(ContractError::InvalidExpiry {});
+    }
+
+    let proposal = OwnershipProposal {
+        owner: owner.into_addr(deps.api)?,
+        expires_at: env.block.time.seconds() + expires_in,
+    };
+
+    OWNERSHIP_PROPOSAL.save(deps.storage, &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_new_owner")
+        .add_attribute("proposed_owner", proposal.owner)
+        .add_attribute("expires_at", proposal.expires_at.to_string()))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let proposal = OWNERSHIP_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::OwnershipProposalNotFound {})?;
+
+    if info.sender != proposal.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if env.block.time.seconds() >= proposal.expires_at {
+        return Err(ContractError::OwnershipProposalExpired {});
+    }
+
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_owner = config.owner;
+    config.owner = proposal.owner;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("previous_owner", previous_owner)
+        .add_attribute("owner", config.owner))
+}
+
+pub fn cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !OWNERSHIP_PROPOSAL.exists(deps.storage) {
+        return Err(ContractError::OwnershipProposalNotFound {});
+    }
+
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_ownership_proposal"))
+}
+
+pub fn propagate_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let flambes = tokens()
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect::<StdResult<Vec<FlambeBaseInfo>>>()?;
+
+    let msgs = flambes
+        .iter()
+        .map(|flambe| {
+            WasmMsg::build_execute(
+                &flambe.flambe_address,
+                ExecuteMsg::UpdateOwner {
+                    owner: config.owner.to_string(),
+                },
+                vec![],
+            )
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let last = flambes
+        .last()
+        .map(|flambe| flambe.main_token.denom.clone())
+        .unwrap_or_default();
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "propagate_owner")
+        .add_attribute("owner", config.owner)
+        .add_attribute("updated", flambes.len().to_string())
+        .add_attribute("last", last))
+}
+
+pub fn migrate_flambes(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    msg: FlambeMigrateMsg,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // New flambes are instantiated with the migrated code
+    if start_after.is_none() {
+        config.flambe_code_id = code_id;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let flambes = tokens()
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect::<StdResult<Vec<FlambeBaseInfo>>>()?;
+
+    let msg = msg.into_binary()?;
+    let mut msgs = vec![];
+
+    for flambe in &flambes {
+        // Closed flambes have no admin, the older ones are still administrated by the owner
+        let admin = deps
+            .querier
+            .query_wasm_contract_info(&flambe.flambe_address)?
+            .admin;
+
+        if admin.as_deref() != Some(env.contract.address.as_str()) {
+            continue;
+        }
+
+        msgs.push(WasmMsg::Migrate {
+            contract_addr: flambe.flambe_address.to_string(),
+            new_code_id: code_id,
+            msg: msg.clone(),
+        });
+    }
+
+    let last = flambes
+        .last()
+        .map(|flambe| flambe.main_token.denom.clone())
+        .unwrap_or_default();
+
+    let migrated = msgs.len();
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "migrate_flambes")
+        .add_attribute("code_id", code_id.to_string())
+        .add_attribute("migrated", migrated.to_string())
+        .add_attribute("skipped", (flambes.len() - migrated).to_string())
+        .add_attribute("last", last))
+}
+
+pub fn propagate_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fields: Vec<PropagatedField>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if fields.is_empty() {
+        return Err(ContractError::InvalidEmptyUpdate);
+    }
+
+    let flambes = tokens()
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect::<StdResult<Vec<FlambeBaseInfo>>>()?;
+
+    let mut attrs = vec![];
+
+    let burner_addr = fields.contains(&PropagatedField::Burner).then(|| {
+        attrs.push(attr("burner", config.burner.clone()));
+        config.burner.to_string()
+    });
+
+    let fee_collector = fields.contains(&PropagatedField::FeeCollector).then(|| {
+        attrs.push(attr("fee_collector", config.fee_collector.clone()));
+        config.fee_collector.to_string()
+    });
+
+    let swap_fee = fields.contains(&PropagatedField::SwapFee).then(|| {
+        attrs.push(attr("swap_fee", config.swap_fee.to_string()));
+        config.swap_fee
+    });
+
+    let mut msgs = vec![];
+    let mut skipped = vec![];
+
+    for flambe in &flambes {
+        // Flambes running an older code may not handle the update,
+        // `MigrateFlambes` has to be run first
+        let code_id = deps
+            .querier
+            .query_wasm_contract_info(&flambe.flambe_address)?
+            .code_id;
+
+        // The swap fee is charged only along the curve, while the fee collector and the burner
+        // still receive the sells of the failed flambes, the graduation burn and the LP fees
+        let swap_fee =
+            swap_fee.filter(|_| matches!(flambe.status, FlambeStatus::OPEN | FlambeStatus::FAILED));
+
+        if code_id != config.flambe_code_id
+            || (burner_addr.is_none() && fee_collector.is_none() && swap_fee.is_none())
+        {
+            skipped.push(flambe.flambe_address.to_string());
+            continue;
+        }
+
+        msgs.push(WasmMsg::build_execute(
+            &flambe.flambe_address,
+            &ExecuteMsg::UpdateConfig {
+                burner_addr: burner_addr.clone(),
+                fee_collector: fee_collector.clone(),
+                swap_fee,
+            },
+            vec![],
+        )?);
+    }
+
+    let last = flambes
+        .last()
+        .map(|flambe| flambe.main_token.denom.clone())
+        .unwrap_or_default();
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "propagate_config")
+        .add_attributes(attrs)
+        .add_attribute("updated", (flambes.len() - skipped.len()).to_string())
+        .add_attributes((!skipped.is_empty()).then(|| attr("skipped", skipped.join(","))))
+        .add_attribute("last", last))
+}
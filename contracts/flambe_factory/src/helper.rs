@@ -1,14 +1,27 @@
-use ratatouille_pkg::flambe_factory::definitions::ProtocolTokenInfo;
+use std::cmp;
 
-use cosmwasm_std::{CosmosMsg, Uint128};
+use ratatouille_pkg::{
+    flambe::{
+        definitions::SwapResponse,
+        msgs::{ExecuteMsg as FlambeExecuteMsg, QueryMsg as FlambeQueryMsg},
+    },
+    flambe_factory::definitions::{Config, ProtocolTokenInfo},
+};
+
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, Coins, CosmosMsg, Decimal, Deps, DepsMut, Env, StdResult, Uint128, WasmMsg,
+};
 
 use osmosis_std::types::{
     cosmos::{
         bank::v1beta1::{DenomUnit, Metadata},
         base::v1beta1::Coin as ProtoCoin,
     },
-    osmosis::tokenfactory::v1beta1::{MsgMint, MsgSetDenomMetadata},
+    osmosis::tokenfactory::v1beta1::{MsgMint, MsgSetDenomMetadata, QueryParamsRequest},
 };
+use rhaki_cw_plus::wasm::WasmMsgBuilder;
+
+use crate::state::COOKIE_EMITTED;
 
 pub fn derive_denom_from_subdenom(creator: impl Into<String>, subdenom: &str) -> String {
     format!("factory/{}/{}", creator.into(), subdenom)
@@ -78,3 +91,134 @@ pub fn create_set_denom_metadata(
     }
     .into()
 }
+
+/// Fees to be paid by the creator of a flambe: the tokenfactory denom creation fee plus the `flambe_fee_creation`
+pub fn creation_fees(deps: Deps, config: &Config) -> StdResult<Coins> {
+    let mut fees = Coins::default();
+
+    let denom_creation_fee = QueryParamsRequest {}
+        .query(&deps.querier)?
+        .params
+        .map(|val| val.denom_creation_fee)
+        .unwrap_or_default();
+
+    for coin in denom_creation_fee {
+        fees.add(coin.try_into()?)?;
+    }
+
+    if let Some(fee_creation) = &config.flambe_fee_creation {
+        fees.add(fee_creation.clone())?;
+    }
+
+    Ok(fees)
+}
+
+/// Build the `Swap` msg to the flambe and the cookie reward msg for the user.
+/// The fee is simulated on the flambe, following its fee schedule
+pub fn create_pump_msgs(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    flambe_address: &Addr,
+    user: &Addr,
+    received: Coin,
+    min_amount_out: Uint128,
+) -> StdResult<(CosmosMsg, Option<CosmosMsg>)> {
+    let SwapResponse { swap_fee, .. } = deps.querier.query_wasm_smart(
+        flambe_address,
+        &FlambeQueryMsg::Simulate {
+            offer: received.denom.clone(),
+            amount: received.amount,
+        },
+    )?;
+
+    let send_cookie_msg = create_cookie_msg(
+        deps,
+        env,
+        config,
+        user,
+        swap_fee.amount,
+        config.cookie_ratio,
+    )?;
+
+    let pump_msg = WasmMsg::build_execute(
+        flambe_address,
+        FlambeExecuteMsg::Swap {
+            min_amount_out,
+            user: user.to_string(),
+        },
+        vec![received],
+    )?;
+
+    Ok((pump_msg.into(), send_cookie_msg))
+}
+
+/// Current cookie ratio factor and cookies that can still be rewarded in this epoch.
+/// Without an emission schedule the cookies are only capped to the factory balance
+pub fn cookie_emission_status(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+) -> StdResult<(Option<u64>, Decimal, Uint128)> {
+    let cookies_left = deps
+        .querier
+        .query_balance(&env.contract.address, config.cookie_token.denom.clone())?
+        .amount;
+
+    let Some(emission) = &config.cookie_emission else {
+        return Ok((None, Decimal::one(), cookies_left));
+    };
+
+    let Some(epoch) = emission.epoch(env.block.time.seconds()) else {
+        return Ok((None, Decimal::zero(), Uint128::zero()));
+    };
+
+    let emitted = match COOKIE_EMITTED.may_load(deps.storage)? {
+        Some((emitted_epoch, emitted)) if emitted_epoch == epoch => emitted,
+        _ => Uint128::zero(),
+    };
+
+    Ok((
+        Some(epoch),
+        emission.rate(epoch),
+        cmp::min(
+            emission.epoch_budget(epoch).saturating_sub(emitted),
+            cookies_left,
+        ),
+    ))
+}
+
+/// Cookies rewarded to the user for `fee_amount` of swap fee paid, following the emission schedule
+pub fn create_cookie_msg(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    user: &Addr,
+    fee_amount: Uint128,
+    cookie_ratio: Decimal,
+) -> StdResult<Option<CosmosMsg>> {
+    let (epoch, rate, remaining) = cookie_emission_status(deps.as_ref(), env, config)?;
+
+    let cookie_to_send = cmp::min(remaining, fee_amount * cookie_ratio * rate);
+
+    if let Some(epoch) = epoch {
+        let emitted = match COOKIE_EMITTED.may_load(deps.storage)? {
+            Some((emitted_epoch, emitted)) if emitted_epoch == epoch => emitted,
+            _ => Uint128::zero(),
+        };
+
+        COOKIE_EMITTED.save(deps.storage, &(epoch, emitted + cookie_to_send))?;
+    }
+
+    Ok(if cookie_to_send > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.to_string(),
+            amount: vec![Coin {
+                denom: config.cookie_token.denom.clone(),
+                amount: cookie_to_send,
+            }],
+        }))
+    } else {
+        None
+    })
+}
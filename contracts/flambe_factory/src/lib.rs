@@ -2,7 +2,9 @@ pub mod contract;
 mod error;
 pub mod execute;
 pub mod helper;
+pub mod migration;
 pub mod query;
+mod reply;
 pub mod state;
 
 pub use crate::error::ContractError;
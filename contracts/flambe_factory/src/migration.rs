@@ -0,0 +1,142 @@
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw2::{set_contract_version, CONTRACT};
+use cw_storage_plus::{Index, MultiIndex, PrimaryKey};
+use ratatouille_pkg::flambe_factory::definitions::FlambeBaseInfo;
+use semver::Version;
+
+use crate::{
+    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    state::tokens,
+    ContractError,
+};
+
+/// Check the stored cw2 version against the new one and update it.
+///
+/// Factories instantiated before the cw2 versioning have no version stored and are always migrated.
+pub fn assert_and_set_version(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if let Some(stored) = CONTRACT.may_load(storage)? {
+        if stored.contract != CONTRACT_NAME {
+            return Err(ContractError::InvalidContractName {
+                expected: CONTRACT_NAME.to_string(),
+                found: stored.contract,
+            });
+        }
+
+        if stored.version.parse::<Version>()? > CONTRACT_VERSION.parse::<Version>()? {
+            return Err(ContractError::MigrationDowngrade {
+                stored: stored.version,
+                new: CONTRACT_VERSION.to_string(),
+            });
+        }
+    }
+
+    set_contract_version(storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(())
+}
+
+/// Index on the legacy stringed values. It shares the namespaces with the current
+/// `liquidity` and `price` indexes and is only used to clean up the old entries.
+fn legacy_index<'a>(
+    idx_fn: fn(&[u8], &FlambeBaseInfo) -> String,
+    idx_namespace: &'a str,
+) -> MultiIndex<'a, String, FlambeBaseInfo, String> {
+    MultiIndex::new(idx_fn, "tokens", idx_namespace)
+}
+
+/// Rebuild the `liquidity` and `price` indexes, replacing the stringed keys
+/// (sorted lexicographically) with the numerically sortable `u128` ones.
+///
+/// Running it on already migrated entries is a no-op.
+pub fn rebuild_price_liquidity_indexes(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy_liquidity = legacy_index(
+        |_, token| token.last_liquidity.to_string(),
+        "tokens_by_liquidity",
+    );
+    let legacy_price = legacy_index(|_, token| token.last_price.to_string(), "tokens_by_price");
+
+    let flambes = tokens()
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (denom, token) in &flambes {
+        let pk = denom.joined_key();
+
+        legacy_liquidity.remove(storage, &pk, token)?;
+        legacy_price.remove(storage, &pk, token)?;
+
+        tokens().idx.liquidity.save(storage, &pk, token)?;
+        tokens().idx.price.save(storage, &pk, token)?;
+    }
+
+    Ok(flambes.len())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{testing::mock_dependencies, Order};
+    use cw_storage_plus::{Index, Map, PrimaryKey};
+    use ratatouille_pkg::flambe_factory::definitions::FlambeBaseInfo;
+
+    use cw2::{get_contract_version, set_contract_version};
+
+    use crate::{
+        contract::{CONTRACT_NAME, CONTRACT_VERSION},
+        state::{test::create_token, tokens},
+        ContractError,
+    };
+
+    use super::{assert_and_set_version, legacy_index, rebuild_price_liquidity_indexes};
+
+    #[test]
+    #[rustfmt::skip]
+    fn version_check() {
+        let mut deps = mock_dependencies();
+
+        // Legacy factory without a stored version
+        assert_and_set_version(deps.as_mut().storage).unwrap();
+        assert_eq!(get_contract_version(deps.as_ref().storage).unwrap().version, CONTRACT_VERSION);
+
+        // Same version
+        assert_and_set_version(deps.as_mut().storage).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+        assert_and_set_version(deps.as_mut().storage).unwrap();
+        assert_eq!(get_contract_version(deps.as_ref().storage).unwrap().version, CONTRACT_VERSION);
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        assert!(matches!(assert_and_set_version(deps.as_mut().storage).unwrap_err(), ContractError::MigrationDowngrade { .. }));
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", CONTRACT_VERSION).unwrap();
+        assert!(matches!(assert_and_set_version(deps.as_mut().storage).unwrap_err(), ContractError::InvalidContractName { .. }));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn rebuild_indexes() {
+        let mut deps = mock_dependencies();
+
+        let legacy_liquidity = legacy_index(|_, token| token.last_liquidity.to_string(), "tokens_by_liquidity");
+        let legacy_price = legacy_index(|_, token| token.last_price.to_string(), "tokens_by_price");
+
+        let flambes = [create_token(1, "0.9", 9), create_token(2, "10", 100), create_token(3, "2.5", 1_000)];
+
+        // Simulate the storage as written by the previous version
+        for token in &flambes {
+            let pk = token.main_token.denom.joined_key();
+            Map::<String, FlambeBaseInfo>::new("tokens").save(deps.as_mut().storage, token.main_token.denom.clone(), token).unwrap();
+            legacy_liquidity.save(deps.as_mut().storage, &pk, token).unwrap();
+            legacy_price.save(deps.as_mut().storage, &pk, token).unwrap();
+        }
+
+        assert_eq!(rebuild_price_liquidity_indexes(deps.as_mut().storage).unwrap(), 3);
+        // Idempotent
+        assert_eq!(rebuild_price_liquidity_indexes(deps.as_mut().storage).unwrap(), 3);
+
+        let liquidities: Vec<u128> = tokens().idx.liquidity.range(deps.as_ref().storage, None, None, Order::Descending).map(|val| val.unwrap().1.last_liquidity.u128()).collect();
+        assert_eq!(liquidities, vec![1_000, 100, 9]);
+
+        let prices: Vec<String> = tokens().idx.price.range(deps.as_ref().storage, None, None, Order::Ascending).map(|val| val.unwrap().1.last_price.to_string()).collect();
+        assert_eq!(prices, vec!["0.9", "2.5", "10"]);
+    }
+}
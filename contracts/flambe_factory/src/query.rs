@@ -1,12 +1,12 @@
 use std::cmp::min;
 
-use cosmwasm_std::{Addr, Deps, Order, StdError, StdResult};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult};
 use cw_storage_plus::{Bound, KeyDeserialize, PrimaryKey};
 use ratatouille_pkg::{
     flambe::{definitions::FlambeInfo, msgs::QueryMsg as FlmabeQueryMsg},
     flambe_factory::{
-        definitions::{Config, FlambeFullInfo},
-        msgs::{FlambeFilter, FlambesFilter},
+        definitions::{Config, FlambeFullInfo, OwnershipProposal},
+        msgs::{CookieEmissionResponse, FlambeFilter, FlambesFilter},
     },
 };
 use rhaki_cw_plus::{
@@ -16,13 +16,37 @@ use rhaki_cw_plus::{
     traits::IntoAddr,
 };
 
-use crate::state::{tokens, CONFIG};
+use crate::{
+    helper::cookie_emission_status,
+    state::{tokens, CONFIG, OWNERSHIP_PROPOSAL},
+};
 
 pub fn qy_config(deps: Deps) -> StdResult<Config> {
     let config = CONFIG.load(deps.storage)?;
     Ok(config)
 }
 
+pub fn qy_ownership_proposal(deps: Deps) -> StdResult<Option<OwnershipProposal>> {
+    OWNERSHIP_PROPOSAL.may_load(deps.storage)
+}
+
+pub fn qy_cookie_emission(deps: Deps, env: Env) -> StdResult<CookieEmissionResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let (epoch, rate, remaining_budget) = cookie_emission_status(deps, &env, &config)?;
+
+    let emission = config.cookie_emission.as_ref();
+
+    Ok(CookieEmissionResponse {
+        epoch,
+        buy_ratio: config.cookie_ratio * rate,
+        sell_ratio: config.cookie_sell_ratio * rate,
+        epoch_budget: epoch.and_then(|epoch| emission.map(|val| val.epoch_budget(epoch))),
+        remaining_budget,
+        end_time: emission.map(|val| val.end_time),
+    })
+}
+
 pub fn qy_flambe(deps: Deps, filter: FlambeFilter) -> StdResult<FlambeFullInfo> {
     let base_info = match filter {
         FlambeFilter::ByTokenDenom(denom) => tokens()
@@ -47,10 +71,10 @@ pub fn qy_flambes(
     filter: FlambesFilter,
 ) -> StdResult<Vec<FlambeFullInfo>> {
     match filter {
-        FlambesFilter::Empty { start_after } => get_items(
+        FlambesFilter::Empty { start_after, order } => get_items(
             deps.storage,
             tokens(),
-            Order::Descending,
+            order.unwrap_or_default().into(),
             limit,
             start_after,
             multi_map_value,
@@ -58,11 +82,12 @@ pub fn qy_flambes(
         FlambesFilter::ByCreator {
             creator,
             start_after,
+            order,
         } => get_multi_index_values(
             deps.storage,
             creator.into_addr(deps.api)?,
             tokens().idx.creator,
-            Order::Descending,
+            order.unwrap_or_default().into(),
             start_after,
             limit,
             multi_map_value,
@@ -70,33 +95,42 @@ pub fn qy_flambes(
         FlambesFilter::ByStatus {
             status,
             start_after,
+            order,
         } => get_multi_index_values(
             deps.storage,
             status.to_string(),
             tokens().idx.status,
-            Order::Descending,
+            order.unwrap_or_default().into(),
             start_after,
             limit,
             multi_map_value,
         ),
-        FlambesFilter::ByLiquidity { start_after } => {
-            let (min_b, max_b) = min_max_from_order(start_after, &Order::Descending);
+        FlambesFilter::ByLiquidity { start_after, order } => {
+            let order: Order = order.unwrap_or_default().into();
+            let (min_b, max_b) = min_max_from_order(
+                start_after.map(|(liquidity, denom)| (liquidity.u128(), denom)),
+                &order,
+            );
 
             tokens()
                 .idx
                 .liquidity
-                .range(deps.storage, min_b, max_b, Order::Descending)
+                .range(deps.storage, min_b, max_b, order)
                 .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
                 .map(|item| item.map(|val| val.1))
                 .collect()
         }
-        FlambesFilter::ByPrice { start_after } => {
-            let (min_b, max_b) = min_max_from_order(start_after, &Order::Descending);
+        FlambesFilter::ByPrice { start_after, order } => {
+            let order: Order = order.unwrap_or_default().into();
+            let (min_b, max_b) = min_max_from_order(
+                start_after.map(|(price, denom)| (price.atomics().u128(), denom)),
+                &order,
+            );
 
             tokens()
                 .idx
-                .liquidity
-                .range(deps.storage, min_b, max_b, Order::Descending)
+                .price
+                .range(deps.storage, min_b, max_b, order)
                 .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
                 .map(|item| item.map(|val| val.1))
                 .collect()
@@ -117,8 +151,8 @@ fn flambe_info(deps: Deps, flambe_addr: &Addr) -> StdResult<FlambeInfo> {
         .query_wasm_smart(flambe_addr, &FlmabeQueryMsg::Info {})
 }
 
-const DEFAULT_LIMIT: u32 = 10;
-const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
 
 fn min_max_from_order<'a, PK: PrimaryKey<'a> + KeyDeserialize + 'static>(
     start_after: Option<PK>,
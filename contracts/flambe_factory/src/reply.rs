@@ -0,0 +1,31 @@
+use cosmwasm_std::{DepsMut, Env, Response};
+
+use crate::{
+    helper::create_pump_msgs,
+    state::{CONFIG, TMP_INITIAL_BUY},
+    ContractError,
+};
+
+pub fn reply_flambe_instantiate(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let initial_buy = TMP_INITIAL_BUY.load(deps.storage)?;
+    TMP_INITIAL_BUY.remove(deps.storage);
+
+    let (pump_msg, send_cookie_msg) = create_pump_msgs(
+        deps,
+        &env,
+        &config,
+        &initial_buy.flambe_address,
+        &initial_buy.creator,
+        initial_buy.offer.clone(),
+        initial_buy.min_amount_out,
+    )?;
+
+    Ok(Response::new()
+        .add_message(pump_msg)
+        .add_messages(send_cookie_msg)
+        .add_attribute("action", "initial_buy")
+        .add_attribute("creator", initial_buy.creator)
+        .add_attribute("initial_buy", initial_buy.offer.to_string()))
+}
@@ -1,17 +1,32 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{index_list, IndexedMap, Item, MultiIndex, UniqueIndex};
-use ratatouille_pkg::flambe_factory::definitions::{Config, FlambeBaseInfo};
+use enum_repr::EnumRepr;
+use ratatouille_pkg::flambe_factory::definitions::{
+    Config, FlambeBaseInfo, OwnershipProposal, TmpInitialBuy,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config_key");
 
-pub type StringedDecimal = String;
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+pub const TMP_INITIAL_BUY: Item<TmpInitialBuy> = Item::new("tmp_initial_buy");
+
+/// Epoch of the cookie emission and cookies rewarded during it
+pub const COOKIE_EMITTED: Item<(u64, Uint128)> = Item::new("cookie_emitted");
+
+#[EnumRepr(type = "u64")]
+pub enum ReplyIds {
+    FlambeInstantiate = 1,
+}
 
 #[index_list(FlambeBaseInfo)]
 pub struct FlambeInfoIndexes<'a> {
     pub status: MultiIndex<'a, String, FlambeBaseInfo, String>,
     pub creator: MultiIndex<'a, Addr, FlambeBaseInfo, String>,
-    pub liquidity: MultiIndex<'a, StringedDecimal, FlambeBaseInfo, String>,
-    pub price: MultiIndex<'a, StringedDecimal, FlambeBaseInfo, String>,
+    /// Keyed by the raw `u128` amount, stored big-endian so the index sorts numerically
+    pub liquidity: MultiIndex<'a, u128, FlambeBaseInfo, String>,
+    /// Keyed by the `Decimal` atomics, stored big-endian so the index sorts numerically
+    pub price: MultiIndex<'a, u128, FlambeBaseInfo, String>,
 
     pub flambe_addr: UniqueIndex<'a, Addr, FlambeBaseInfo, String>,
 }
@@ -30,12 +45,12 @@ pub fn tokens<'a>() -> IndexedMap<'a, String, FlambeBaseInfo, FlambeInfoIndexes<
         ),
         flambe_addr: UniqueIndex::new(|token| token.flambe_address.clone(), "token_by_flambe_addr"),
         liquidity: MultiIndex::new(
-            |_, token| token.last_liquidity.to_string(),
+            |_, token| token.last_liquidity.u128(),
             "tokens",
             "tokens_by_liquidity",
         ),
         price: MultiIndex::new(
-            |_, token| token.last_price.to_string(),
+            |_, token| token.last_price.atomics().u128(),
             "tokens",
             "tokens_by_price",
         ),
@@ -45,32 +60,49 @@ pub fn tokens<'a>() -> IndexedMap<'a, String, FlambeBaseInfo, FlambeInfoIndexes<
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use cosmwasm_std::{testing::mock_dependencies, Decimal, Order, Uint128};
     use cw_storage_plus::Bound;
     use ratatouille_pkg::{
         flambe::definitions::FlambeStatus,
         flambe_factory::definitions::{
-            FlambeBaseInfo, FlambeSetting, PoolCreationInfo, ProtocolTokenInfo,
+            CurveType, FlambeBaseInfo, FlambeSetting, GraduationTarget, PoolCreationInfo,
+            ProtocolTokenInfo,
         },
     };
-    use rhaki_cw_plus::{
-        math::IntoDecimal,
-        traits::{IntoAddr, Unclone},
-    };
+    use rhaki_cw_plus::{math::IntoDecimal, traits::IntoAddr};
 
     use super::tokens;
 
-    fn create_token(
+    pub fn create_token(
         index: usize,
-        fs: &FlambeSetting,
         last_price: impl IntoDecimal,
         liquidity: u128,
     ) -> FlambeBaseInfo {
         FlambeBaseInfo {
             creator: format!("creator_{index}").into_unchecked_addr(),
             flambe_address: format!("flambe_{index}").into_unchecked_addr(),
-            flambe_setting: fs.clone(),
+            flambe_setting: FlambeSetting {
+                pair_denom: "denom_pair".to_string(),
+                threshold: 100_000_u128.into(),
+                initial_price: "0.1".into_decimal(),
+                initial_supply: 1_000_000_u128.into(),
+                pool_creation_info: PoolCreationInfo {
+                    tick_spacing: 100,
+                    spread_factor: "0.01".into_decimal(),
+                    lower_tick: -100,
+                    upper_tick: 100,
+                },
+                trades_retention: 100,
+                max_buy_per_tx: None,
+                max_holding: None,
+                launch_window: 0,
+                curve: CurveType::ConstantProductVirtual,
+                deadline: None,
+                graduation_target: GraduationTarget::OsmosisCL,
+                fee_split: None,
+                fee_schedule: None,
+            },
             main_token: ProtocolTokenInfo {
                 denom: format!("flambe_{index}_denom").to_string(),
                 description: format!("description_{index}").to_string(),
@@ -89,74 +121,49 @@ mod test {
     #[test]
     #[rustfmt::skip]
     fn test() {
-        let fs = FlambeSetting {
-            pair_denom: "denom_pair".to_string(),
-            threshold: 100_000_u128.into(),
-            initial_price: "0.1".into_decimal(),
-            initial_supply: 1_000_000_u128.into(),
-            pool_creation_info: PoolCreationInfo {
-                tick_spacing: 100,
-                spread_factor: "0.01".into_decimal(),
-                lower_tick: -100,
-                upper_tick: 100,
-            },
-        };
-
-        let token_1 = create_token(1, &fs, "0.1", 100_000);
-        let token_2 = create_token(2, &fs, "0.1", 200_000);
-        let token_3 = create_token(3, &fs, "0.3", 300_000);
-        let token_4 = create_token(4, &fs, "0.3", 100_000);
-        let token_5 = create_token(5, &fs, "0.2", 200_000);
-        let token_6 = create_token(6, &fs, "0.2", 300_000);
-        let token_7 = create_token(7, &fs, "0.2", 400_000);
+        let token_1 = create_token(1, "0.1", 100_000);
+        let token_2 = create_token(2, "0.1", 200_000);
+        let token_3 = create_token(3, "0.3", 300_000);
+        let token_4 = create_token(4, "0.3", 100_000);
+        let token_5 = create_token(5, "0.2", 20_000);
+        let token_6 = create_token(6, "2", 3_000_000);
+        let token_7 = create_token(7, "10", 400);
 
         let mut deps = mock_dependencies();
 
-        tokens().save(deps.as_mut().storage, token_1.main_token.denom.clone(), &token_1).unwrap();
-        tokens().save(deps.as_mut().storage, token_2.main_token.denom.clone(), &token_2).unwrap();
-        tokens().save(deps.as_mut().storage, token_3.main_token.denom.clone(), &token_3).unwrap();
-        tokens().save(deps.as_mut().storage, token_4.main_token.denom.clone(), &token_4).unwrap();
-        tokens().save(deps.as_mut().storage, token_5.main_token.denom.clone(), &token_5).unwrap();
-        tokens().save(deps.as_mut().storage, token_6.main_token.denom.clone(), &token_6).unwrap();
-        tokens().save(deps.as_mut().storage, token_7.main_token.denom.clone(), &token_7).unwrap();
-
-        let liquidities: Vec<Uint128> = tokens().idx.liquidity.range(deps.as_ref().storage, None, None, Order::Descending).take(7).map(|val| val.unwrap().1.last_liquidity ).collect();
-        let prices: Vec<Decimal> = tokens().idx.price.range(deps.as_ref().storage, None, None, Order::Descending).take(7).map(|val| val.unwrap().1.last_price ).collect();
-
-        println!("{:#?}", liquidities);
-        println!("{:#?}", prices);
-
-        let prices: Vec<(String, Decimal)> = tokens().idx.price.range(deps.as_ref().storage, None, Some(Bound::exclusive((token_5.last_price.to_string(), token_5.main_token.denom.clone()))), Order::Descending)
-        .take(2).map(|val| (val.map(|val| (val.1.main_token.denom, val.1.last_price)).unwrap())).collect();
-
-        println!("2: {:#?}", prices);
+        for token in [&token_1, &token_2, &token_3, &token_4, &token_5, &token_6, &token_7] {
+            tokens().save(deps.as_mut().storage, token.main_token.denom.clone(), token).unwrap();
+        }
 
-        let prices: Vec<(String, Decimal)> = tokens().idx.price.range(deps.as_ref().storage, None, Some(Bound::exclusive((token_2.last_price.to_string(), token_2.main_token.denom.clone()))), Order::Descending)
-        .take(2).map(|val| (val.map(|val| (val.1.main_token.denom, val.1.last_price)).unwrap())).collect();
+        // Numeric and not lexicographic ordering
+        let liquidities: Vec<Uint128> = tokens().idx.liquidity.range(deps.as_ref().storage, None, None, Order::Descending).map(|val| val.unwrap().1.last_liquidity).collect();
+        assert_eq!(liquidities, [3_000_000_u128, 300_000, 200_000, 100_000, 100_000, 20_000, 400].map(Uint128::new).to_vec());
 
-        println!("2: {:#?}", prices);
+        let prices: Vec<Decimal> = tokens().idx.price.range(deps.as_ref().storage, None, None, Order::Ascending).map(|val| val.unwrap().1.last_price).collect();
+        assert_eq!(prices, ["0.1", "0.1", "0.2", "0.3", "0.3", "2", "10"].map(|val| val.into_decimal()).to_vec());
 
+        // Pagination with start_after
         let limit = 2;
         let mut start_after = None;
         let mut data = vec![];
 
-        #[allow(while_true)]
-        while true {
-            let prices: Vec<(String, Decimal)> = tokens().idx.price.range(deps.as_ref().storage, None, start_after.clone(), Order::Descending)
-            .take(limit).map(|val| (val.map(|val| (val.1.main_token.denom, val.1.last_price)).unwrap())).collect();
+        loop {
+            let page: Vec<(String, Decimal)> = tokens().idx.price.range(deps.as_ref().storage, None, start_after.clone(), Order::Descending)
+                .take(limit).map(|val| val.map(|val| (val.1.main_token.denom, val.1.last_price)).unwrap()).collect();
 
-            data.extend(prices.clone());
+            data.extend(page.clone());
 
-            if prices.len() == limit {
-                start_after = Some(Bound::exclusive((prices.last().unclone().1.to_string(), prices.last().unwrap().0.clone())));
+            if page.len() == limit {
+                let (denom, price) = page.last().unwrap().clone();
+                start_after = Some(Bound::exclusive((price.atomics().u128(), denom)));
             } else {
                 break;
             }
         }
 
-        for (denom, price) in data {
-            println!("denom: {} price: {}", denom, price);
-        }      
-
+        assert_eq!(
+            data.into_iter().map(|val| val.0).collect::<Vec<_>>(),
+            [7, 6, 4, 3, 5, 2, 1].map(|index| format!("flambe_{index}_denom")).to_vec()
+        );
     }
 }
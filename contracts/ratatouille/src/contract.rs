@@ -0,0 +1,96 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+
+use rhaki_cw_plus::traits::IntoBinaryResult;
+
+use crate::error::ContractError;
+use crate::execute::{claim, end_game, play, refund};
+use crate::query::{
+    qy_game_info, qy_leaderboard, qy_player_burned_amount, qy_prize, qy_total_burned, qy_winner,
+};
+use crate::state::{CONFIG, PRIZE_CLAIMED, STATUS, TOTAL_BURNED};
+use ratatouille_pkg::ratatouille::definitions::{Config, Status};
+use ratatouille_pkg::ratatouille::msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let prize = rhaki_cw_plus::asset::only_one_coin(&info.funds, None)?;
+
+    if msg.burn_target.is_zero() {
+        return Err(StdError::generic_err("Burn target can't be 0").into());
+    }
+
+    if msg.ticket_price.is_zero() {
+        return Err(StdError::generic_err("Ticket price can't be 0").into());
+    }
+
+    if msg.duration == 0 {
+        return Err(StdError::generic_err("Duration can't be 0").into());
+    }
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        creator: deps.api.addr_validate(&msg.creator)?,
+        game_id: msg.game_id,
+        factory_address: deps.api.addr_validate(&msg.factory_address)?,
+        burner_address: deps.api.addr_validate(&msg.burner_address)?,
+        burn_target: msg.burn_target,
+        ticket_price: msg.ticket_price,
+        ticket_denom: msg.ticket_denom,
+        prize: prize.amount,
+        prize_denom: prize.denom,
+        end_date: env.block.time.seconds() + msg.duration,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    STATUS.save(deps.storage, &Status::OPEN)?;
+    TOTAL_BURNED.save(deps.storage, &Uint128::zero())?;
+    PRIZE_CLAIMED.save(deps.storage, &false)?;
+
+    Ok(Response::new()
+        .add_attribute("ratatouille", "start.cooking")
+        .add_attribute("game_id", config.game_id)
+        .add_attribute("end_date", config.end_date.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Play {} => play(deps, env, info),
+        ExecuteMsg::Claim {} => claim(deps, info),
+        ExecuteMsg::EndGame { winner } => end_game(deps, info, winner),
+        ExecuteMsg::Refund {} => refund(deps, env, info),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GameInfo {} => qy_game_info(deps, env).into_binary(),
+        QueryMsg::Winner {} => qy_winner(deps).into_binary(),
+        QueryMsg::PlayerBurnedAmount { player } => {
+            qy_player_burned_amount(deps, player).into_binary()
+        }
+        QueryMsg::TotalBurned {} => qy_total_burned(deps).into_binary(),
+        QueryMsg::Prize {} => qy_prize(deps).into_binary(),
+        QueryMsg::Leaderboard { limit } => qy_leaderboard(deps, limit).into_binary(),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
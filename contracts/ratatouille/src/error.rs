@@ -0,0 +1,38 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Game Closed")]
+    GameClosed {},
+
+    #[error("Game Expired")]
+    GameExpired {},
+
+    #[error("Game not Expired")]
+    GameNotExpired {},
+
+    #[error("Burn Target not reached")]
+    BurnTargetNotReached {},
+
+    #[error("Burn Target reached")]
+    BurnTargetReached {},
+
+    #[error("Invalid Ticket Amount - ticket price {ticket_price}")]
+    InvalidTicketAmount { ticket_price: Uint128 },
+
+    #[error("Not a Player: {player}")]
+    NotAPlayer { player: String },
+
+    #[error("Prize already Claimed")]
+    AlreadyClaimed {},
+
+    #[error("Nothing to Refund")]
+    NothingToRefund {},
+}
@@ -0,0 +1,224 @@
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg,
+};
+use ratatouille_pkg::{
+    ratatouille::definitions::{LeaderboardEntry, Status},
+    ratatouille_factory::msgs::ExecuteMsg as FactoryExecuteMsg,
+};
+use rhaki_cw_plus::{traits::Wrapper, wasm::WasmMsgBuilder};
+
+use crate::{
+    error::ContractError,
+    functions::load_status,
+    state::{players, CONFIG, PRIZE_CLAIMED, STATUS, TOTAL_BURNED, WINNER},
+};
+
+pub fn play(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if STATUS.load(deps.storage)? != Status::OPEN {
+        return Err(ContractError::GameClosed {});
+    }
+
+    if env.block.time.seconds() >= config.end_date {
+        return Err(ContractError::GameExpired {});
+    }
+
+    let tickets = rhaki_cw_plus::asset::only_one_coin(&info.funds, Some(config.ticket_denom))?;
+
+    if tickets.amount.is_zero() || !(tickets.amount % config.ticket_price).is_zero() {
+        return Err(ContractError::InvalidTicketAmount {
+            ticket_price: config.ticket_price,
+        });
+    }
+
+    let entry = players()
+        .may_load(deps.storage, info.sender.clone())?
+        .unwrap_or(LeaderboardEntry {
+            player: info.sender.clone(),
+            burned: Uint128::zero(),
+        });
+
+    let entry = LeaderboardEntry {
+        burned: entry.burned + tickets.amount,
+        ..entry
+    };
+
+    players().save(deps.storage, info.sender.clone(), &entry)?;
+
+    TOTAL_BURNED.update(deps.storage, |total| -> Result<_, ContractError> {
+        Ok(total + tickets.amount)
+    })?;
+
+    let msg_update_game = WasmMsg::build_execute(
+        &config.factory_address,
+        FactoryExecuteMsg::UpdateGame {
+            player: info.sender.clone(),
+            new_burn_amount: Some(entry.burned),
+            winner: None,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(msg_update_game)
+        .add_attribute("action", "play")
+        .add_attribute("player", info.sender)
+        .add_attribute(
+            "tickets",
+            (tickets.amount / config.ticket_price).to_string(),
+        )
+        .add_attribute("burned", tickets.amount)
+        .add_attribute("player_burned", entry.burned))
+}
+
+pub fn end_game(
+    deps: DepsMut,
+    info: MessageInfo,
+    winner: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if STATUS.load(deps.storage)? != Status::OPEN {
+        return Err(ContractError::GameClosed {});
+    }
+
+    let total_burned = TOTAL_BURNED.load(deps.storage)?;
+
+    if total_burned < config.burn_target {
+        return Err(ContractError::BurnTargetNotReached {});
+    }
+
+    let winner = deps.api.addr_validate(&winner)?;
+
+    if players().may_load(deps.storage, winner.clone())?.is_none() {
+        return Err(ContractError::NotAPlayer {
+            player: winner.to_string(),
+        });
+    }
+
+    STATUS.save(deps.storage, &Status::CLOSED)?;
+    WINNER.save(deps.storage, &winner)?;
+
+    // --- Burn all the tickets ---
+    let burn_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: config.burner_address.to_string(),
+        amount: vec![Coin::new(total_burned.u128(), config.ticket_denom)],
+    });
+
+    let msg_update_game = WasmMsg::build_execute(
+        &config.factory_address,
+        FactoryExecuteMsg::UpdateGame {
+            player: winner.clone(),
+            new_burn_amount: None,
+            winner: Some(winner.to_string()),
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(burn_msg)
+        .add_message(msg_update_game)
+        .add_attribute("action", "end_game")
+        .add_attribute("winner", winner)
+        .add_attribute("total_burned", total_burned))
+}
+
+pub fn claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if WINNER.may_load(deps.storage)? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if PRIZE_CLAIMED.load(deps.storage)? {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    PRIZE_CLAIMED.save(deps.storage, &true)?;
+
+    Ok(Response::new()
+        .add_messages(prize_msg(&info.sender, config.prize, config.prize_denom))
+        .add_attribute("action", "claim")
+        .add_attribute("winner", info.sender)
+        .add_attribute("prize", config.prize))
+}
+
+pub fn refund(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match load_status(deps.storage, &env, &config)? {
+        Status::UNFULFILLED => STATUS.save(deps.storage, &Status::UNFULFILLED)?,
+        Status::CLOSED => return Err(ContractError::GameClosed {}),
+        Status::OPEN => {
+            if env.block.time.seconds() < config.end_date {
+                return Err(ContractError::GameNotExpired {});
+            } else {
+                return Err(ContractError::BurnTargetReached {});
+            }
+        }
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    // --- Refund the tickets to the player ---
+    if let Some(entry) = players().may_load(deps.storage, info.sender.clone())? {
+        players().remove(deps.storage, info.sender.clone())?;
+
+        TOTAL_BURNED.update(deps.storage, |total| -> Result<_, ContractError> {
+            Ok(total - entry.burned)
+        })?;
+
+        msgs.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(entry.burned.u128(), config.ticket_denom.clone())],
+            }
+            .into(),
+        );
+
+        msgs.push(
+            WasmMsg::build_execute(
+                &config.factory_address,
+                FactoryExecuteMsg::UpdateGame {
+                    player: info.sender.clone(),
+                    new_burn_amount: Some(Uint128::zero()),
+                    winner: None,
+                },
+                vec![],
+            )?
+            .into(),
+        );
+    }
+
+    // --- Refund the prize to the creator ---
+    if info.sender == config.creator && !PRIZE_CLAIMED.load(deps.storage)? {
+        PRIZE_CLAIMED.save(deps.storage, &true)?;
+        msgs.extend(prize_msg(&info.sender, config.prize, config.prize_denom));
+    }
+
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToRefund {});
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "refund")
+        .add_attribute("user", info.sender))
+}
+
+fn prize_msg(to: &Addr, amount: Uint128, denom: String) -> Option<CosmosMsg> {
+    if amount > Uint128::zero() {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin::new(amount.u128(), denom)],
+        })
+        .wrap_some()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,19 @@
+use cosmwasm_std::{Env, StdResult, Storage};
+use ratatouille_pkg::ratatouille::definitions::{Config, Status};
+
+use crate::state::{STATUS, TOTAL_BURNED};
+
+/// Load the stored `Status`, returning `Status::UNFULFILLED` if the game is still `OPEN`
+/// but the `end_date` is passed without reaching the `burn_target`.
+pub fn load_status(storage: &dyn Storage, env: &Env, config: &Config) -> StdResult<Status> {
+    let status = STATUS.load(storage)?;
+
+    if status == Status::OPEN
+        && env.block.time.seconds() >= config.end_date
+        && TOTAL_BURNED.load(storage)? < config.burn_target
+    {
+        return Ok(Status::UNFULFILLED);
+    }
+
+    Ok(status)
+}
@@ -0,0 +1,6 @@
+pub mod contract;
+mod error;
+mod execute;
+mod functions;
+mod query;
+mod state;
@@ -0,0 +1,84 @@
+use std::cmp::min;
+
+use cosmwasm_std::{Deps, Env, Order, StdResult, Uint128};
+use ratatouille_pkg::ratatouille::definitions::{
+    GameInfoResponse, LeaderboardEntry, PlayerBurnResponse, PrizeResponse, TotalBurnedResponse,
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::{
+    functions::load_status,
+    state::{players, CONFIG, TOTAL_BURNED, WINNER},
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn qy_game_info(deps: Deps, env: Env) -> StdResult<GameInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let status = load_status(deps.storage, &env, &config)?;
+
+    Ok(GameInfoResponse {
+        owner: config.owner.to_string(),
+        creator: config.creator.to_string(),
+        burner_address: config.burner_address.to_string(),
+        burn_target: config.burn_target,
+        ticket_price: config.ticket_price,
+        ticket_denom: config.ticket_denom,
+        prize: config.prize,
+        prize_denom: config.prize_denom,
+        end_date: config.end_date,
+        total_burned: TOTAL_BURNED.load(deps.storage)?,
+        winner: WINNER.may_load(deps.storage)?.map(|val| val.to_string()),
+        status: status.to_string(),
+    })
+}
+
+pub fn qy_winner(deps: Deps) -> StdResult<PlayerBurnResponse> {
+    let winner = WINNER.load(deps.storage)?;
+    let entry = players().load(deps.storage, winner)?;
+
+    Ok(PlayerBurnResponse {
+        player: entry.player.to_string(),
+        burned: entry.burned,
+    })
+}
+
+pub fn qy_player_burned_amount(deps: Deps, player: String) -> StdResult<PlayerBurnResponse> {
+    let player = player.into_addr(deps.api)?;
+
+    let burned = players()
+        .may_load(deps.storage, player.clone())?
+        .map(|entry| entry.burned)
+        .unwrap_or(Uint128::zero());
+
+    Ok(PlayerBurnResponse {
+        player: player.to_string(),
+        burned,
+    })
+}
+
+pub fn qy_total_burned(deps: Deps) -> StdResult<TotalBurnedResponse> {
+    Ok(TotalBurnedResponse {
+        total_burned: TOTAL_BURNED.load(deps.storage)?,
+    })
+}
+
+pub fn qy_prize(deps: Deps) -> StdResult<PrizeResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(PrizeResponse {
+        prize_amount: config.prize,
+        prize_denom: config.prize_denom,
+    })
+}
+
+pub fn qy_leaderboard(deps: Deps, limit: Option<u32>) -> StdResult<Vec<LeaderboardEntry>> {
+    players()
+        .idx
+        .burned
+        .range(deps.storage, None, None, Order::Descending)
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect()
+}
@@ -0,0 +1,26 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{index_list, IndexedMap, Item, MultiIndex};
+use ratatouille_pkg::ratatouille::definitions::{Config, LeaderboardEntry, Status};
+
+pub const CONFIG: Item<Config> = Item::new("config_key");
+pub const STATUS: Item<Status> = Item::new("status_key");
+pub const TOTAL_BURNED: Item<Uint128> = Item::new("total_burned_key");
+pub const WINNER: Item<Addr> = Item::new("winner_key");
+pub const PRIZE_CLAIMED: Item<bool> = Item::new("prize_claimed_key");
+
+#[index_list(LeaderboardEntry)]
+pub struct PlayerIndexes<'a> {
+    pub burned: MultiIndex<'a, u128, LeaderboardEntry, Addr>,
+}
+
+pub fn players<'a>() -> IndexedMap<'a, Addr, LeaderboardEntry, PlayerIndexes<'a>> {
+    let indexes = PlayerIndexes {
+        burned: MultiIndex::new(
+            |_, entry| entry.burned.u128(),
+            "players",
+            "players_by_burned",
+        ),
+    };
+
+    IndexedMap::new("players", indexes)
+}
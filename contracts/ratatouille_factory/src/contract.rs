@@ -0,0 +1,97 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+use rhaki_cw_plus::traits::IntoBinaryResult;
+
+use crate::error::ContractError;
+use crate::execute::{create_game, update_game};
+use crate::query::{
+    qy_all_games, qy_config, qy_game_info, qy_games_by_creator, qy_games_by_player,
+    qy_games_by_status, qy_players_by_game,
+};
+use crate::state::{CONFIG, COUNTER_GAME};
+
+use ratatouille_pkg::ratatouille_factory::definitions::Config;
+use ratatouille_pkg::ratatouille_factory::msgs::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        burner_address: deps.api.addr_validate(&msg.burner_address)?,
+        game_fee: msg.game_fee,
+        denom_fee: msg.denom_fee,
+        ticket_price: msg.ticket_price,
+        game_code_id: msg.game_code_id,
+    };
+
+    config.validate()?;
+
+    CONFIG.save(deps.storage, &config)?;
+    COUNTER_GAME.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new().add_attribute("ratatouille_factory", "start.cooking"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateGame {
+            burn_target,
+            end_date,
+        } => create_game(deps, env, info, burn_target, end_date),
+        ExecuteMsg::UpdateGame {
+            player,
+            new_burn_amount,
+            winner,
+        } => update_game(deps, env, info, player, new_burn_amount, winner),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => qy_config(deps).into_binary(),
+        QueryMsg::AllGames { start_after, limit } => {
+            qy_all_games(deps, env, start_after, limit).into_binary()
+        }
+        QueryMsg::GameInfo { address } => qy_game_info(deps, env, address).into_binary(),
+        QueryMsg::GamesByCreator {
+            creator,
+            start_after,
+            limit,
+        } => qy_games_by_creator(deps, env, creator, start_after, limit).into_binary(),
+        QueryMsg::GamesByStatus {
+            status,
+            start_after,
+            limit,
+        } => qy_games_by_status(deps, env, status, start_after, limit).into_binary(),
+        QueryMsg::GamesByPlayer {
+            player,
+            start_after,
+            limit,
+        } => qy_games_by_player(deps, player, start_after, limit).into_binary(),
+        QueryMsg::PlayersByGame {
+            game,
+            start_after,
+            limit,
+        } => qy_players_by_game(deps, game, start_after, limit).into_binary(),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
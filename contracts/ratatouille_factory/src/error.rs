@@ -0,0 +1,20 @@
+use cosmwasm_std::{Coin, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Insufficient Fee - requested {0} ")]
+    InsufficientFee(Coin),
+
+    #[error("Invalid Prize - exactly one coin has to be sent as prize")]
+    InvalidPrize {},
+
+    #[error("Invalid End Date: {end_date}")]
+    InvalidEndDate { end_date: u64 },
+}
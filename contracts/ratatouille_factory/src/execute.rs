@@ -0,0 +1,186 @@
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128};
+use ratatouille_pkg::{
+    ratatouille::{definitions::Status, msgs::InstantiateMsg as GameInstantiateMsg},
+    ratatouille_factory::definitions::{GameInfo, PositionInfo},
+};
+use rhaki_cw_plus::{
+    traits::{IntoAddr, IntoBinary},
+    wasm::build_instantiate_2,
+};
+
+use crate::{
+    helper::game_status,
+    state::{games, positions, CONFIG, COUNTER_GAME},
+    ContractError,
+};
+
+pub fn create_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    burn_target: Uint128,
+    end_date: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if end_date <= env.block.time.seconds() {
+        return Err(ContractError::InvalidEndDate { end_date });
+    }
+
+    // --- Split the funds between game fee and prize ---
+    let fee = Coin::new(config.game_fee.u128(), config.denom_fee.clone());
+
+    let mut prize: Vec<Coin> = vec![];
+    let mut fee_paid = false;
+
+    for coin in info.funds {
+        if coin.denom == fee.denom {
+            if coin.amount < fee.amount {
+                return Err(ContractError::InsufficientFee(fee));
+            }
+
+            fee_paid = true;
+
+            if coin.amount > fee.amount {
+                prize.push(Coin::new((coin.amount - fee.amount).u128(), coin.denom));
+            }
+        } else if coin.amount > Uint128::zero() {
+            prize.push(coin);
+        }
+    }
+
+    if !fee_paid && fee.amount > Uint128::zero() {
+        return Err(ContractError::InsufficientFee(fee));
+    }
+
+    if prize.len() != 1 {
+        return Err(ContractError::InvalidPrize {});
+    }
+
+    let prize = prize.remove(0);
+
+    let game_id = COUNTER_GAME.load(deps.storage)?;
+
+    let (game_init, game_address) = build_instantiate_2(
+        deps.as_ref(),
+        &env.contract.address,
+        game_id.into_binary()?,
+        Some(config.owner.to_string()),
+        config.game_code_id,
+        GameInstantiateMsg {
+            owner: config.owner.to_string(),
+            game_id,
+            creator: info.sender.to_string(),
+            factory_address: env.contract.address.to_string(),
+            burner_address: config.burner_address.to_string(),
+            burn_target,
+            ticket_price: config.ticket_price,
+            ticket_denom: config.denom_fee.clone(),
+            duration: end_date - env.block.time.seconds(),
+        },
+        vec![prize.clone()],
+        "Ratatouille start.cooking".to_string(),
+    )?;
+
+    let msg_fee = if fee.amount > Uint128::zero() {
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: config.burner_address.to_string(),
+            amount: vec![fee],
+        }))
+    } else {
+        None
+    };
+
+    games().save(
+        deps.storage,
+        game_address.clone(),
+        &GameInfo {
+            creator: info.sender,
+            address: game_address.clone(),
+            game_id,
+            burn_target,
+            current_burn: Uint128::zero(),
+            prize: prize.amount,
+            prize_denom: prize.denom,
+            end_date,
+            winner: None,
+            status: Status::OPEN.to_string(),
+        },
+    )?;
+
+    COUNTER_GAME.save(deps.storage, &(game_id + Uint128::one()))?;
+
+    Ok(Response::new()
+        .add_message(game_init)
+        .add_messages(msg_fee)
+        .add_attribute("action", "create_game")
+        .add_attribute("game_id", game_id)
+        .add_attribute("game_addr", game_address))
+}
+
+pub fn update_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    player: Addr,
+    new_burn_amount: Option<Uint128>,
+    winner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut game = games()
+        .may_load(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    // Persist the UNFULFILLED status as soon as the game notifies after the end_date
+    game.status = game_status(&game, &env);
+
+    if let Some(new_burn_amount) = new_burn_amount {
+        let key = (game.address.clone(), player.clone());
+
+        let old_burn_amount = positions()
+            .may_load(deps.storage, key.clone())?
+            .map(|position| position.burn_amount)
+            .unwrap_or_default();
+
+        game.current_burn = game.current_burn - old_burn_amount + new_burn_amount;
+
+        if new_burn_amount.is_zero() {
+            positions().remove(deps.storage, key)?;
+        } else {
+            positions().save(
+                deps.storage,
+                key,
+                &PositionInfo {
+                    player: player.clone(),
+                    game: game.address.clone(),
+                    burn_amount: new_burn_amount,
+                    winner: false,
+                },
+            )?;
+        }
+    }
+
+    if let Some(winner) = winner {
+        let winner = winner.into_addr(deps.api)?;
+
+        positions().update(
+            deps.storage,
+            (game.address.clone(), winner.clone()),
+            |position| -> Result<_, ContractError> {
+                let mut position = position.ok_or(ContractError::Unauthorized {})?;
+                position.winner = true;
+                Ok(position)
+            },
+        )?;
+
+        game.winner = Some(winner);
+        game.status = Status::CLOSED.to_string();
+    }
+
+    games().save(deps.storage, game.address.clone(), &game)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_game")
+        .add_attribute("game_addr", game.address)
+        .add_attribute("player", player)
+        .add_attribute("status", game.status))
+}
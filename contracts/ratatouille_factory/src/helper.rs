@@ -0,0 +1,17 @@
+use cosmwasm_std::Env;
+use ratatouille_pkg::{
+    ratatouille::definitions::Status, ratatouille_factory::definitions::GameInfo,
+};
+
+/// Return the status of the game, considering as `UNFULFILLED` an `OPEN` game
+/// whose `end_date` is passed without reaching the `burn_target`.
+pub fn game_status(game: &GameInfo, env: &Env) -> String {
+    if game.status == Status::OPEN.to_string()
+        && env.block.time.seconds() >= game.end_date
+        && game.current_burn < game.burn_target
+    {
+        Status::UNFULFILLED.to_string()
+    } else {
+        game.status.clone()
+    }
+}
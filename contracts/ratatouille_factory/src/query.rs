@@ -0,0 +1,172 @@
+use std::cmp::min;
+
+use cosmwasm_std::{Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+use ratatouille_pkg::{
+    ratatouille::definitions::Status,
+    ratatouille_factory::definitions::{Config, GameInfo, PositionInfo},
+};
+use rhaki_cw_plus::{
+    storage::multi_index::{get_items, get_multi_index_values, multi_map_value},
+    traits::IntoAddr,
+};
+
+use crate::{
+    helper::game_status,
+    state::{games, positions, CONFIG},
+};
+
+pub fn qy_config(deps: Deps) -> StdResult<Config> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(config)
+}
+
+pub fn qy_all_games(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<GameInfo>> {
+    get_items(
+        deps.storage,
+        games(),
+        Order::Descending,
+        limit,
+        start_after.map(|val| val.into_addr(deps.api)).transpose()?,
+        multi_map_value,
+    )
+    .map(|games| with_status(games, &env))
+}
+
+pub fn qy_game_info(deps: Deps, env: Env, address: String) -> StdResult<GameInfo> {
+    let mut game = games().load(deps.storage, address.into_addr(deps.api)?)?;
+    game.status = game_status(&game, &env);
+    Ok(game)
+}
+
+pub fn qy_games_by_creator(
+    deps: Deps,
+    env: Env,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<GameInfo>> {
+    get_multi_index_values(
+        deps.storage,
+        creator.into_addr(deps.api)?,
+        games().idx.creator,
+        Order::Descending,
+        start_after.map(|val| val.into_addr(deps.api)).transpose()?,
+        limit,
+        multi_map_value,
+    )
+    .map(|games| with_status(games, &env))
+}
+
+pub fn qy_games_by_status(
+    deps: Deps,
+    env: Env,
+    status: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<GameInfo>> {
+    let start_after = start_after.map(|val| val.into_addr(deps.api)).transpose()?;
+    let limit = min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize;
+
+    // The expired games are stored as OPEN until they notify the factory
+    let stored_statuses = if status == Status::UNFULFILLED.to_string() {
+        vec![Status::OPEN.to_string(), status.clone()]
+    } else {
+        vec![status.clone()]
+    };
+
+    let mut games = stored_statuses
+        .into_iter()
+        .map(|stored_status| {
+            games()
+                .idx
+                .status
+                .prefix(stored_status)
+                .range(
+                    deps.storage,
+                    None,
+                    start_after.clone().map(Bound::exclusive),
+                    Order::Descending,
+                )
+                .map(|item| {
+                    item.map(|(_, mut game)| {
+                        game.status = game_status(&game, &env);
+                        game
+                    })
+                })
+                .filter(|item| item.as_ref().map_or(true, |game| game.status == status))
+                .take(limit)
+                .collect::<StdResult<Vec<GameInfo>>>()
+        })
+        .collect::<StdResult<Vec<_>>>()?
+        .concat();
+
+    games.sort_by(|a, b| b.address.cmp(&a.address));
+    games.truncate(limit);
+
+    Ok(games)
+}
+
+pub fn qy_games_by_player(
+    deps: Deps,
+    player: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PositionInfo>> {
+    let player = player.into_addr(deps.api)?;
+
+    let start_after = start_after
+        .map(|game| -> StdResult<_> { Ok((game.into_addr(deps.api)?, player.clone())) })
+        .transpose()?;
+
+    get_multi_index_values(
+        deps.storage,
+        player,
+        positions().idx.player,
+        Order::Descending,
+        start_after,
+        limit,
+        multi_map_value,
+    )
+}
+
+pub fn qy_players_by_game(
+    deps: Deps,
+    game: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PositionInfo>> {
+    let start_after = start_after
+        .map(|player| player.into_addr(deps.api))
+        .transpose()?;
+
+    positions()
+        .prefix(game.into_addr(deps.api)?)
+        .range(
+            deps.storage,
+            None,
+            start_after.map(Bound::exclusive),
+            Order::Descending,
+        )
+        .take(min(MAX_LIMIT, limit.unwrap_or(DEFAULT_LIMIT)) as usize)
+        .map(|item| item.map(|val| val.1))
+        .collect()
+}
+
+fn with_status(games: Vec<GameInfo>, env: &Env) -> Vec<GameInfo> {
+    games
+        .into_iter()
+        .map(|mut game| {
+            game.status = game_status(&game, env);
+            game
+        })
+        .collect()
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
@@ -0,0 +1,39 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{index_list, IndexedMap, Item, MultiIndex};
+use ratatouille_pkg::ratatouille_factory::definitions::{Config, GameInfo, PositionInfo};
+
+pub const CONFIG: Item<Config> = Item::new("config_key");
+pub const COUNTER_GAME: Item<Uint128> = Item::new("counter_game_key");
+
+#[index_list(GameInfo)]
+pub struct GameInfoIndexes<'a> {
+    pub status: MultiIndex<'a, String, GameInfo, Addr>,
+    pub creator: MultiIndex<'a, Addr, GameInfo, Addr>,
+}
+
+pub fn games<'a>() -> IndexedMap<'a, Addr, GameInfo, GameInfoIndexes<'a>> {
+    let indexes = GameInfoIndexes {
+        status: MultiIndex::new(|_, game| game.status.clone(), "games", "games_by_status"),
+        creator: MultiIndex::new(|_, game| game.creator.clone(), "games", "games_by_creator"),
+    };
+
+    IndexedMap::new("games", indexes)
+}
+
+#[index_list(PositionInfo)]
+pub struct PositionInfoIndexes<'a> {
+    pub player: MultiIndex<'a, Addr, PositionInfo, (Addr, Addr)>,
+}
+
+/// Player positions, keyed by `(game, player)`
+pub fn positions<'a>() -> IndexedMap<'a, (Addr, Addr), PositionInfo, PositionInfoIndexes<'a>> {
+    let indexes = PositionInfoIndexes {
+        player: MultiIndex::new(
+            |_, position| position.player.clone(),
+            "positions",
+            "positions_by_player",
+        ),
+    };
+
+    IndexedMap::new("positions", indexes)
+}
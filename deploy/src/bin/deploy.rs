@@ -10,8 +10,8 @@ async fn main() {
 
     let mut c = Data::read_data_from_input().unwrap();
 
-    let mut grpc = GrpcClient::new(&c.chain_info.grpc).await.unwrap();
-    let mut wallet = deploy_create_wallet(&mut grpc, &c.chain_info)
+    let grpc = GrpcClient::new(&c.chain_info.grpc).await.unwrap();
+    let mut wallet = deploy_create_wallet(&grpc, &c.chain_info)
         .await
         .unwrap();
 
@@ -1,5 +1,7 @@
 use cosmwasm_std::Coin;
-use ratatouille_pkg::flambe_factory::definitions::{FlambeSetting, ProtocolTokensInfoCreation};
+use ratatouille_pkg::flambe_factory::definitions::{
+    CookieEmission, FeeSplit, FlambeSetting, LpFeesSplit, ProtocolTokensInfoCreation,
+};
 use rhaki_cw_plus::deploy::{
     cosmos_grpc_client::{Decimal, Uint128},
     Deploier,
@@ -32,15 +34,23 @@ pub struct Addresses {
 
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct Variables {
+    pub auto_graduation: Option<bool>,
     pub burner_addr: Option<String>,
     pub cook_token: Option<ProtocolTokensInfoCreation>,
     pub cookie_token: Option<ProtocolTokensInfoCreation>,
+    pub cookie_emission: Option<CookieEmission>,
     pub cookie_ratio: Option<Decimal>,
+    pub cookie_staking: Option<String>,
+    pub cookie_sell_ratio: Option<Decimal>,
     pub cookie_owner_reward: Option<Uint128>,
     pub fee_collector: Option<String>,
+    pub fee_split: Option<FeeSplit>,
     pub flambe_code_id: Option<u64>,
     pub flambe_fee_creation: Option<Coin>,
     pub flambe_settings: Option<Vec<FlambeSetting>>,
+    pub guardian: Option<String>,
+    pub keeper_bounty: Option<Decimal>,
+    pub lp_fees_split: Option<LpFeesSplit>,
     pub owner: Option<String>,
     pub swap_fee: Option<Decimal>,
 }
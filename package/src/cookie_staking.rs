@@ -0,0 +1,111 @@
+pub mod msgs {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+    use cosmwasm_std::{Coin, Uint128};
+
+    use super::definitions::{Config, Staker};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub owner: String,
+        /// Flambe factory, its `cookie_token` is the bonded denom and
+        /// the `pair_denom` of its flambe settings are the reward denoms
+        pub factory: String,
+        pub unbonding_period: u64,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        /// Bond the cookies sent
+        Bond {},
+        /// Start the unbonding of `amount` cookies
+        Unbond {
+            amount: Uint128,
+        },
+        /// Withdraw the cookies whose unbonding period is over
+        Withdraw {},
+        /// Claim the pending rewards
+        Claim {},
+        /// Distribute the rewards received since the last distribution to the stakers
+        Distribute {},
+        UpdateConfig {
+            unbonding_period: Option<u64>,
+        },
+        /// Send the whole balance of a denom that is neither bonded nor distributed as reward,
+        /// to `recipient` or to the owner
+        Sweep {
+            denom: String,
+            recipient: Option<String>,
+        },
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(Config)]
+        Config {},
+        #[returns(Staker)]
+        Staker { address: String },
+        /// Rewards claimable by `address`, including the ones not distributed yet
+        #[returns(Vec<Coin>)]
+        PendingRewards { address: String },
+        #[returns(Uint128)]
+        TotalBonded {},
+    }
+
+    #[cw_serde]
+    pub struct MigrateMsg {}
+}
+
+pub mod definitions {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, Decimal256, StdError, StdResult, Uint128};
+
+    #[cw_serde]
+    pub struct Config {
+        pub owner: Addr,
+        pub factory: Addr,
+        pub cookie_denom: String,
+        /// Seconds before the unbonded cookies can be withdrawn
+        pub unbonding_period: u64,
+    }
+
+    impl Config {
+        pub fn validate(&self) -> StdResult<()> {
+            if self.unbonding_period == 0 {
+                return Err(StdError::generic_err("Unbonding period can't be 0"));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cw_serde]
+    #[derive(Default)]
+    pub struct Staker {
+        pub bonded: Uint128,
+        pub unbondings: Vec<Unbonding>,
+    }
+
+    #[cw_serde]
+    pub struct Unbonding {
+        pub amount: Uint128,
+        pub release_at: u64,
+    }
+
+    /// Rewards distributed for each bonded cookie
+    #[cw_serde]
+    pub struct RewardIndex {
+        pub denom: String,
+        pub index: Decimal256,
+        /// Rewards held by the contract and already distributed
+        pub distributed: Uint128,
+    }
+
+    #[cw_serde]
+    #[derive(Default)]
+    pub struct StakerReward {
+        /// Value of the reward index at the last update of the staker
+        pub index: Decimal256,
+        pub pending: Uint128,
+    }
+}
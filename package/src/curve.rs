@@ -0,0 +1,688 @@
+use std::cmp::min;
+
+use cosmwasm_std::{Decimal, Decimal256, StdError, StdResult, Uint128, Uint256};
+
+use crate::{flambe::definitions::Config, flambe_factory::definitions::CurveType};
+
+const LN_2: Decimal256 = Decimal256::raw(693_147_180_559_945_309);
+
+/// Max unit adjustments applied to the inverse of a curve to round in its favour
+const MAX_ROUNDING_STEPS: u8 = 10;
+
+/// Balances of the flambe used by the curves
+#[derive(Clone, Debug)]
+pub struct CurveState {
+    /// Main token held by the flambe, the supply not sold yet
+    pub main_amount: Uint256,
+    /// Pair token held by the flambe, excluding the keeper bounty
+    pub pair_amount: Uint256,
+    pub initial_supply: Uint256,
+    pub initial_price: Decimal256,
+    pub virtual_reserve: Uint256,
+}
+
+impl CurveState {
+    pub fn new(config: &Config, main_amount: Uint128, pair_amount: Uint128) -> CurveState {
+        CurveState {
+            main_amount: main_amount.into(),
+            pair_amount: pair_amount.into(),
+            initial_supply: config.flambe_setting.initial_supply.into(),
+            initial_price: config.flambe_setting.initial_price.into(),
+            virtual_reserve: config.virtual_reserve.into(),
+        }
+    }
+
+    pub fn sold(&self) -> Uint256 {
+        self.initial_supply.saturating_sub(self.main_amount)
+    }
+
+    pub fn after_buy(&self, pair_in: Uint256, main_out: Uint256) -> StdResult<CurveState> {
+        Ok(CurveState {
+            main_amount: self.main_amount.checked_sub(main_out)?,
+            pair_amount: self.pair_amount + pair_in,
+            ..self.clone()
+        })
+    }
+
+    pub fn after_sell(&self, main_in: Uint256, pair_out: Uint256) -> StdResult<CurveState> {
+        Ok(CurveState {
+            main_amount: self.main_amount + main_in,
+            pair_amount: self.pair_amount.checked_sub(pair_out)?,
+            ..self.clone()
+        })
+    }
+}
+
+/// Shared interface of the bonding curves.
+/// The outputs are rounded down and the inputs up, in favour of the curve
+pub trait BondingCurve {
+    /// Marginal price of the main token in pair
+    fn price(&self, state: &CurveState) -> StdResult<Decimal256>;
+
+    /// Main amount returned for `pair_in`
+    fn buy_out(&self, state: &CurveState, pair_in: Uint256) -> StdResult<Uint256>;
+
+    /// Pair amount returned for `main_in`
+    fn sell_out(&self, state: &CurveState, main_in: Uint256) -> StdResult<Uint256>;
+
+    /// Pair amount needed to receive `main_out`, inverse of `buy_out`
+    fn buy_in(&self, state: &CurveState, main_out: Uint256) -> StdResult<Uint256>;
+
+    /// Main amount needed to receive `pair_out`, inverse of `sell_out`
+    fn sell_in(&self, state: &CurveState, pair_out: Uint256) -> StdResult<Uint256>;
+}
+
+impl BondingCurve for CurveType {
+    fn price(&self, state: &CurveState) -> StdResult<Decimal256> {
+        as_curve(self).price(state)
+    }
+
+    fn buy_out(&self, state: &CurveState, pair_in: Uint256) -> StdResult<Uint256> {
+        as_curve(self).buy_out(state, pair_in)
+    }
+
+    fn sell_out(&self, state: &CurveState, main_in: Uint256) -> StdResult<Uint256> {
+        as_curve(self).sell_out(state, main_in)
+    }
+
+    fn buy_in(&self, state: &CurveState, main_out: Uint256) -> StdResult<Uint256> {
+        as_curve(self).buy_in(state, main_out)
+    }
+
+    fn sell_in(&self, state: &CurveState, pair_out: Uint256) -> StdResult<Uint256> {
+        as_curve(self).sell_in(state, pair_out)
+    }
+}
+
+fn as_curve(curve: &CurveType) -> Box<dyn BondingCurve + '_> {
+    match curve {
+        CurveType::ConstantProductVirtual => Box::new(ConstantProduct),
+        CurveType::Linear { final_price } => Box::new(Linear {
+            final_price: (*final_price).into(),
+        }),
+        CurveType::Exponential { final_price } => Box::new(Exponential {
+            final_price: (*final_price).into(),
+        }),
+        CurveType::Step { steps } => Box::new(Step {
+            steps: steps
+                .iter()
+                .map(|step| (step.from.into(), step.price.into()))
+                .collect(),
+        }),
+    }
+}
+
+/// Constant product between the main balance and the pair balance plus the virtual reserve
+struct ConstantProduct;
+
+impl ConstantProduct {
+    fn swap_out(offer_qta: Uint256, ask_qta: Uint256, amount: Uint256) -> Uint256 {
+        (Decimal256::from_ratio(ask_qta, 1u8)
+            - Decimal256::from_ratio(offer_qta * ask_qta, offer_qta + amount))
+            * Uint256::from(1u8)
+    }
+
+    fn swap_in(offer_qta: Uint256, ask_qta: Uint256, amount: Uint256) -> StdResult<Uint256> {
+        if amount >= ask_qta {
+            return Err(invalid_amount_out(amount));
+        }
+
+        div_ceil(offer_qta * amount, ask_qta - amount)
+    }
+}
+
+impl BondingCurve for ConstantProduct {
+    fn price(&self, state: &CurveState) -> StdResult<Decimal256> {
+        Decimal256::checked_from_ratio(state.pair_amount + state.virtual_reserve, state.main_amount)
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    }
+
+    fn buy_out(&self, state: &CurveState, pair_in: Uint256) -> StdResult<Uint256> {
+        Ok(Self::swap_out(
+            state.pair_amount + state.virtual_reserve,
+            state.main_amount,
+            pair_in,
+        ))
+    }
+
+    fn sell_out(&self, state: &CurveState, main_in: Uint256) -> StdResult<Uint256> {
+        Ok(Self::swap_out(
+            state.main_amount,
+            state.pair_amount + state.virtual_reserve,
+            main_in,
+        ))
+    }
+
+    fn buy_in(&self, state: &CurveState, main_out: Uint256) -> StdResult<Uint256> {
+        Self::swap_in(
+            state.pair_amount + state.virtual_reserve,
+            state.main_amount,
+            main_out,
+        )
+    }
+
+    fn sell_in(&self, state: &CurveState, pair_out: Uint256) -> StdResult<Uint256> {
+        Self::swap_in(
+            state.main_amount,
+            state.pair_amount + state.virtual_reserve,
+            pair_out,
+        )
+    }
+}
+
+/// Curve defined by the price of the sold supply.
+/// The pair reserve needed for a sold supply is the integral of the price, its `cost`
+trait CostCurve {
+    fn spot_price(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256>;
+
+    fn cost(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256>;
+
+    /// Inverse of `cost`, not necessarily exact
+    fn sold_at_cost(&self, state: &CurveState, cost: Decimal256) -> StdResult<Decimal256>;
+}
+
+impl<T: CostCurve> BondingCurve for T {
+    fn price(&self, state: &CurveState) -> StdResult<Decimal256> {
+        self.spot_price(state, to_decimal(state.sold()))
+    }
+
+    fn buy_out(&self, state: &CurveState, pair_in: Uint256) -> StdResult<Uint256> {
+        let sold = state.sold();
+        let cost = self.cost(state, to_decimal(sold))?;
+
+        let sold_after = self.sold_at_cost(state, cost + to_decimal(pair_in))?;
+
+        let mut main_out = min(
+            sold_after.to_uint_floor().saturating_sub(sold),
+            state.main_amount,
+        );
+
+        for _ in 0..MAX_ROUNDING_STEPS {
+            if main_out.is_zero()
+                || self.cost(state, to_decimal(sold + main_out))? - cost <= to_decimal(pair_in)
+            {
+                return Ok(main_out);
+            }
+
+            main_out -= Uint256::one();
+        }
+
+        Err(StdError::generic_err("Curve inversion not converged"))
+    }
+
+    fn sell_out(&self, state: &CurveState, main_in: Uint256) -> StdResult<Uint256> {
+        let sold = state.sold();
+
+        if main_in > sold {
+            return Err(StdError::generic_err(format!(
+                "Invalid sell amount: {main_in}"
+            )));
+        }
+
+        Ok(
+            (self.cost(state, to_decimal(sold))? - self.cost(state, to_decimal(sold - main_in))?)
+                .to_uint_floor(),
+        )
+    }
+
+    fn buy_in(&self, state: &CurveState, main_out: Uint256) -> StdResult<Uint256> {
+        // Unlike the constant product, the whole supply can be bought
+        if main_out > state.main_amount {
+            return Err(invalid_amount_out(main_out));
+        }
+
+        let sold = state.sold();
+
+        Ok(
+            (self.cost(state, to_decimal(sold + main_out))?
+                - self.cost(state, to_decimal(sold))?)
+            .to_uint_ceil(),
+        )
+    }
+
+    fn sell_in(&self, state: &CurveState, pair_out: Uint256) -> StdResult<Uint256> {
+        let sold = state.sold();
+        let cost = self.cost(state, to_decimal(sold))?;
+
+        if to_decimal(pair_out) >= cost {
+            return Err(invalid_amount_out(pair_out));
+        }
+
+        let sold_after = self.sold_at_cost(state, cost - to_decimal(pair_out))?;
+
+        let mut main_in = sold.saturating_sub(sold_after.to_uint_ceil());
+
+        for _ in 0..MAX_ROUNDING_STEPS {
+            if main_in <= sold
+                && cost - self.cost(state, to_decimal(sold - main_in))? >= to_decimal(pair_out)
+            {
+                return Ok(main_in);
+            }
+
+            main_in += Uint256::one();
+        }
+
+        Err(StdError::generic_err("Curve inversion not converged"))
+    }
+}
+
+/// Price from `initial_price` to `final_price` linearly with the sold supply
+struct Linear {
+    final_price: Decimal256,
+}
+
+impl Linear {
+    /// Price increase for each unit of sold supply, multiplied by the initial supply
+    fn slope(&self, state: &CurveState) -> Decimal256 {
+        self.final_price - state.initial_price
+    }
+}
+
+impl CostCurve for Linear {
+    fn spot_price(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        Ok(state.initial_price + self.slope(state) * sold / to_decimal(state.initial_supply))
+    }
+
+    fn cost(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        Ok(state.initial_price * sold
+            + self.slope(state) * sold * sold
+                / (to_decimal(state.initial_supply) * Decimal256::from_ratio(2u8, 1u8)))
+    }
+
+    fn sold_at_cost(&self, state: &CurveState, cost: Decimal256) -> StdResult<Decimal256> {
+        let slope = self.slope(state);
+
+        if slope.is_zero() {
+            return Ok(cost / state.initial_price);
+        }
+
+        let supply = to_decimal(state.initial_supply);
+
+        // Positive root of slope / (2 * supply) * sold^2 + initial_price * sold - cost
+        let discriminant = state.initial_price * state.initial_price
+            + Decimal256::from_ratio(2u8, 1u8) * slope * cost / supply;
+
+        Ok((discriminant.sqrt() - state.initial_price) * supply / slope)
+    }
+}
+
+/// Price from `initial_price` to `final_price` exponentially with the sold supply
+struct Exponential {
+    final_price: Decimal256,
+}
+
+impl Exponential {
+    /// Growth rate of the price over the ratio of the sold supply
+    fn rate(&self, state: &CurveState) -> StdResult<Decimal256> {
+        ln(self.final_price / state.initial_price)
+    }
+}
+
+impl CostCurve for Exponential {
+    fn spot_price(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        let ratio = sold / to_decimal(state.initial_supply);
+        Ok(state.initial_price * exp(self.rate(state)? * ratio)?)
+    }
+
+    fn cost(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        let rate = self.rate(state)?;
+        let supply = to_decimal(state.initial_supply);
+
+        Ok(state.initial_price * supply * (exp(rate * sold / supply)? - Decimal256::one()) / rate)
+    }
+
+    fn sold_at_cost(&self, state: &CurveState, cost: Decimal256) -> StdResult<Decimal256> {
+        let rate = self.rate(state)?;
+        let supply = to_decimal(state.initial_supply);
+
+        Ok(ln(Decimal256::one() + cost * rate / (state.initial_price * supply))? * supply / rate)
+    }
+}
+
+/// `initial_price` until the first step, then the price of the last reached step
+struct Step {
+    /// Ratio of the initial supply and price of each step
+    steps: Vec<(Decimal256, Decimal256)>,
+}
+
+impl Step {
+    /// Sold supply from which each price applies, including the `initial_price`
+    fn ranges(&self, state: &CurveState) -> Vec<(Decimal256, Decimal256)> {
+        let supply = to_decimal(state.initial_supply);
+
+        [(Decimal256::zero(), state.initial_price)]
+            .into_iter()
+            .chain(
+                self.steps
+                    .iter()
+                    .map(|(from, price)| (*from * supply, *price)),
+            )
+            .collect()
+    }
+}
+
+impl CostCurve for Step {
+    fn spot_price(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        Ok(self
+            .ranges(state)
+            .into_iter()
+            .take_while(|(from, _)| *from <= sold)
+            .last()
+            .map(|(_, price)| price)
+            .unwrap_or(state.initial_price))
+    }
+
+    fn cost(&self, state: &CurveState, sold: Decimal256) -> StdResult<Decimal256> {
+        let ranges = self.ranges(state);
+        let mut cost = Decimal256::zero();
+
+        for (index, (from, price)) in ranges.iter().enumerate() {
+            if sold <= *from {
+                break;
+            }
+
+            let to = ranges
+                .get(index + 1)
+                .map(|(to, _)| min(*to, sold))
+                .unwrap_or(sold);
+
+            cost += *price * (to - *from);
+        }
+
+        Ok(cost)
+    }
+
+    fn sold_at_cost(&self, state: &CurveState, cost: Decimal256) -> StdResult<Decimal256> {
+        let ranges = self.ranges(state);
+        let mut remaining = cost;
+
+        for (index, (from, price)) in ranges.iter().enumerate() {
+            let range_cost = ranges.get(index + 1).map(|(to, _)| *price * (*to - *from));
+
+            match range_cost {
+                Some(range_cost) if range_cost < remaining => remaining -= range_cost,
+                _ => return Ok(*from + remaining / *price),
+            }
+        }
+
+        Ok(Decimal256::zero())
+    }
+}
+
+/// Pair reserve of the curve once the whole supply is sold, `None` if the reserve is unbounded
+pub fn max_reserve(
+    curve: &CurveType,
+    initial_price: Decimal,
+    initial_supply: Uint128,
+) -> StdResult<Option<Uint256>> {
+    if *curve == CurveType::ConstantProductVirtual {
+        return Ok(None);
+    }
+
+    let sold_all = CurveState {
+        main_amount: Uint256::zero(),
+        pair_amount: Uint256::zero(),
+        initial_supply: initial_supply.into(),
+        initial_price: initial_price.into(),
+        virtual_reserve: Uint256::zero(),
+    };
+
+    curve.sell_out(&sold_all, sold_all.initial_supply).map(Some)
+}
+
+pub fn to_price(price: Decimal256) -> StdResult<Decimal> {
+    price
+        .try_into()
+        .map_err(|_| StdError::generic_err(format!("Price overflow: {price}")))
+}
+
+pub fn div_ceil(numerator: Uint256, denominator: Uint256) -> StdResult<Uint256> {
+    Ok(numerator
+        .checked_add(denominator)?
+        .checked_sub(Uint256::one())?
+        .checked_div(denominator)?)
+}
+
+fn invalid_amount_out(amount: Uint256) -> StdError {
+    StdError::generic_err(format!("Invalid amount out: {amount}"))
+}
+
+fn to_decimal(amount: Uint256) -> Decimal256 {
+    Decimal256::from_ratio(amount, 1u8)
+}
+
+/// `e^x`, reducing `x` by multiples of `ln(2)` before the taylor series
+fn exp(x: Decimal256) -> StdResult<Decimal256> {
+    let halvings = Uint128::try_from((x / LN_2).to_uint_floor())?;
+    let halvings = u32::try_from(halvings.u128())
+        .map_err(|_| StdError::generic_err(format!("Exp overflow: {x}")))?;
+
+    let reduced = x - LN_2 * Decimal256::from_ratio(halvings, 1u8);
+
+    let mut result = Decimal256::one();
+    let mut term = Decimal256::one();
+
+    for i in 1..40u32 {
+        term = term * reduced / Decimal256::from_ratio(i, 1u8);
+
+        if term.is_zero() {
+            break;
+        }
+
+        result += term;
+    }
+
+    Ok(result.checked_mul(Decimal256::from_ratio(2u8, 1u8).checked_pow(halvings)?)?)
+}
+
+/// `ln(x)` for `x >= 1`, with the `atanh` series after dividing `x` by powers of 2
+fn ln(x: Decimal256) -> StdResult<Decimal256> {
+    if x < Decimal256::one() {
+        return Err(StdError::generic_err(format!("Ln of {x} lower then 1")));
+    }
+
+    let two = Decimal256::from_ratio(2u8, 1u8);
+
+    let mut reduced = x;
+    let mut halvings = 0u32;
+
+    while reduced >= two {
+        reduced /= two;
+        halvings += 1;
+    }
+
+    let t = (reduced - Decimal256::one()) / (reduced + Decimal256::one());
+    let t_squared = t * t;
+
+    let mut sum = Decimal256::zero();
+    let mut term = t;
+
+    for i in 0..60u32 {
+        if term.is_zero() {
+            break;
+        }
+
+        sum += term / Decimal256::from_ratio(2 * i + 1, 1u8);
+        term *= t_squared;
+    }
+
+    Ok(two * sum + LN_2 * Decimal256::from_ratio(halvings, 1u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint256};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::flambe_factory::definitions::{CurveType, PriceStep};
+
+    use super::{BondingCurve, CurveState};
+
+    const SUPPLY: u128 = 1_000_000_000_000;
+    const MAX_TRADE: u128 = 20_000_000_000;
+
+    fn curves() -> Vec<CurveType> {
+        vec![
+            CurveType::ConstantProductVirtual,
+            CurveType::Linear {
+                final_price: Decimal::percent(150),
+            },
+            CurveType::Exponential {
+                final_price: Decimal::percent(1_000),
+            },
+            CurveType::Step {
+                steps: vec![
+                    PriceStep {
+                        from: Decimal::percent(5),
+                        price: Decimal::percent(20),
+                    },
+                    PriceStep {
+                        from: Decimal::percent(20),
+                        price: Decimal::percent(50),
+                    },
+                ],
+            },
+        ]
+    }
+
+    fn initial_state() -> CurveState {
+        CurveState {
+            main_amount: SUPPLY.into(),
+            pair_amount: Uint256::zero(),
+            initial_supply: SUPPLY.into(),
+            initial_price: Decimal256::from_ratio(1u8, 10u8),
+            virtual_reserve: (SUPPLY / 10).into(),
+        }
+    }
+
+    fn to_u128(amount: Uint256) -> u128 {
+        Uint128::try_from(amount).unwrap().u128()
+    }
+
+    /// Apply a random buy or sell to `state`
+    fn random_trade(rng: &mut StdRng, curve: &CurveType, state: &CurveState) -> CurveState {
+        let sold = to_u128(state.sold());
+
+        if sold == 0 || rng.gen_bool(0.6) {
+            let pair_in = Uint256::from(rng.gen_range(1..MAX_TRADE));
+            let main_out = curve.buy_out(state, pair_in).unwrap();
+            state.after_buy(pair_in, main_out).unwrap()
+        } else {
+            let main_in = Uint256::from(rng.gen_range(1..=sold));
+            let pair_out = curve.sell_out(state, main_in).unwrap();
+            state.after_sell(main_in, pair_out).unwrap()
+        }
+    }
+
+    #[test]
+    fn value_conservation() {
+        for curve in curves() {
+            let mut rng = StdRng::seed_from_u64(1);
+            let mut state = initial_state();
+
+            for _ in 0..300 {
+                state = random_trade(&mut rng, &curve, &state);
+
+                // The reserve always covers the sell of the whole sold supply
+                let sold = state.sold();
+                if !sold.is_zero() {
+                    assert!(
+                        curve.sell_out(&state, sold).unwrap() <= state.pair_amount,
+                        "{curve:?}"
+                    );
+                }
+            }
+
+            // Selling everything back leaves no debt
+            let sold = state.sold();
+            let pair_out = curve.sell_out(&state, sold).unwrap();
+            let state = state.after_sell(sold, pair_out).unwrap();
+            assert_eq!(state.main_amount, Uint256::from(SUPPLY));
+        }
+    }
+
+    #[test]
+    fn round_trip_is_not_profitable() {
+        for curve in curves() {
+            let mut rng = StdRng::seed_from_u64(2);
+            let mut state = initial_state();
+
+            for _ in 0..100 {
+                state = random_trade(&mut rng, &curve, &state);
+
+                let pair_in = Uint256::from(rng.gen_range(1..MAX_TRADE));
+                let main_out = curve.buy_out(&state, pair_in).unwrap();
+                let after_buy = state.after_buy(pair_in, main_out).unwrap();
+
+                assert!(
+                    curve.sell_out(&after_buy, main_out).unwrap() <= pair_in,
+                    "{curve:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inverses_round_in_favour_of_the_curve() {
+        for curve in curves() {
+            let mut rng = StdRng::seed_from_u64(3);
+            let mut state = initial_state();
+
+            for _ in 0..100 {
+                state = random_trade(&mut rng, &curve, &state);
+
+                let pair_in = Uint256::from(rng.gen_range(1..MAX_TRADE));
+                let main_out = curve.buy_out(&state, pair_in).unwrap();
+                assert!(
+                    curve.buy_in(&state, main_out).unwrap() <= pair_in,
+                    "{curve:?}"
+                );
+
+                let main_out = Uint256::from(rng.gen_range(1..to_u128(state.main_amount) / 10));
+                let pair_in = curve.buy_in(&state, main_out).unwrap();
+                assert!(
+                    curve.buy_out(&state, pair_in).unwrap() >= main_out,
+                    "{curve:?}"
+                );
+
+                let max_pair_out = to_u128(curve.sell_out(&state, state.sold()).unwrap());
+                if max_pair_out > 1 {
+                    let pair_out = Uint256::from(rng.gen_range(1..max_pair_out));
+                    let main_in = curve.sell_in(&state, pair_out).unwrap();
+                    assert!(main_in <= state.sold(), "{curve:?}");
+                    assert!(
+                        curve.sell_out(&state, main_in).unwrap() >= pair_out,
+                        "{curve:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prices() {
+        let state = initial_state();
+
+        for curve in curves() {
+            assert_eq!(
+                curve.price(&state).unwrap(),
+                state.initial_price,
+                "{curve:?}"
+            );
+        }
+
+        let sold_all = CurveState {
+            main_amount: Uint256::zero(),
+            ..initial_state()
+        };
+
+        let price = |curve: &CurveType| curve.price(&sold_all).unwrap();
+
+        assert_eq!(price(&curves()[1]), Decimal256::from_ratio(3u8, 2u8));
+        assert!(
+            price(&curves()[2]).abs_diff(Decimal256::from_ratio(10u8, 1u8))
+                < Decimal256::from_ratio(1u8, 1_000_000_000u128)
+        );
+        assert_eq!(price(&curves()[3]), Decimal256::from_ratio(1u8, 2u8));
+    }
+}
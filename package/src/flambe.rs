@@ -2,17 +2,27 @@ pub mod msgs {
     use cosmwasm_schema::{cw_serde, QueryResponses};
     use cosmwasm_std::{Decimal, Uint128};
 
-    use crate::flambe_factory::{definitions::FlambeSetting, msgs::EndFlambeMsg};
+    use crate::flambe_factory::{
+        definitions::{FeeSplit, FlambeSetting},
+        msgs::EndFlambeSwapMsg,
+    };
 
-    use super::definitions::{Config, FlambeInfo};
+    use super::definitions::{
+        Candle, CandleResolution, Config, FlambeInfo, Holder, PositionLockResponse,
+        ReverseSwapResponse, Trade, TwapResponse,
+    };
 
     #[cw_serde]
     pub struct InstantiateMsg {
+        pub auto_graduation: bool,
         pub burner_addr: String,
+        pub cookie_staking: Option<String>,
         pub creator: String,
         pub factory: String,
         pub fee_collector: String,
+        pub fee_split: FeeSplit,
         pub flambe_setting: FlambeSetting,
+        pub keeper_bounty: Decimal,
         pub owner: String,
         pub swap_fee: Decimal,
     }
@@ -23,9 +33,44 @@ pub mod msgs {
             min_amount_out: Uint128,
             user: String,
         },
+        /// Receive exactly `amount_out`, refunding the unused part of the offer
+        SwapExactOut {
+            amount_out: Uint128,
+            max_amount_in: Uint128,
+            user: String,
+        },
 
-        Deploy(EndFlambeMsg),
-        CheckToPending,
+        Deploy {
+            keeper: String,
+            swap_msg: Option<EndFlambeSwapMsg>,
+        },
+        CheckToPending {
+            keeper: String,
+        },
+        /// Internal, create the pool of the graduation target once the pool creation fee is paid
+        CreatePool {},
+        SetPaused {
+            paused: bool,
+        },
+        UpdateOwner {
+            owner: String,
+        },
+        /// Fail an `OPEN` flambe after its deadline, the keeper bounty is paid to the sender
+        Fail {},
+        /// Return flambe tokens to a `FAILED` flambe for a pro-rata share of the pair reserve
+        Refund {},
+        /// Collect the spread rewards and incentives of the graduated CL position,
+        /// split by the `lp_fees_split` of the factory
+        CollectFees {},
+        /// Send the accrued creator share of the swap fees to the creator
+        ClaimCreatorFees {},
+        /// Update the values copied from the factory config at the instantiation.
+        /// Callable only by the factory
+        UpdateConfig {
+            burner_addr: Option<String>,
+            fee_collector: Option<String>,
+            swap_fee: Option<Decimal>,
+        },
     }
 
     #[cw_serde]
@@ -37,10 +82,57 @@ pub mod msgs {
         Info {},
         #[returns(SimulateResponse)]
         Simulate { offer: String, amount: Uint128 },
+        /// Simulate the offer needed to receive `amount` of `ask`
+        #[returns(ReverseSwapResponse)]
+        SimulateReverse { ask: String, amount: Uint128 },
+        /// Last trades, from the most recent
+        #[returns(Vec<Trade>)]
+        Trades {
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        },
+        #[returns(Vec<Trade>)]
+        TradesByUser {
+            user: String,
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        },
+        /// Candles between `from` and `to` (capped to the block time), in ascending order.
+        /// The buckets without trades are filled from the last close
+        #[returns(Vec<Candle>)]
+        Candles {
+            resolution: CandleResolution,
+            from: u64,
+            to: u64,
+            limit: Option<u32>,
+        },
+        /// Time weighted average price of the last `window_seconds`, up to `MAX_TWAP_WINDOW`
+        #[returns(TwapResponse)]
+        Twap { window_seconds: u64 },
+        /// Holders by net bought balance, from the largest
+        #[returns(Vec<Holder>)]
+        Holders {
+            limit: Option<u32>,
+            start_after: Option<(Uint128, String)>,
+        },
+        /// Ownership of the CL position created at graduation
+        #[returns(PositionLockResponse)]
+        PositionLock {},
     }
 
     #[cw_serde]
-    pub struct MigrateMsg {}
+    pub enum MigrateMsg {
+        /// Only update the stored contract version
+        Version {},
+        /// Set the `Config` fields introduced after the flambe instantiation
+        MigrateConfig {
+            auto_graduation: Option<bool>,
+            keeper_bounty: Option<Decimal>,
+        },
+        /// Store the CL pool and position ids of a flambe graduated before they were saved,
+        /// required to collect the LP fees. Flambes without a CL position are left untouched
+        BackfillPosition {},
+    }
 
     #[cw_serde]
     pub struct SimulateResponse {
@@ -51,20 +143,44 @@ pub mod msgs {
 }
 
 pub mod definitions {
+    use std::fmt::{self, Display, Formatter};
+
     use cosmwasm_schema::cw_serde;
-    use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+    use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128};
 
-    use crate::flambe_factory::definitions::FlambeSetting;
+    use crate::flambe_factory::definitions::{FeeSplit, FlambeSetting};
 
     #[cw_serde]
     pub struct Config {
+        /// Pair amount retained from the swap fees, paid to the keeper on graduation
+        #[serde(default)]
+        pub accrued_bounty: Uint128,
+        /// Pair amount retained from the swap fees, claimable by the creator
+        #[serde(default)]
+        pub accrued_creator_fees: Uint128,
+        #[serde(default)]
+        pub auto_graduation: bool,
         pub burner_addr: Addr,
+        /// Main amount bought and sent to the burner by the swap fees
+        #[serde(default)]
+        pub burned: Uint128,
+        #[serde(default)]
+        pub cookie_staking: Option<Addr>,
         pub creator: Addr,
         pub factory: Addr,
         pub fee_collector: Addr,
+        #[serde(default)]
+        pub fee_split: FeeSplit,
         pub flambe_setting: FlambeSetting,
+        #[serde(default)]
+        pub keeper_bounty: Decimal,
+        /// Flambe creation time, start of the launch window
+        #[serde(default)]
+        pub launched_at: u64,
         pub main_denom: String,
         pub owner: Addr,
+        #[serde(default)]
+        pub paused: bool,
         pub status: FlambeStatus,
         pub swap_fee: Decimal,
         pub virtual_reserve: Uint128,
@@ -78,6 +194,9 @@ pub mod definitions {
         pub pair_amount: Uint128,
         pub pair_denom: String,
         pub price: Decimal,
+        /// Number of users with a positive net bought balance
+        #[serde(default)]
+        pub holders: u64,
     }
     #[cw_serde]
     pub struct PriceResponse {
@@ -91,14 +210,17 @@ pub mod definitions {
         OPEN,
         PENDING,
         CLOSED,
+        /// Deadline passed without graduation
+        FAILED,
     }
 
-    impl ToString for FlambeStatus {
-        fn to_string(&self) -> String {
+    impl Display for FlambeStatus {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             match self {
-                FlambeStatus::OPEN => String::from("OPEN"),
-                FlambeStatus::PENDING => String::from("PENDING"),
-                FlambeStatus::CLOSED => String::from("CLOSED"),
+                FlambeStatus::OPEN => write!(f, "OPEN"),
+                FlambeStatus::PENDING => write!(f, "PENDING"),
+                FlambeStatus::CLOSED => write!(f, "CLOSED"),
+                FlambeStatus::FAILED => write!(f, "FAILED"),
             }
         }
     }
@@ -109,4 +231,116 @@ pub mod definitions {
         pub swap_fee: Coin,
         pub price_impact: Decimal,
     }
+
+    #[cw_serde]
+    pub enum TradeSide {
+        Buy,
+        Sell,
+    }
+
+    #[cw_serde]
+    pub struct Trade {
+        pub id: u64,
+        pub user: Addr,
+        pub side: TradeSide,
+        pub offer_amount: Uint128,
+        pub return_amount: Uint128,
+        /// Always in the pair denom
+        pub fee_amount: Uint128,
+        /// Price of the flambe after the trade
+        pub price: Decimal,
+        pub block_height: u64,
+        pub timestamp: u64,
+    }
+
+    /// Net amount of flambe token bought from the curve by `address`.
+    /// Transfers and trades outside the flambe are not tracked
+    #[cw_serde]
+    pub struct Holder {
+        pub address: Addr,
+        pub balance: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct PositionLockResponse {
+        pub pool_id: u64,
+        pub position_id: u64,
+        /// Owner of the position in the concentrated liquidity module
+        pub owner: String,
+        /// Admin of the flambe contract, cleared once the flambe is `CLOSED`
+        pub admin: Option<String>,
+        /// The position is owned by the flambe, which has no withdraw path and can't be migrated
+        pub locked: bool,
+    }
+
+    #[cw_serde]
+    #[derive(Copy)]
+    pub enum CandleResolution {
+        M1,
+        M15,
+        H1,
+        D1,
+    }
+
+    impl CandleResolution {
+        pub const ALL: [CandleResolution; 4] = [
+            CandleResolution::M1,
+            CandleResolution::M15,
+            CandleResolution::H1,
+            CandleResolution::D1,
+        ];
+
+        pub fn seconds(&self) -> u64 {
+            match self {
+                CandleResolution::M1 => 60,
+                CandleResolution::M15 => 15 * 60,
+                CandleResolution::H1 => 60 * 60,
+                CandleResolution::D1 => 24 * 60 * 60,
+            }
+        }
+
+        /// Start of the bucket containing `time`
+        pub fn bucket_start(&self, time: u64) -> u64 {
+            time - time % self.seconds()
+        }
+    }
+
+    #[cw_serde]
+    pub struct Candle {
+        /// Start of the bucket, in seconds
+        pub start: u64,
+        pub open: Decimal,
+        pub high: Decimal,
+        pub low: Decimal,
+        pub close: Decimal,
+        /// Traded amount in the pair denom, fee included
+        pub volume: Uint128,
+    }
+
+    /// Longest window supported by the twap
+    pub const MAX_TWAP_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+    #[cw_serde]
+    pub struct PriceObservation {
+        pub timestamp: u64,
+        /// Sum of `price * seconds` since the first observation
+        pub cumulative_price: Decimal256,
+        /// Price from `timestamp` until the next observation
+        pub price: Decimal,
+    }
+
+    #[cw_serde]
+    pub struct TwapResponse {
+        pub price: Decimal,
+        pub start: u64,
+        pub end: u64,
+    }
+
+    #[cw_serde]
+    pub struct ReverseSwapResponse {
+        /// Amount to offer to receive the requested amount, fee included
+        pub offer_amount: Coin,
+        pub swap_fee: Coin,
+        pub price_impact: Decimal,
+    }
 }
@@ -1,37 +1,51 @@
 pub mod msgs {
     use cosmwasm_schema::{cw_serde, QueryResponses};
-    use cosmwasm_std::{Coin, Decimal, Uint128};
+    use cosmwasm_std::{Coin, Decimal, Order, Uint128};
     use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountOutRoute;
 
-    use crate::flambe::definitions::FlambeStatus;
+    use crate::flambe::{definitions::FlambeStatus, msgs::MigrateMsg as FlambeMigrateMsg};
 
     use super::definitions::{
-        Config, CreateFactoryInput, FlambeFullInfo, FlambeSetting, PoolCreationInfo,
+        Config, CookieEmission, CreateFactoryInput, FeeSplit, FlambeFullInfo, FlambeSetting,
+        LpFeesSplit, OwnershipProposal, PoolCreationInfo, PropagatedField,
         ProtocolTokensInfoCreation,
     };
 
     #[cw_serde]
     pub struct InstantiateMsg {
+        pub auto_graduation: bool,
         pub burner: String,
         pub cook_token: ProtocolTokensInfoCreation,
+        pub cookie_staking: Option<String>,
         pub cookie_token: ProtocolTokensInfoCreation,
+        pub cookie_emission: Option<CookieEmission>,
         pub cookie_ratio: Decimal,
+        pub cookie_sell_ratio: Decimal,
         pub cookie_owner_reward: Uint128,
         pub fee_collector: String,
+        pub fee_split: FeeSplit,
         pub flambe_code_id: u64,
         pub flambe_fee_creation: Option<Coin>,
         pub flambe_settings: Vec<FlambeSetting>,
+        pub guardian: Option<String>,
+        pub keeper_bounty: Decimal,
+        pub lp_fees_split: LpFeesSplit,
         pub owner: String,
         pub swap_fee: Decimal,
     }
 
     #[cw_serde]
+    #[allow(clippy::large_enum_variant)]
     pub enum ExecuteMsg {
         UpdatedConfig(UpdateConfigMsg),
         CreateFactory {
             subdenom: String,
             flambe_threshold_index: u8,
             msg: CreateFactoryInput,
+            /// Amount of `pair_denom` swapped for the creator as soon as the flambe is instantiated.
+            /// Defaults to the `pair_denom` sent over the creation fees
+            initial_buy: Option<Uint128>,
+            min_amount_out: Option<Uint128>,
         },
         UpdateFlambeStatus {
             status: FlambeStatus,
@@ -42,6 +56,54 @@ pub mod msgs {
             flambe_addr: String,
             min_amount_out: Uint128,
         },
+        /// Receive exactly `amount_out`, the unused part of the funds is refunded
+        SwapExactOut {
+            flambe_addr: String,
+            amount_out: Uint128,
+            max_amount_in: Uint128,
+        },
+        /// Pause the whole protocol or, if `flambe_addr` is provided, a single flambe.
+        /// Callable by the owner or the guardian
+        Pause {
+            flambe_addr: Option<String>,
+        },
+        /// Unpause the whole protocol or a single flambe. Callable only by the owner
+        Unpause {
+            flambe_addr: Option<String>,
+        },
+        /// Propose a new owner, that has to accept the ownership within `expires_in` seconds
+        ProposeNewOwner {
+            owner: String,
+            expires_in: u64,
+        },
+        AcceptOwnership {},
+        CancelOwnershipProposal {},
+        /// Set the factory owner as owner of the existing flambes, paginated over the flambe denoms
+        PropagateOwner {
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+        /// Migrate the existing flambes to `code_id` with `msg`, paginated over the flambe denoms.
+        /// `code_id` is used for the new flambes from the first page.
+        ///
+        /// The factory has to be the admin of the flambes. The flambes created before the factory
+        /// became their admin have the factory owner as admin and are skipped, until the owner
+        /// moves them to the factory with a `WasmMsg::UpdateAdmin`.
+        MigrateFlambes {
+            code_id: u64,
+            msg: FlambeMigrateMsg,
+            limit: Option<u32>,
+            start_after: Option<String>,
+        },
+        /// Push the current value of `fields` to the existing flambes, the swap fee only to the
+        /// `OPEN` and `FAILED` ones, paginated over the flambe denoms.
+        /// Flambes not running the current `flambe_code_id` are skipped and listed in the `skipped`
+        /// attribute, `MigrateFlambes` must be run first
+        PropagateConfig {
+            fields: Vec<PropagatedField>,
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
     }
 
     #[cw_serde]
@@ -50,6 +112,9 @@ pub mod msgs {
         #[returns(Config)]
         Config {},
 
+        #[returns(Option<OwnershipProposal>)]
+        OwnershipProposal {},
+
         #[returns(FlambeFullInfo)]
         Flambe { filter: FlambeFilter },
 
@@ -58,6 +123,25 @@ pub mod msgs {
             limit: Option<u32>,
             filter: FlambesFilter,
         },
+
+        /// Current cookie rewards rates and remaining budget
+        #[returns(CookieEmissionResponse)]
+        CookieEmission {},
+    }
+
+    #[cw_serde]
+    pub struct CookieEmissionResponse {
+        /// `None` outside of the emission schedule or if there is no schedule
+        pub epoch: Option<u64>,
+        /// Cookies rewarded for each unit of swap fee paid on buys
+        pub buy_ratio: Decimal,
+        /// Cookies rewarded for each unit of swap fee paid on sells
+        pub sell_ratio: Decimal,
+        /// Budget of the current epoch, `None` if there is no schedule
+        pub epoch_budget: Option<Uint128>,
+        /// Cookies that can still be rewarded, capped to the factory balance
+        pub remaining_budget: Uint128,
+        pub end_time: Option<u64>,
     }
 
     #[cw_serde]
@@ -69,18 +153,39 @@ pub mod msgs {
     }
 
     #[cw_serde]
-    pub struct MigrateMsg {}
+    pub enum MigrateMsg {
+        /// Only update the stored contract version
+        Version {},
+        /// Rebuild the `price` and `liquidity` indexes stored with the legacy stringed keys
+        RebuildIndexes {},
+        /// Set the `Config` fields introduced after the factory instantiation
+        MigrateConfig {
+            auto_graduation: Option<bool>,
+            guardian: Option<String>,
+            keeper_bounty: Option<Decimal>,
+        },
+    }
 
     #[cw_serde]
+    #[derive(Default)]
     pub struct UpdateConfigMsg {
+        pub auto_graduation: Option<bool>,
         pub burner: Option<String>,
+        pub cookie_emission: Option<CookieEmission>,
         pub cookie_ratio: Option<Decimal>,
+        pub cookie_sell_ratio: Option<Decimal>,
         pub cookie_owner_reward: Option<Uint128>,
+        pub cookie_staking: Option<String>,
         pub fee_collector: Option<String>,
+        pub fee_split: Option<FeeSplit>,
         pub flambe_code_id: Option<u64>,
         pub flambe_settings: Option<Vec<FlambeSetting>>,
-        pub owner: Option<String>,
+        pub guardian: Option<String>,
+        pub keeper_bounty: Option<Decimal>,
+        pub lp_fees_split: Option<LpFeesSplit>,
         pub pool_creation_info: Option<PoolCreationInfo>,
+        /// Revoke the guardian, can't be combined with `guardian`
+        pub remove_guardian: Option<bool>,
         pub swap_fee: Option<Decimal>,
     }
 
@@ -94,26 +199,49 @@ pub mod msgs {
     pub enum FlambesFilter {
         Empty {
             start_after: Option<String>,
+            order: Option<OrderBy>,
         },
         ByStatus {
             status: FlambeStatus,
             start_after: Option<String>,
+            order: Option<OrderBy>,
         },
         ByCreator {
             creator: String,
             start_after: Option<String>,
+            order: Option<OrderBy>,
         },
         ByPrice {
-            start_after: Option<(String, String)>,
+            start_after: Option<(Decimal, String)>,
+            order: Option<OrderBy>,
         },
         ByLiquidity {
-            start_after: Option<(String, String)>,
+            start_after: Option<(Uint128, String)>,
+            order: Option<OrderBy>,
         },
     }
 
+    #[cw_serde]
+    #[derive(Default)]
+    pub enum OrderBy {
+        Ascending,
+        #[default]
+        Descending,
+    }
+
+    impl From<OrderBy> for Order {
+        fn from(value: OrderBy) -> Self {
+            match value {
+                OrderBy::Ascending => Order::Ascending,
+                OrderBy::Descending => Order::Descending,
+            }
+        }
+    }
+
     #[cw_serde]
     pub struct EndFlambeMsg {
         pub flambe_address: String,
+        /// Only the owner can provide a `swap_msg`
         pub swap_msg: Option<EndFlambeSwapMsg>,
     }
 
@@ -126,34 +254,93 @@ pub mod msgs {
 }
 
 pub mod definitions {
+    use astroport::pair_concentrated::ConcentratedPoolParams;
     use cosmwasm_schema::cw_serde;
-    use cosmwasm_std::{Addr, Coin, Decimal, QuerierWrapper, StdError, StdResult, Uint128};
+    use cosmwasm_std::{
+        Addr, Api, Coin, Decimal, QuerierWrapper, StdError, StdResult, Uint128, Uint256,
+    };
     use osmosis_std::types::osmosis::poolmanager::v1beta1::ParamsRequest;
 
-    use crate::flambe::definitions::{FlambeInfo, FlambeStatus};
+    use crate::{
+        curve::max_reserve,
+        flambe::definitions::{FlambeInfo, FlambeStatus},
+    };
 
     #[cw_serde]
     pub struct Config {
+        /// Graduate the flambe as soon as it reaches the threshold, if no swap is needed to pay the pool creation fee
+        #[serde(default)]
+        pub auto_graduation: bool,
         pub burner: Addr,
         pub cook_token: ProtocolTokenInfo,
         pub cookie_token: ProtocolTokenInfo,
+        /// Schedule of the cookie rewards, without it they are only capped to the factory balance
+        #[serde(default)]
+        pub cookie_emission: Option<CookieEmission>,
+        /// Cookies rewarded for each unit of swap fee paid on buys
         pub cookie_ratio: Decimal,
+        /// Cookies rewarded for each unit of swap fee paid on sells
+        #[serde(default)]
+        pub cookie_sell_ratio: Decimal,
         pub cookie_owner_reward: Uint128,
+        /// Cookie staking contract receiving the `stakers` share of the swap fees
+        #[serde(default)]
+        pub cookie_staking: Option<Addr>,
         pub fee_collector: Addr,
+        /// Split of the swap fees, unless overridden by the flambe setting
+        #[serde(default)]
+        pub fee_split: FeeSplit,
         pub flambe_fee_creation: Option<Coin>,
         pub flambe_code_id: u64,
         pub flambe_settings: Vec<FlambeSetting>,
+        /// Can pause the protocol, but not unpause it
+        #[serde(default)]
+        pub guardian: Option<Addr>,
+        /// Share of the swap fees retained by each flambe and paid to whoever triggers its graduation
+        #[serde(default)]
+        pub keeper_bounty: Decimal,
+        /// Split of the fees collected from the graduated CL positions
+        #[serde(default)]
+        pub lp_fees_split: LpFeesSplit,
         pub owner: Addr,
+        #[serde(default)]
+        pub paused: bool,
         pub swap_fee: Decimal,
         pub counter_flambe: u64,
     }
 
     impl Config {
-        pub fn validate(&self, querier: QuerierWrapper) -> StdResult<()> {
+        pub fn validate(&self, api: &dyn Api, querier: QuerierWrapper) -> StdResult<()> {
             if self.swap_fee >= Decimal::one() {
                 return Err(StdError::generic_err("Swap fee can't be greater then 1"));
             }
 
+            if self.keeper_bounty > Decimal::one() {
+                return Err(StdError::generic_err(
+                    "Keeper bounty can't be greater then 1",
+                ));
+            }
+
+            if let Some(emission) = &self.cookie_emission {
+                if emission.epoch_duration == 0 {
+                    return Err(StdError::generic_err("Epoch duration can't be 0"));
+                }
+
+                if emission.end_time <= emission.start_time {
+                    return Err(StdError::generic_err(
+                        "End time must be greater then the start time",
+                    ));
+                }
+            }
+
+            if self.lp_fees_split.creator + self.lp_fees_split.protocol + self.lp_fees_split.burn
+                != Decimal::one()
+            {
+                return Err(StdError::generic_err("Lp fees split must sum to 1"));
+            }
+
+            self.fee_split.validate(&self.cookie_staking)?;
+
             let params = ParamsRequest {}.query(&querier)?;
 
             let authorized_quote_denoms = params
@@ -174,7 +361,130 @@ pub mod definitions {
                     return Err(StdError::generic_err("Threshold can't be 0"));
                 }
 
-                if !authorized_quote_denoms.contains(&setting.pair_denom) {
+                if setting.trades_retention > MAX_TRADES_RETENTION {
+                    return Err(StdError::generic_err(format!(
+                        "Trades retention can't be greater then {MAX_TRADES_RETENTION}"
+                    )));
+                }
+
+                if setting.max_buy_per_tx == Some(Uint128::zero()) {
+                    return Err(StdError::generic_err("Max buy per tx can't be 0"));
+                }
+
+                if let Some(max_holding) = setting.max_holding {
+                    if max_holding.is_zero() || max_holding > Decimal::one() {
+                        return Err(StdError::generic_err(
+                            "Max holding must be greater then 0 and lower or equal then 1",
+                        ));
+                    }
+                }
+
+                if let Some(fee_split) = &setting.fee_split {
+                    fee_split.validate(&self.cookie_staking)?;
+                }
+
+                if let Some(schedule) = &setting.fee_schedule {
+                    if schedule.initial_fee >= Decimal::one() {
+                        return Err(StdError::generic_err(
+                            "Initial fee can't be greater or equal then 1",
+                        ));
+                    }
+
+                    if schedule.initial_fee < self.swap_fee {
+                        return Err(StdError::generic_err(
+                            "Initial fee can't be lower then the swap fee",
+                        ));
+                    }
+
+                    if schedule.duration == 0 {
+                        return Err(StdError::generic_err("Fee schedule duration can't be 0"));
+                    }
+
+                    if schedule.decay == (FeeDecay::Step { steps: 0 }) {
+                        return Err(StdError::generic_err("Fee schedule steps can't be 0"));
+                    }
+                }
+
+                if setting.deadline == Some(0) {
+                    return Err(StdError::generic_err("Deadline can't be 0"));
+                }
+
+                if setting.launch_window > MAX_LAUNCH_WINDOW {
+                    return Err(StdError::generic_err(format!(
+                        "Launch window can't be greater then {MAX_LAUNCH_WINDOW}"
+                    )));
+                }
+
+                match &setting.curve {
+                    CurveType::ConstantProductVirtual => {}
+                    CurveType::Linear { final_price } => {
+                        if *final_price < setting.initial_price {
+                            return Err(StdError::generic_err(
+                                "Final price can't be lower then the initial price",
+                            ));
+                        }
+                    }
+                    CurveType::Exponential { final_price } => {
+                        if *final_price <= setting.initial_price {
+                            return Err(StdError::generic_err(
+                                "Final price must be greater then the initial price",
+                            ));
+                        }
+                    }
+                    CurveType::Step { steps } => {
+                        if steps.is_empty() {
+                            return Err(StdError::generic_err("Steps can't be empty"));
+                        }
+
+                        let mut last_from = Decimal::zero();
+
+                        for step in steps {
+                            if step.from <= last_from || step.from >= Decimal::one() {
+                                return Err(StdError::generic_err(
+                                    "Steps must be in ascending order between 0 and 1",
+                                ));
+                            }
+
+                            if step.price.is_zero() {
+                                return Err(StdError::generic_err("Step price can't be 0"));
+                            }
+
+                            last_from = step.from;
+                        }
+                    }
+                }
+
+                // The reserve of the bounded curves can't grow over the cost of the whole supply
+                if let Some(max_reserve) = max_reserve(
+                    &setting.curve,
+                    setting.initial_price,
+                    setting.initial_supply,
+                )? {
+                    if Uint256::from(setting.threshold) > max_reserve {
+                        return Err(StdError::generic_err(format!(
+                            "Threshold can't be greater then the curve reserve {max_reserve}"
+                        )));
+                    }
+                }
+
+                match &setting.graduation_target {
+                    GraduationTarget::OsmosisCL => {}
+                    GraduationTarget::OsmosisBalancer { swap_fee } => {
+                        if *swap_fee >= Decimal::one() {
+                            return Err(StdError::generic_err(
+                                "Balancer swap fee can't be greater or equal then 1",
+                            ));
+                        }
+                    }
+                    GraduationTarget::AstroportXyk { factory }
+                    | GraduationTarget::AstroportPcl { factory, .. } => {
+                        api.addr_validate(factory)?;
+                    }
+                }
+
+                if setting.graduation_target == GraduationTarget::OsmosisCL
+                    && !authorized_quote_denoms.contains(&setting.pair_denom)
+                {
                     return Err(StdError::generic_err(format!(
                         "Pair denom {} can't be used for create ConcentratedPool",
                         setting.pair_denom
@@ -202,6 +512,10 @@ pub mod definitions {
         }
     }
 
+    pub const DEFAULT_TRADES_RETENTION: u64 = 100;
+    pub const MAX_TRADES_RETENTION: u64 = 1_000;
+    pub const MAX_LAUNCH_WINDOW: u64 = 24 * 60 * 60;
+
     #[cw_serde]
     pub struct FlambeSetting {
         pub pair_denom: String,
@@ -209,6 +523,244 @@ pub mod definitions {
         pub initial_price: Decimal,
         pub initial_supply: Uint128,
         pub pool_creation_info: PoolCreationInfo,
+        /// Number of the last trades stored by the flambe, `0` disables the history
+        #[serde(default = "default_trades_retention")]
+        pub trades_retention: u64,
+        /// Max pair amount offered by a single buy during the launch window
+        #[serde(default)]
+        pub max_buy_per_tx: Option<Uint128>,
+        /// Max net bought balance of a wallet during the launch window, as a ratio of `initial_supply`
+        #[serde(default)]
+        pub max_holding: Option<Decimal>,
+        /// Seconds after the flambe creation during which the buy caps apply
+        #[serde(default)]
+        pub launch_window: u64,
+        #[serde(default)]
+        pub curve: CurveType,
+        /// Seconds after the flambe creation to reach the threshold.
+        /// Once passed the flambe can be failed and the holders refunded
+        #[serde(default)]
+        pub deadline: Option<u64>,
+        /// Pool receiving the liquidity of the flambe at graduation
+        #[serde(default)]
+        pub graduation_target: GraduationTarget,
+        /// Override of the factory `fee_split`
+        #[serde(default)]
+        pub fee_split: Option<FeeSplit>,
+        /// Swap fee decaying after the flambe creation, down to the flambe `swap_fee`
+        #[serde(default)]
+        pub fee_schedule: Option<FeeSchedule>,
+    }
+
+    /// Bonding curve used by the flambe, starting from `initial_price`.
+    /// The final prices are reached when the whole `initial_supply` is sold
+    #[cw_serde]
+    #[derive(Default)]
+    pub enum CurveType {
+        /// Constant product with a virtual pair reserve of `initial_supply * initial_price`
+        #[default]
+        ConstantProductVirtual,
+        Linear {
+            final_price: Decimal,
+        },
+        Exponential {
+            final_price: Decimal,
+        },
+        /// `initial_price` until the first step, then the price of the last reached step
+        Step {
+            steps: Vec<PriceStep>,
+        },
+    }
+
+    /// Pool created when the flambe graduates, the received LP is sent to the burner
+    #[cw_serde]
+    #[derive(Default)]
+    pub enum GraduationTarget {
+        /// Concentrated pool using `pool_creation_info`
+        #[default]
+        OsmosisCL,
+        OsmosisBalancer {
+            swap_fee: Decimal,
+        },
+        AstroportXyk {
+            factory: String,
+        },
+        /// `price_scale` is replaced by the price of the flambe at graduation
+        AstroportPcl {
+            factory: String,
+            params: ConcentratedPoolParams,
+        },
+    }
+
+    impl GraduationTarget {
+        /// The pool creation fee of the Osmosis pool manager is charged
+        pub fn is_osmosis(&self) -> bool {
+            matches!(
+                self,
+                GraduationTarget::OsmosisCL | GraduationTarget::OsmosisBalancer { .. }
+            )
+        }
+    }
+
+    #[cw_serde]
+    pub struct FeeSchedule {
+        /// Swap fee at the flambe creation
+        pub initial_fee: Decimal,
+        /// Seconds after the flambe creation to reach the floor
+        pub duration: u64,
+        pub decay: FeeDecay,
+    }
+
+    impl FeeSchedule {
+        /// Swap fee `elapsed` seconds after the flambe creation, never below `floor`
+        pub fn fee_at(&self, floor: Decimal, elapsed: u64) -> Decimal {
+            if elapsed >= self.duration || self.initial_fee <= floor {
+                return floor;
+            }
+
+            let progress = match self.decay {
+                FeeDecay::Linear => Decimal::from_ratio(elapsed, self.duration),
+                FeeDecay::Step { steps } => Decimal::from_ratio(
+                    elapsed as u128 * steps as u128 / self.duration as u128,
+                    steps,
+                ),
+            };
+
+            self.initial_fee - (self.initial_fee - floor) * progress
+        }
+    }
+
+    /// Config fields copied into the flambes at the instantiation
+    #[cw_serde]
+    pub enum PropagatedField {
+        Burner,
+        FeeCollector,
+        SwapFee,
+    }
+
+    #[cw_serde]
+    pub enum FeeDecay {
+        Linear,
+        /// The fee is lowered in `steps` equal steps over the duration
+        Step {
+            steps: u64,
+        },
+    }
+
+    #[cw_serde]
+    pub struct PriceStep {
+        /// Ratio of `initial_supply` sold from which `price` applies
+        pub from: Decimal,
+        pub price: Decimal,
+    }
+
+    fn default_trades_retention() -> u64 {
+        DEFAULT_TRADES_RETENTION
+    }
+
+    #[cw_serde]
+    pub struct CookieEmission {
+        pub start_time: u64,
+        pub epoch_duration: u64,
+        /// Max cookies rewarded in each epoch
+        pub epoch_budget: Uint128,
+        /// Epochs after which the budget and the cookie ratios are halved, `0` disables the halving
+        pub halving_epochs: u64,
+        /// No cookies are rewarded from `end_time`
+        pub end_time: u64,
+    }
+
+    impl CookieEmission {
+        pub fn epoch(&self, time: u64) -> Option<u64> {
+            if time < self.start_time || time >= self.end_time {
+                None
+            } else {
+                Some((time - self.start_time) / self.epoch_duration)
+            }
+        }
+
+        fn halvings(&self, epoch: u64) -> u32 {
+            epoch
+                .checked_div(self.halving_epochs)
+                .map(|halvings| halvings.min(u128::BITS as u64) as u32)
+                .unwrap_or_default()
+        }
+
+        pub fn epoch_budget(&self, epoch: u64) -> Uint128 {
+            Uint128::new(
+                self.epoch_budget
+                    .u128()
+                    .checked_shr(self.halvings(epoch))
+                    .unwrap_or_default(),
+            )
+        }
+
+        /// Factor applied to the cookie ratios during `epoch`
+        pub fn rate(&self, epoch: u64) -> Decimal {
+            1_u128
+                .checked_shl(self.halvings(epoch))
+                .map(|divisor| Decimal::from_ratio(1_u128, divisor))
+                .unwrap_or_default()
+        }
+    }
+
+    #[cw_serde]
+    pub struct LpFeesSplit {
+        pub creator: Decimal,
+        /// Sent to the `fee_collector`
+        pub protocol: Decimal,
+        /// Sent to the `burner`
+        pub burn: Decimal,
+    }
+
+    impl Default for LpFeesSplit {
+        fn default() -> Self {
+            LpFeesSplit {
+                creator: Decimal::zero(),
+                protocol: Decimal::one(),
+                burn: Decimal::zero(),
+            }
+        }
+    }
+
+    /// Split of the swap fees paid to a flambe, after the keeper bounty
+    #[cw_serde]
+    pub struct FeeSplit {
+        /// Accrued in the flambe and claimed by the creator
+        pub creator: Decimal,
+        /// Sent to the `fee_collector`
+        pub protocol: Decimal,
+        /// Used to buy the flambe token along the curve, sent to the burner
+        pub burn: Decimal,
+        /// Sent to the `cookie_staking`
+        pub stakers: Decimal,
+    }
+
+    impl Default for FeeSplit {
+        fn default() -> Self {
+            FeeSplit {
+                creator: Decimal::zero(),
+                protocol: Decimal::one(),
+                burn: Decimal::zero(),
+                stakers: Decimal::zero(),
+            }
+        }
+    }
+
+    impl FeeSplit {
+        pub fn validate(&self, cookie_staking: &Option<Addr>) -> StdResult<()> {
+            if self.creator + self.protocol + self.burn + self.stakers != Decimal::one() {
+                return Err(StdError::generic_err("Fee split must sum to 1"));
+            }
+
+            if !self.stakers.is_zero() && cookie_staking.is_none() {
+                return Err(StdError::generic_err(
+                    "Cookie staking is required for the stakers fee share",
+                ));
+            }
+
+            Ok(())
+        }
     }
 
     #[cw_serde]
@@ -223,6 +775,20 @@ pub mod definitions {
         pub uri_hash: String,
     }
 
+    #[cw_serde]
+    pub struct OwnershipProposal {
+        pub owner: Addr,
+        pub expires_at: u64,
+    }
+
+    #[cw_serde]
+    pub struct TmpInitialBuy {
+        pub creator: Addr,
+        pub flambe_address: Addr,
+        pub offer: Coin,
+        pub min_amount_out: Uint128,
+    }
+
     #[cw_serde]
     pub struct FlambeBaseInfo {
         pub creator: Addr,
@@ -1,3 +1,5 @@
+pub mod cookie_staking;
+pub mod curve;
 pub mod flambe;
 pub mod flambe_factory;
 pub mod ratatouille;
@@ -49,6 +49,8 @@ pub mod msgs {
 }
 
 pub mod definitions {
+    use std::fmt::{self, Display, Formatter};
+
     use cosmwasm_schema::cw_serde;
     use cosmwasm_std::{Addr, Uint128};
 
@@ -113,12 +115,12 @@ pub mod definitions {
         UNFULFILLED,
     }
 
-    impl ToString for Status {
-        fn to_string(&self) -> String {
+    impl Display for Status {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             match self {
-                Status::OPEN => String::from("OPEN"),
-                Status::CLOSED => String::from("CLOSED"),
-                Status::UNFULFILLED => String::from("UNFULFILLED"),
+                Status::OPEN => write!(f, "OPEN"),
+                Status::CLOSED => write!(f, "CLOSED"),
+                Status::UNFULFILLED => write!(f, "UNFULFILLED"),
             }
         }
     }
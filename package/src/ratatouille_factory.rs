@@ -71,7 +71,7 @@ pub mod msgs {
 
 pub mod definitions {
     use cosmwasm_schema::cw_serde;
-    use cosmwasm_std::{Addr, Uint128};
+    use cosmwasm_std::{Addr, StdError, StdResult, Uint128};
 
     #[cw_serde]
     pub struct Config {
@@ -83,6 +83,20 @@ pub mod definitions {
         pub game_code_id: u64,
     }
 
+    impl Config {
+        pub fn validate(&self) -> StdResult<()> {
+            if self.ticket_price == Uint128::zero() {
+                return Err(StdError::generic_err("Ticket price can't be 0"));
+            }
+
+            if self.denom_fee.is_empty() {
+                return Err(StdError::generic_err("Denom fee can't be empty"));
+            }
+
+            Ok(())
+        }
+    }
+
     #[cw_serde]
     pub struct GameInfo {
         pub creator: Addr,
@@ -0,0 +1,98 @@
+use cosmwasm_std::{Addr, Coin, Uint128};
+use ratatouille_pkg::{
+    cookie_staking::{
+        definitions::Staker,
+        msgs::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    },
+    flambe_factory::msgs::UpdateConfigMsg,
+};
+use rhaki_cw_plus::multi_test::helper::{
+    anyhow::Error as AnyError,
+    create_code,
+    cw_multi_test::{AppResponse, Executor},
+};
+
+use crate::{
+    flambe_factory::helpers::{_update_flambe_factory_config, startup, AppResult, Def},
+    helpers::OsmosisApp,
+};
+
+pub const UNBONDING_PERIOD: u64 = 86_400;
+
+/// Start the flambe factory and the cookie staking, set as the factory `fee_collector`
+pub fn startup_staking(def: &mut Def) -> (OsmosisApp, Addr) {
+    let mut app = startup(def);
+
+    let code_id = app.store_code(create_code(
+        cookie_staking::contract::instantiate,
+        cookie_staking::contract::execute,
+        cookie_staking::contract::query,
+    ));
+
+    let staking = app
+        .instantiate_contract(
+            code_id,
+            def.owner.clone(),
+            &InstantiateMsg {
+                owner: def.owner.to_string(),
+                factory: def.factory_address.clone().unwrap().to_string(),
+                unbonding_period: UNBONDING_PERIOD,
+            },
+            &[],
+            "Cookie Staking",
+            Some(def.owner.to_string()),
+        )
+        .unwrap();
+
+    _update_flambe_factory_config(
+        &mut app,
+        def,
+        UpdateConfigMsg {
+            fee_collector: Some(staking.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    def.fee_collector = staking.clone();
+
+    (app, staking)
+}
+
+pub fn run_staking(
+    app: &mut OsmosisApp,
+    sender: &Addr,
+    staking: &Addr,
+    msg: ExecuteMsg,
+    funds: Vec<Coin>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(sender.clone(), staking.clone(), &msg, &funds)
+}
+
+pub fn qy_staker(app: &OsmosisApp, staking: &Addr, address: &Addr) -> AppResult<Staker> {
+    Ok(app.wrap().query_wasm_smart(
+        staking,
+        &QueryMsg::Staker {
+            address: address.to_string(),
+        },
+    )?)
+}
+
+pub fn qy_pending_rewards(
+    app: &OsmosisApp,
+    staking: &Addr,
+    address: &Addr,
+) -> AppResult<Vec<Coin>> {
+    Ok(app.wrap().query_wasm_smart(
+        staking,
+        &QueryMsg::PendingRewards {
+            address: address.to_string(),
+        },
+    )?)
+}
+
+pub fn qy_total_bonded(app: &OsmosisApp, staking: &Addr) -> AppResult<Uint128> {
+    Ok(app
+        .wrap()
+        .query_wasm_smart(staking, &QueryMsg::TotalBonded {})?)
+}
@@ -0,0 +1,118 @@
+use cosmwasm_std::{Coin, Uint128};
+use ratatouille_pkg::{
+    cookie_staking::{definitions::Unbonding, msgs::ExecuteMsg},
+    flambe_factory::msgs::FlambeFilter,
+};
+use rhaki_cw_plus::{
+    asset::AssetInfoPrecisioned,
+    math::IntoDecimal,
+    multi_test::helper::{cw_multi_test::Executor, AppExt, Bench32AppExt, UnwrapError},
+    traits::Unclone,
+};
+
+use crate::flambe_factory::helpers::{
+    _qy_factory_config, create_input, creation_fees, parse_swap_output_from_response,
+    qy_factory_flambe, run_create_flambe, run_swap, Def,
+};
+
+use super::helpers::{
+    qy_pending_rewards, qy_staker, qy_total_bonded, run_staking, startup_staking, UNBONDING_PERIOD,
+};
+
+#[test]
+#[rustfmt::skip]
+fn stake_and_claim() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let (mut app, staking) = startup_staking(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let cookie = _qy_factory_config(&app, &def).cookie_token.denom;
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    app.send_tokens(def.owner.clone(), user_1.clone(), &[Coin::new(300_000, &cookie)]).unwrap();
+    app.send_tokens(def.owner.clone(), user_2.clone(), &[Coin::new(100_000, &cookie)]).unwrap();
+    app.mint(&user_1, osmo.to_asset(1_000u128.into_decimal()));
+
+    run_staking(&mut app, &user_1, &staking, ExecuteMsg::Bond {}, vec![Coin::new(1_000_000, "uosmo")]).unwrap_err_contains("Denom not match");
+    run_staking(&mut app, &user_1, &staking, ExecuteMsg::Bond {}, vec![Coin::new(300_000, &cookie)]).unwrap();
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Bond {}, vec![Coin::new(100_000, &cookie)]).unwrap();
+
+    assert_eq!(qy_total_bonded(&app, &staking).unwrap(), Uint128::new(400_000));
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_1).unwrap(), Vec::<Coin>::new());
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    // The creation and swap fees are shared pro-rata on the bonded cookies
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap();
+    let fee = app.qy_balance(&staking, &osmo).unwrap().amount_raw();
+
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_1).unwrap(), vec![Coin::new((fee * Uint128::new(3) / Uint128::new(4)).u128(), "uosmo")]);
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_2).unwrap(), vec![Coin::new((fee / Uint128::new(4)).u128(), "uosmo")]);
+
+    let osmo_before = app.qy_balance(&user_2, &osmo).unwrap().amount_raw();
+
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Claim {}, vec![]).unwrap();
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Claim {}, vec![]).unwrap_err_contains("Nothing to claim");
+
+    assert_eq!(app.qy_balance(&user_2, &osmo).unwrap().amount_raw(), osmo_before + fee / Uint128::new(4));
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_2).unwrap(), Vec::<Coin>::new());
+
+    // Unbonded cookies stop earning and are withdrawable after the unbonding period
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Unbond { amount: Uint128::new(100_001) }, vec![]).unwrap_err_contains("Insufficient Bonded");
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Unbond { amount: Uint128::new(100_000) }, vec![]).unwrap();
+
+    let release_at = app.block_info().time.seconds() + UNBONDING_PERIOD;
+
+    let staker = qy_staker(&app, &staking, &user_2).unwrap();
+    assert_eq!(staker.bonded, Uint128::zero());
+    assert_eq!(staker.unbondings, vec![Unbonding { amount: Uint128::new(100_000), release_at }]);
+
+    let swap = parse_swap_output_from_response(run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+    assert!(swap.fee.amount_raw() > Uint128::zero());
+
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_2).unwrap(), Vec::<Coin>::new());
+
+    // The rounding dust is left to the next distribution
+    let pending = qy_pending_rewards(&app, &staking, &user_1).unwrap()[0].amount;
+    assert_eq!(pending, fee * Uint128::new(3) / Uint128::new(4) + swap.fee.amount_raw() - Uint128::one());
+
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Withdraw {}, vec![]).unwrap_err_contains("Nothing to withdraw");
+
+    app.update_block(|block| block.time = block.time.plus_seconds(UNBONDING_PERIOD));
+
+    run_staking(&mut app, &user_2, &staking, ExecuteMsg::Withdraw {}, vec![]).unwrap();
+
+    assert_eq!(app.qy_balance(&user_2, &AssetInfoPrecisioned::native(&cookie, 6)).unwrap().amount_raw(), Uint128::new(100_000));
+    assert_eq!(qy_staker(&app, &staking, &user_2).unwrap().unbondings, vec![]);
+    assert_eq!(qy_total_bonded(&app, &staking).unwrap(), Uint128::new(300_000));
+
+    // Only the owner can update the unbonding period
+    run_staking(&mut app, &user_1, &staking, ExecuteMsg::UpdateConfig { unbonding_period: Some(10) }, vec![]).unwrap_err_contains("Unauthorized");
+    run_staking(&mut app, &def.owner.clone(), &staking, ExecuteMsg::UpdateConfig { unbonding_period: Some(0) }, vec![]).unwrap_err_contains("Unbonding period can't be 0");
+
+    // Only the denoms that are neither bonded nor rewards can be swept
+    let ion = AssetInfoPrecisioned::native("uion", 6);
+    app.mint(&staking, ion.to_asset(10u128.into_decimal()));
+
+    let pending = qy_pending_rewards(&app, &staking, &user_1).unwrap();
+
+    run_staking(&mut app, &user_1, &staking, ExecuteMsg::Sweep { denom: "uion".to_string(), recipient: None }, vec![]).unwrap_err_contains("Unauthorized");
+    run_staking(&mut app, &def.owner.clone(), &staking, ExecuteMsg::Sweep { denom: "uosmo".to_string(), recipient: None }, vec![]).unwrap_err_contains("Invalid Sweep Denom");
+    run_staking(&mut app, &def.owner.clone(), &staking, ExecuteMsg::Sweep { denom: cookie.clone(), recipient: None }, vec![]).unwrap_err_contains("Invalid Sweep Denom");
+    run_staking(&mut app, &def.owner.clone(), &staking, ExecuteMsg::Sweep { denom: "uatom".to_string(), recipient: None }, vec![]).unwrap_err_contains("Nothing to sweep");
+    run_staking(&mut app, &def.owner.clone(), &staking, ExecuteMsg::Sweep { denom: "uion".to_string(), recipient: Some(user_2.to_string()) }, vec![]).unwrap();
+
+    assert_eq!(app.qy_balance(&user_2, &ion).unwrap().amount_raw(), Uint128::new(10_000_000));
+    assert_eq!(app.qy_balance(&staking, &ion).unwrap().amount_raw(), Uint128::zero());
+    assert_eq!(qy_pending_rewards(&app, &staking, &user_1).unwrap(), pending);
+}
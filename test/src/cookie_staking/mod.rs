@@ -0,0 +1,4 @@
+#[cfg(test)]
+mod helpers;
+#[cfg(test)]
+mod integration_test;
@@ -1,12 +1,22 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{Addr, Decimal, Uint128};
-use ratatouille_pkg::flambe_factory::{
-    definitions::{
-        Config as FactoryConfig, CreateFactoryInput, FlambeFullInfo, FlambeSetting,
-        PoolCreationInfo, ProtocolTokensInfoCreation,
+use ratatouille_pkg::{
+    flambe::definitions::{
+        Candle, CandleResolution, Config as FlambeConfig, FlambeInfo, Holder, PositionLockResponse,
+        ReverseSwapResponse, SwapResponse, Trade, TwapResponse,
+    },
+    flambe_factory::{
+        definitions::{
+            Config as FactoryConfig, CookieEmission, CreateFactoryInput, CurveType, FeeSplit,
+            FlambeFullInfo, FlambeSetting, GraduationTarget, LpFeesSplit, OwnershipProposal,
+            PoolCreationInfo, PropagatedField, ProtocolTokensInfoCreation,
+        },
+        msgs::{
+            CookieEmissionResponse, EndFlambeMsg, EndFlambeSwapMsg, FlambeFilter, FlambesFilter,
+            UpdateConfigMsg,
+        },
     },
-    msgs::{EndFlambeMsg, EndFlambeSwapMsg, FlambeFilter, FlambesFilter, UpdateConfigMsg},
 };
 use rhaki_cw_plus::{
     asset::{AssetInfoPrecisioned, AssetPrecisioned},
@@ -16,7 +26,7 @@ use rhaki_cw_plus::{
         custom_modules::osmosis::token_factory::{TokenFactoryFee, TokenFactoryModule},
         helper::{
             anyhow::Error as AnyError,
-            build_bech32_app, create_code, create_code_with_reply,
+            build_bech32_app, create_code_with_reply,
             cw_multi_test::{
                 addons::MockAddressGenerator, no_init, AppResponse, Executor, WasmKeeper,
             },
@@ -29,6 +39,7 @@ use rhaki_cw_plus::{
 use crate::{helpers::OsmosisApp, mock_gamm::MockGamm};
 
 pub struct Def {
+    pub auto_graduation: bool,
     pub owner: Addr,
     pub burner: Addr,
     pub swap_fee: Decimal,
@@ -38,10 +49,17 @@ pub struct Def {
     pub flambe_fee_creaton: Option<AssetPrecisioned>,
     pub flambe_settings: Vec<FlambeSetting>,
     pub factory_address: Option<Addr>,
+    pub guardian: Addr,
     pub cookie_ratio: Decimal,
+    pub cookie_sell_ratio: Decimal,
+    pub cookie_emission: Option<CookieEmission>,
     pub cookie_token: ProtocolTokensInfoCreation,
     pub cookie_owner_reward: Uint128,
     pub cook_token: ProtocolTokensInfoCreation,
+    pub keeper_bounty: Decimal,
+    pub lp_fees_split: LpFeesSplit,
+    pub fee_split: FeeSplit,
+    pub cookie_staking: Option<Addr>,
 }
 
 pub const CHAIN_PREFIX: &str = "osmo";
@@ -52,6 +70,7 @@ impl Default for Def {
     fn default() -> Self {
         let app = build_bech32_app(CHAIN_PREFIX);
         Def {
+            auto_graduation: false,
             owner: app.generate_addr("owner"),
             burner: app.generate_addr("burner"),
             swap_fee: "0.01".into_decimal(),
@@ -79,9 +98,21 @@ impl Default for Def {
                     lower_tick: -108000000,
                     upper_tick: 342000000,
                 },
+                trades_retention: 100,
+                max_buy_per_tx: None,
+                max_holding: None,
+                launch_window: 0,
+                curve: CurveType::ConstantProductVirtual,
+                deadline: None,
+                graduation_target: GraduationTarget::OsmosisCL,
+                fee_split: None,
+                fee_schedule: None,
             }],
             factory_address: None,
+            guardian: app.generate_addr("guardian"),
             cookie_ratio: Decimal::from_ratio(10u128, 1u128),
+            cookie_sell_ratio: Decimal::from_ratio(5u128, 1u128),
+            cookie_emission: None,
             cookie_owner_reward: Uint128::new(1000),
 
             cookie_token: ProtocolTokensInfoCreation {
@@ -100,6 +131,10 @@ impl Default for Def {
                 uri: "https://cook.com".to_string(),
                 uri_hash: "".to_string(),
             },
+            keeper_bounty: Decimal::zero(),
+            lp_fees_split: LpFeesSplit::default(),
+            fee_split: FeeSplit::default(),
+            cookie_staking: None,
         }
     }
 }
@@ -137,18 +172,17 @@ pub fn startup(def: &mut Def) -> OsmosisApp {
     })
     .unwrap();
 
-    let factory_code_id = app.store_code(create_code(
-        flambe_factory::contract::instantiate,
-        flambe_factory::contract::execute,
-        flambe_factory::contract::query,
+    let factory_code_id = app.store_code(Box::new(
+        create_code_with_reply(
+            flambe_factory::contract::instantiate,
+            flambe_factory::contract::execute,
+            flambe_factory::contract::query,
+            flambe_factory::contract::reply,
+        )
+        .with_migrate(flambe_factory::contract::migrate),
     ));
 
-    let flambe_code_id = app.store_code(create_code_with_reply(
-        flambe::contract::instantiate,
-        flambe::contract::execute,
-        flambe::contract::query,
-        flambe::contract::reply,
-    ));
+    let flambe_code_id = store_flambe_code(&mut app);
 
     app.mint(def.owner.clone(), def.factory_minting_fee.clone());
     app.mint(def.owner.clone(), def.factory_minting_fee.clone());
@@ -171,9 +205,17 @@ pub fn startup(def: &mut Def) -> OsmosisApp {
                     .map(|val| val.try_into().unwrap()),
                 flambe_settings: def.flambe_settings.clone(),
                 cookie_ratio: def.cookie_ratio,
+                cookie_sell_ratio: def.cookie_sell_ratio,
+                cookie_emission: def.cookie_emission.clone(),
                 cookie_owner_reward: def.cookie_owner_reward,
                 cookie_token: def.cookie_token.clone(),
                 cook_token: def.cook_token.clone(),
+                keeper_bounty: def.keeper_bounty,
+                lp_fees_split: def.lp_fees_split.clone(),
+                fee_split: def.fee_split.clone(),
+                cookie_staking: def.cookie_staking.as_ref().map(|val| val.to_string()),
+                auto_graduation: def.auto_graduation,
+                guardian: Some(def.guardian.to_string()),
             },
             &[(def.factory_minting_fee.clone() * "2".into_decimal())
                 .try_into()
@@ -188,6 +230,18 @@ pub fn startup(def: &mut Def) -> OsmosisApp {
     app
 }
 
+pub fn store_flambe_code(app: &mut OsmosisApp) -> u64 {
+    app.store_code(Box::new(
+        create_code_with_reply(
+            flambe::contract::instantiate,
+            flambe::contract::execute,
+            flambe::contract::query,
+            flambe::contract::reply,
+        )
+        .with_migrate(flambe::contract::migrate),
+    ))
+}
+
 pub fn parse_swap_output_from_response(response: AppResponse) -> ParsedSwapResponse {
     let mut input_denom = None;
     let mut input_amount = None;
@@ -247,6 +301,13 @@ pub fn _qy_factory_config(app: &OsmosisApp, def: &Def) -> FactoryConfig {
         .unwrap()
 }
 
+pub fn qy_cookie_emission(app: &OsmosisApp, def: &Def) -> AppResult<CookieEmissionResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::QueryMsg::CookieEmission {},
+    )?)
+}
+
 pub fn qy_factory_flambe(
     app: &OsmosisApp,
     def: &Def,
@@ -290,7 +351,7 @@ pub fn run_create_flambe(
     subdenom: String,
     flambe_threshold_index: u8,
     msg: CreateFactoryInput,
-    coin: AssetPrecisioned,
+    funds: Vec<AssetPrecisioned>,
 ) -> Result<AppResponse, AnyError> {
     app.execute_contract(
         sender.clone(),
@@ -299,11 +360,60 @@ pub fn run_create_flambe(
             subdenom,
             flambe_threshold_index,
             msg,
+            initial_buy: None,
+            min_amount_out: None,
         },
-        &[coin.try_into().unwrap()],
+        &funds
+            .into_iter()
+            .map(|val| val.try_into().unwrap())
+            .collect::<Vec<_>>(),
     )
 }
 
+pub fn run_create_flambe_with_initial_buy(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    subdenom: &str,
+    initial_buy: AssetPrecisioned,
+    min_amount_out: impl Into<Uint128>,
+    funds: Vec<AssetPrecisioned>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::CreateFactory {
+            subdenom: subdenom.to_string(),
+            flambe_threshold_index: 0,
+            msg: create_input(subdenom),
+            initial_buy: Some(initial_buy.amount_raw()),
+            min_amount_out: Some(min_amount_out.into()),
+        },
+        &funds
+            .into_iter()
+            .map(|val| val.try_into().unwrap())
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn create_input(name: &str) -> CreateFactoryInput {
+    CreateFactoryInput {
+        description: "Test".to_string(),
+        name: name.to_string(),
+        symbol: name.to_uppercase(),
+        uri: "".to_string(),
+        uri_hash: "".to_string(),
+    }
+}
+
+pub fn creation_fees(def: &Def) -> AssetPrecisioned {
+    def.factory_minting_fee.clone()
+        + def
+            .flambe_fee_creaton
+            .clone()
+            .unwrap_or_else(|| def.factory_minting_fee.clone_with_amount(0))
+}
+
 pub fn run_swap(
     app: &mut OsmosisApp,
     def: &Def,
@@ -323,6 +433,184 @@ pub fn run_swap(
     )
 }
 
+pub fn run_fail_flambe(
+    app: &mut OsmosisApp,
+    sender: &Addr,
+    flambe: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        flambe.clone(),
+        &ratatouille_pkg::flambe::msgs::ExecuteMsg::Fail {},
+        &[],
+    )
+}
+
+pub fn run_refund(
+    app: &mut OsmosisApp,
+    sender: &Addr,
+    flambe: &Addr,
+    input: AssetPrecisioned,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        flambe.clone(),
+        &ratatouille_pkg::flambe::msgs::ExecuteMsg::Refund {},
+        &[input.try_into().unwrap()],
+    )
+}
+
+pub fn run_claim_creator_fees(
+    app: &mut OsmosisApp,
+    sender: &Addr,
+    flambe: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        flambe.clone(),
+        &ratatouille_pkg::flambe::msgs::ExecuteMsg::ClaimCreatorFees {},
+        &[],
+    )
+}
+
+pub fn run_collect_fees(
+    app: &mut OsmosisApp,
+    sender: &Addr,
+    flambe: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        flambe.clone(),
+        &ratatouille_pkg::flambe::msgs::ExecuteMsg::CollectFees {},
+        &[],
+    )
+}
+
+pub fn run_swap_exact_out(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    flambe: &Addr,
+    amount_out: impl Into<Uint128>,
+    max_amount_in: impl Into<Uint128>,
+    input: AssetPrecisioned,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::SwapExactOut {
+            flambe_addr: flambe.to_string(),
+            amount_out: amount_out.into(),
+            max_amount_in: max_amount_in.into(),
+        },
+        &[input.try_into().unwrap()],
+    )
+}
+
+pub fn qy_simulate(
+    app: &OsmosisApp,
+    flambe: &Addr,
+    offer: &AssetInfoPrecisioned,
+    amount: impl Into<Uint128>,
+) -> AppResult<SwapResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::Simulate {
+            offer: offer.info.inner(),
+            amount: amount.into(),
+        },
+    )?)
+}
+
+pub fn qy_simulate_reverse(
+    app: &OsmosisApp,
+    flambe: &Addr,
+    ask: &AssetInfoPrecisioned,
+    amount: impl Into<Uint128>,
+) -> AppResult<ReverseSwapResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::SimulateReverse {
+            ask: ask.info.inner(),
+            amount: amount.into(),
+        },
+    )?)
+}
+
+pub fn qy_trades(
+    app: &OsmosisApp,
+    flambe: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> AppResult<Vec<Trade>> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::Trades { start_after, limit },
+    )?)
+}
+
+pub fn qy_trades_by_user(app: &OsmosisApp, flambe: &Addr, user: &Addr) -> AppResult<Vec<Trade>> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::TradesByUser {
+            user: user.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)
+}
+
+pub fn qy_candles(
+    app: &OsmosisApp,
+    flambe: &Addr,
+    resolution: CandleResolution,
+    from: u64,
+    to: u64,
+    limit: Option<u32>,
+) -> AppResult<Vec<Candle>> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::Candles {
+            resolution,
+            from,
+            to,
+            limit,
+        },
+    )?)
+}
+
+pub fn qy_holders(
+    app: &OsmosisApp,
+    flambe: &Addr,
+    limit: Option<u32>,
+    start_after: Option<(Uint128, String)>,
+) -> AppResult<Vec<Holder>> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::Holders { limit, start_after },
+    )?)
+}
+
+pub fn qy_position_lock(app: &OsmosisApp, flambe: &Addr) -> AppResult<PositionLockResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::PositionLock {},
+    )?)
+}
+
+pub fn qy_twap(app: &OsmosisApp, flambe: &Addr, window_seconds: u64) -> AppResult<TwapResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        flambe,
+        &ratatouille_pkg::flambe::msgs::QueryMsg::Twap { window_seconds },
+    )?)
+}
+
+pub fn qy_flambe_info(app: &OsmosisApp, flambe: &Addr) -> AppResult<FlambeInfo> {
+    Ok(app
+        .wrap()
+        .query_wasm_smart(flambe, &ratatouille_pkg::flambe::msgs::QueryMsg::Info {})?)
+}
+
 pub fn run_end_flambe(
     app: &mut OsmosisApp,
     def: &Def,
@@ -340,3 +628,149 @@ pub fn run_end_flambe(
         &[],
     )
 }
+
+pub fn run_pause(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    flambe: Option<&Addr>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::Pause {
+            flambe_addr: flambe.map(|val| val.to_string()),
+        },
+        &[],
+    )
+}
+
+pub fn run_unpause(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    flambe: Option<&Addr>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::Unpause {
+            flambe_addr: flambe.map(|val| val.to_string()),
+        },
+        &[],
+    )
+}
+
+pub fn qy_flambe_config(app: &OsmosisApp, flambe: &Addr) -> AppResult<FlambeConfig> {
+    Ok(app
+        .wrap()
+        .query_wasm_smart(flambe, &ratatouille_pkg::flambe::msgs::QueryMsg::Config {})?)
+}
+
+pub fn run_propose_new_owner(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    owner: &Addr,
+    expires_in: u64,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::ProposeNewOwner {
+            owner: owner.to_string(),
+            expires_in,
+        },
+        &[],
+    )
+}
+
+pub fn run_accept_ownership(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::AcceptOwnership {},
+        &[],
+    )
+}
+
+pub fn run_cancel_ownership_proposal(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::CancelOwnershipProposal {},
+        &[],
+    )
+}
+
+pub fn run_propagate_owner(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::PropagateOwner { start_after, limit },
+        &[],
+    )
+}
+
+pub fn run_propagate_config(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    fields: Vec<PropagatedField>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::PropagateConfig {
+            fields,
+            start_after,
+            limit,
+        },
+        &[],
+    )
+}
+
+pub fn qy_ownership_proposal(app: &OsmosisApp, def: &Def) -> AppResult<Option<OwnershipProposal>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::QueryMsg::OwnershipProposal {},
+    )?)
+}
+
+pub fn run_migrate_flambes(
+    app: &mut OsmosisApp,
+    def: &Def,
+    sender: &Addr,
+    code_id: u64,
+    msg: ratatouille_pkg::flambe::msgs::MigrateMsg,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ratatouille_pkg::flambe_factory::msgs::ExecuteMsg::MigrateFlambes {
+            code_id,
+            msg,
+            limit,
+            start_after,
+        },
+        &[],
+    )
+}
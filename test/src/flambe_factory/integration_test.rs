@@ -1,18 +1,51 @@
-use cosmwasm_std::Decimal;
+use astroport::{
+    asset::{Asset as AstroportAsset, AssetInfo as AstroportAssetInfo, PairInfo},
+    factory::{ExecuteMsg as AstroportFactoryExecuteMsg, PairType, QueryMsg as AstroportFactoryQueryMsg},
+    pair::ExecuteMsg as AstroportPairExecuteMsg,
+    pair_concentrated::ConcentratedPoolParams,
+};
+use cosmwasm_std::{storage_keys::to_length_prefixed_nested, Coin, Decimal, Storage, Timestamp, Uint128, WasmMsg};
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgTransferPositions;
 use ratatouille_pkg::{
-    flambe::definitions::FlambeStatus,
-    flambe_factory::{definitions::CreateFactoryInput, msgs::FlambeFilter},
+    flambe::{
+        definitions::{CandleResolution, FlambeStatus, TradeSide},
+        msgs::MigrateMsg as FlambeMigrateMsg,
+    },
+    flambe_factory::{
+        definitions::{CookieEmission, CreateFactoryInput, CurveType, FeeDecay, FeeSchedule, FeeSplit, GraduationTarget, LpFeesSplit, PropagatedField},
+        msgs::{
+            EndFlambeSwapMsg, FlambeFilter, FlambesFilter, MigrateMsg, OrderBy, UpdateConfigMsg,
+        },
+    },
 };
 use rhaki_cw_plus::{
     asset::AssetInfoPrecisioned,
     math::IntoDecimal,
-    multi_test::helper::{AppExt, Bench32AppExt, UnwrapError},
+    multi_test::{
+        helper::{cw_multi_test::Executor, AppExt, Bench32AppExt, UnwrapError},
+        multi_stargate_module::ModuleDb,
+    },
     traits::Unclone,
 };
 
-use crate::flambe_factory::helpers::{parse_swap_output_from_response, run_end_flambe, run_swap};
+use crate::flambe_factory::helpers::{
+    _qy_factory_config, _update_flambe_factory_config, parse_swap_output_from_response, qy_candles, qy_cookie_emission,
+    qy_flambe_config, qy_flambe_info, qy_ownership_proposal, qy_position_lock, run_claim_creator_fees, run_collect_fees, qy_simulate, qy_simulate_reverse,
+    qy_holders, qy_trades, qy_trades_by_user, qy_twap, run_accept_ownership, run_cancel_ownership_proposal,
+    run_end_flambe, run_fail_flambe, run_migrate_flambes, run_refund, run_pause, run_propagate_config, run_propagate_owner, run_propose_new_owner,
+    run_swap, run_swap_exact_out, run_unpause, store_flambe_code,
+};
+
+use crate::{
+    mock_astroport::{instantiate_mock_astroport, MockPairQueryMsg},
+    helpers::OsmosisApp,
+    mock_gamm::{MockGamm, BALANCER_INITIAL_SHARES},
+};
 
-use super::helpers::{qy_factory_flambe, run_create_flambe, startup, Def};
+use super::helpers::{
+    _qy_factory_flambes, create_input, creation_fees, qy_factory_flambe, run_create_flambe,
+    run_create_flambe_with_initial_buy, startup, Def,
+};
 
 #[test]
 #[rustfmt::skip]
@@ -39,7 +72,7 @@ fn t1() {
             uri: "".to_string(),
             uri_hash: "".to_string(),
         },
-        def.factory_minting_fee.clone() + def.flambe_fee_creaton.clone().unwrap_or_else(|| def.factory_minting_fee.clone_with_amount(0)),
+        vec![creation_fees(&def)],
     )
     .unwrap();
 
@@ -121,8 +154,10 @@ fn t1() {
     
     let random = app.generate_addr("random");
 
-    run_end_flambe(&mut app, &def, &random, &flambe.flambe_address, None).unwrap_err_contains("Unauthorized");
-    run_end_flambe(&mut app, &def, &def.owner, &flambe.flambe_address, None).unwrap();
+    // Only the owner can provide the swap msg
+    let swap_msg = EndFlambeSwapMsg { routes: vec![], token_in_max_amount: Uint128::one(), token_out: Coin::new(1, "uatom") };
+    run_end_flambe(&mut app, &def, &random, &flambe.flambe_address, Some(swap_msg)).unwrap_err_contains("Unauthorized");
+    run_end_flambe(&mut app, &def, &random, &flambe.flambe_address, None).unwrap();
 
     assert_eq!(app.qy_balance(&flambe.flambe_address, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
     assert_eq!(app.qy_balance(&flambe.flambe_address, &token).unwrap().amount_precisioned().unwrap(), Decimal::zero());
@@ -135,3 +170,1792 @@ fn t1() {
     assert_eq!(app.qy_balance(&def.fee_collector, &token).unwrap().amount_precisioned().unwrap(), Decimal::zero());
 
 }
+
+#[test]
+#[rustfmt::skip]
+fn flambes_ordering() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let creator = app.generate_addr("user");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    let buyer = app.generate_addr("buyer");
+    app.mint(&buyer, osmo.to_asset(10_000u128.into_decimal()));
+
+    // 9 < 100 < 1_000 but "9" > "1000" > "100" when compared as strings
+    for (subdenom, buy_amount) in [("aaa", 100_u128), ("bbb", 9), ("ccc", 1_000)] {
+        run_create_flambe(&mut app, &def, creator.clone(), subdenom.to_string(), 0, create_input(subdenom), vec![creation_fees(&def)]).unwrap();
+
+        let token = format!("factory/{}/{subdenom}", def.factory_address.unclone());
+        let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token)).unwrap();
+
+        run_swap(&mut app, &def, &buyer, &flambe.flambe_address, 0_u128, osmo.to_asset(buy_amount.into_decimal())).unwrap();
+    }
+
+    let symbols = |flambes: Vec<ratatouille_pkg::flambe_factory::definitions::FlambeFullInfo>| flambes.into_iter().map(|val| val.token.symbol).collect::<Vec<_>>();
+
+    let by_liquidity = _qy_factory_flambes(&app, &def, None, FlambesFilter::ByLiquidity { start_after: None, order: None }).unwrap();
+    assert_eq!(symbols(by_liquidity.clone()), vec!["CCC", "AAA", "BBB"]);
+
+    let by_liquidity_asc = _qy_factory_flambes(&app, &def, None, FlambesFilter::ByLiquidity { start_after: None, order: Some(OrderBy::Ascending) }).unwrap();
+    assert_eq!(symbols(by_liquidity_asc), vec!["BBB", "AAA", "CCC"]);
+
+    let by_price = _qy_factory_flambes(&app, &def, None, FlambesFilter::ByPrice { start_after: None, order: None }).unwrap();
+    assert_eq!(symbols(by_price.clone()), vec!["CCC", "AAA", "BBB"]);
+
+    let by_price_asc = _qy_factory_flambes(&app, &def, None, FlambesFilter::ByPrice { start_after: None, order: Some(OrderBy::Ascending) }).unwrap();
+    assert_eq!(symbols(by_price_asc), vec!["BBB", "AAA", "CCC"]);
+
+    // Pagination
+    let first = &by_liquidity[0];
+    let page = _qy_factory_flambes(&app, &def, Some(1), FlambesFilter::ByLiquidity { start_after: Some((first.pair_amount, first.token.denom.clone())), order: None }).unwrap();
+    assert_eq!(symbols(page), vec!["AAA"]);
+
+    let first = &by_price[0];
+    let page = _qy_factory_flambes(&app, &def, None, FlambesFilter::ByPrice { start_after: Some((first.price, first.token.denom.clone())), order: None }).unwrap();
+    assert_eq!(symbols(page), vec!["AAA", "BBB"]);
+
+    let page = _qy_factory_flambes(&app, &def, None, FlambesFilter::Empty { start_after: None, order: Some(OrderBy::Ascending) }).unwrap();
+    assert_eq!(page.len(), 3);
+}
+
+#[test]
+#[rustfmt::skip]
+fn creation_fee() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+    let atom = AssetInfoPrecisioned::native("uatom", 6);
+
+    let mut app = startup(&mut def);
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+    app.mint(&creator, atom.to_asset(10_000u128.into_decimal()));
+
+    let factory = def.factory_address.unclone();
+
+    // Missing or partial fees
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![]).unwrap_err_contains("Insufficient Fee");
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![def.factory_minting_fee.clone()]).unwrap_err_contains("Insufficient Fee");
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![atom.to_asset(101_u128.into_decimal())]).unwrap_err_contains("Insufficient Fee");
+
+    // Exact fees, nothing is taken from the factory balance
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    assert_eq!(app.qy_balance(&creator, &osmo).unwrap().amount_precisioned().unwrap(), 9_899_u128.into_decimal());
+    assert_eq!(app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_precisioned().unwrap(), 1_u128.into_decimal());
+    assert_eq!(app.qy_balance(&factory, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+
+    // Surplus in a different denom is refunded
+    run_create_flambe(&mut app, &def, creator.clone(), "bbb".to_string(), 0, create_input("bbb"), vec![creation_fees(&def), atom.to_asset(5_u128.into_decimal())]).unwrap();
+
+    assert_eq!(app.qy_balance(&creator, &osmo).unwrap().amount_precisioned().unwrap(), 9_798_u128.into_decimal());
+    assert_eq!(app.qy_balance(&creator, &atom).unwrap().amount_precisioned().unwrap(), 10_000_u128.into_decimal());
+    assert_eq!(app.qy_balance(&factory, &atom).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+
+    // Surplus in the pair denom is used as initial buy
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(500_000, cookie.info.inner())]).unwrap();
+
+    let res = run_create_flambe(&mut app, &def, creator.clone(), "ccc".to_string(), 0, create_input("ccc"), vec![creation_fees(&def) + osmo.to_asset(100_u128.into_decimal())]).unwrap();
+
+    let swap = parse_swap_output_from_response(res);
+    assert_eq!(swap.input.amount_precisioned().unwrap(), 100_u128.into_decimal());
+    assert_eq!(swap.fee.amount_precisioned().unwrap(), 1_u128.into_decimal());
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/ccc"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+
+    assert_eq!(app.qy_balance(&creator, &token).unwrap(), swap.output);
+    assert_eq!(app.qy_balance(&creator, &osmo).unwrap().amount_precisioned().unwrap(), 9_597_u128.into_decimal());
+    assert_eq!(flambe.pair_amount, osmo.to_asset(99_u128.into_decimal()).amount_raw());
+    assert_eq!(app.qy_balance(&factory, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+
+    // Cookies are rewarded as on a regular buy, capped by the factory balance
+    assert_eq!(app.qy_balance(&creator, &cookie).unwrap().amount_raw(), Uint128::new(500_000));
+}
+
+#[test]
+#[rustfmt::skip]
+fn initial_buy() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    let factory = def.factory_address.unclone();
+
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(500_000, cookie.info.inner())]).unwrap();
+
+    let initial_buy = osmo.to_asset(100_u128.into_decimal());
+
+    // Funds don't cover fees + initial buy
+    run_create_flambe_with_initial_buy(&mut app, &def, &creator, "aaa", initial_buy.clone(), 0_u128, vec![creation_fees(&def) + osmo.to_asset(50_u128.into_decimal())]).unwrap_err_contains("Invalid Initial Buy");
+
+    // Slippage reverts the whole creation
+    run_create_flambe_with_initial_buy(&mut app, &def, &creator, "aaa", initial_buy.clone(), 1_000_000_000_000_u128, vec![creation_fees(&def) + initial_buy.clone()]).unwrap_err_contains("Slippage Error");
+
+    // Surplus over the initial buy is refunded
+    let res = run_create_flambe_with_initial_buy(&mut app, &def, &creator, "aaa", initial_buy.clone(), 1_u128, vec![creation_fees(&def) + initial_buy.clone() + osmo.to_asset(10_u128.into_decimal())]).unwrap();
+
+    // Reserve: 100_000
+    // Swap_input = 99
+    // output = 1_000_000 - (1_000_000 * 100_000 / (100_000 + 99)) = 989.020869
+
+    let swap = parse_swap_output_from_response(res);
+    assert_eq!(swap.input.amount_precisioned().unwrap(), 100_u128.into_decimal());
+    assert_eq!(swap.fee.amount_precisioned().unwrap(), 1_u128.into_decimal());
+    assert_eq!(swap.output.amount_precisioned().unwrap(), "989.020869".into_decimal());
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+
+    assert_eq!(app.qy_balance(&creator, &token).unwrap(), swap.output);
+    assert_eq!(app.qy_balance(&creator, &osmo).unwrap().amount_precisioned().unwrap(), 9_799_u128.into_decimal());
+    assert_eq!(app.qy_balance(&factory, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+    assert_eq!(flambe.pair_amount, osmo.to_asset(99_u128.into_decimal()).amount_raw());
+    assert_eq!(flambe.main_amount, (token.to_asset(1_000_000_u128.into_decimal()) - &swap.output).amount_raw());
+
+    // Cookies are rewarded as on a regular buy, capped by the factory balance
+    assert_eq!(app.qy_balance(&creator, &cookie).unwrap().amount_raw(), Uint128::new(500_000));
+}
+
+#[test]
+#[rustfmt::skip]
+fn permissionless_graduation() {
+    let mut def = Def { keeper_bounty: "0.1".into_decimal(), ..Default::default() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+
+    let keeper = app.generate_addr("keeper");
+
+    run_end_flambe(&mut app, &def, &keeper, &flambe.flambe_address, None).unwrap_err_contains("Invalid Flambè Status");
+
+    // Buy 60_000 osmo, fee 600 osmo, 60 osmo retained as bounty
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+    run_swap(&mut app, &def, &user, &flambe.flambe_address, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+    assert_eq!(flambe.status, FlambeStatus::PENDING);
+    assert_eq!(flambe.pair_amount, osmo.to_asset(59_400_u128.into_decimal()).amount_raw());
+    assert_eq!(app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_precisioned().unwrap(), 541_u128.into_decimal());
+    assert_eq!(app.qy_balance(&flambe.flambe_address, &osmo).unwrap().amount_precisioned().unwrap(), 59_460_u128.into_decimal());
+
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(def.cookie_owner_reward.u128(), cookie.info.inner())]).unwrap();
+
+    // Anyone can graduate the flambe and collects the bounty
+    run_end_flambe(&mut app, &def, &keeper, &flambe.flambe_address, None).unwrap();
+
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+    assert_eq!(flambe.status, FlambeStatus::CLOSED);
+    assert_eq!(app.qy_balance(&keeper, &osmo).unwrap().amount_precisioned().unwrap(), 60_u128.into_decimal());
+    assert_eq!(app.qy_balance(&flambe.flambe_address, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+    assert_eq!(app.qy_balance(&creator, &cookie).unwrap().amount_raw(), def.cookie_owner_reward);
+
+    run_end_flambe(&mut app, &def, &keeper, &flambe.flambe_address, None).unwrap_err_contains("Invalid Flambè Status");
+}
+
+#[test]
+#[rustfmt::skip]
+fn auto_graduation() {
+    let mut def = Def { keeper_bounty: "0.1".into_decimal(), ..Default::default() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        auto_graduation: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    // Below the threshold nothing happens
+    run_swap(&mut app, &def, &user, &flambe.flambe_address, 0_u128, osmo.to_asset(10_000u128.into_decimal())).unwrap();
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::OPEN);
+
+    // The swap reaching the threshold graduates the flambe, the swapper is the keeper
+    run_swap(&mut app, &def, &user, &flambe.flambe_address, 0_u128, osmo.to_asset(50_000u128.into_decimal())).unwrap();
+
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap();
+    assert_eq!(flambe.status, FlambeStatus::CLOSED);
+    assert_eq!(app.qy_balance(&user, &osmo).unwrap().amount_precisioned().unwrap(), 60_u128.into_decimal());
+    assert_eq!(app.qy_balance(&flambe.flambe_address, &osmo).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+    assert_eq!(app.qy_balance(&flambe.flambe_address, &token).unwrap().amount_precisioned().unwrap(), Decimal::zero());
+}
+
+#[test]
+#[rustfmt::skip]
+fn pause() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+    run_create_flambe(&mut app, &def, creator.clone(), "bbb".to_string(), 0, create_input("bbb"), vec![creation_fees(&def)]).unwrap();
+
+    let flambe_a = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/aaa"))).unwrap().flambe_address;
+    let flambe_b = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/bbb"))).unwrap().flambe_address;
+
+    let random = app.generate_addr("random");
+
+    // Protocol wide pause
+    run_pause(&mut app, &def, &random, None).unwrap_err_contains("Unauthorized");
+    run_pause(&mut app, &def, &def.guardian, None).unwrap();
+
+    assert!(_qy_factory_config(&app, &def).paused);
+
+    run_create_flambe(&mut app, &def, creator.clone(), "ccc".to_string(), 0, create_input("ccc"), vec![creation_fees(&def)]).unwrap_err_contains("Protocol Paused");
+    run_swap(&mut app, &def, &user, &flambe_a, 0_u128, osmo.to_asset(10u128.into_decimal())).unwrap_err_contains("Protocol Paused");
+    run_end_flambe(&mut app, &def, &user, &flambe_a, None).unwrap_err_contains("Protocol Paused");
+
+    // Guardian can't unpause
+    run_unpause(&mut app, &def, &def.guardian, None).unwrap_err_contains("Unauthorized");
+    run_unpause(&mut app, &def, &def.owner, None).unwrap();
+
+    assert!(!_qy_factory_config(&app, &def).paused);
+
+    run_swap(&mut app, &def, &user, &flambe_a, 0_u128, osmo.to_asset(10u128.into_decimal())).unwrap();
+
+    // Single flambe pause
+    run_pause(&mut app, &def, &random, Some(&flambe_a)).unwrap_err_contains("Unauthorized");
+    run_pause(&mut app, &def, &def.guardian, Some(&flambe_a)).unwrap();
+
+    assert!(qy_flambe_config(&app, &flambe_a).unwrap().paused);
+    assert!(!qy_flambe_config(&app, &flambe_b).unwrap().paused);
+
+    run_swap(&mut app, &def, &user, &flambe_a, 0_u128, osmo.to_asset(10u128.into_decimal())).unwrap_err_contains("Flambè Paused");
+    run_swap(&mut app, &def, &user, &flambe_b, 0_u128, osmo.to_asset(10u128.into_decimal())).unwrap();
+
+    run_unpause(&mut app, &def, &def.guardian, Some(&flambe_a)).unwrap_err_contains("Unauthorized");
+    run_unpause(&mut app, &def, &def.owner, Some(&flambe_a)).unwrap();
+
+    run_swap(&mut app, &def, &user, &flambe_a, 0_u128, osmo.to_asset(10u128.into_decimal())).unwrap();
+
+    // Revoked guardian
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        guardian: Some(random.to_string()),
+        remove_guardian: Some(true),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Can't set and remove the guardian in the same update");
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        remove_guardian: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(_qy_factory_config(&app, &def).guardian, None);
+    run_pause(&mut app, &def, &def.guardian, None).unwrap_err_contains("Unauthorized");
+}
+
+#[test]
+#[rustfmt::skip]
+fn ownership_transfer() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    for subdenom in ["aaa", "bbb", "ccc"] {
+        run_create_flambe(&mut app, &def, creator.clone(), subdenom.to_string(), 0, create_input(subdenom), vec![creation_fees(&def)]).unwrap();
+    }
+
+    let flambes = ["aaa", "bbb", "ccc"].map(|subdenom| qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/{subdenom}"))).unwrap().flambe_address);
+
+    let new_owner = app.generate_addr("new_owner");
+    let random = app.generate_addr("random");
+
+    run_propose_new_owner(&mut app, &def, &random, &new_owner, 100).unwrap_err_contains("Unauthorized");
+    run_propose_new_owner(&mut app, &def, &def.owner, &new_owner, 0).unwrap_err_contains("Invalid Ownership Proposal Expiry");
+    run_accept_ownership(&mut app, &def, &new_owner).unwrap_err_contains("Ownership Proposal Not Found");
+
+    // Expired proposal
+    run_propose_new_owner(&mut app, &def, &def.owner, &new_owner, 100).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    run_accept_ownership(&mut app, &def, &new_owner).unwrap_err_contains("Ownership Proposal Expired");
+
+    // Cancelled proposal
+    run_propose_new_owner(&mut app, &def, &def.owner, &new_owner, 100).unwrap();
+    assert_eq!(qy_ownership_proposal(&app, &def).unwrap().unwrap().owner, new_owner);
+    run_cancel_ownership_proposal(&mut app, &def, &random).unwrap_err_contains("Unauthorized");
+    run_cancel_ownership_proposal(&mut app, &def, &def.owner).unwrap();
+    assert_eq!(qy_ownership_proposal(&app, &def).unwrap(), None);
+    run_accept_ownership(&mut app, &def, &new_owner).unwrap_err_contains("Ownership Proposal Not Found");
+
+    run_propose_new_owner(&mut app, &def, &def.owner, &new_owner, 100).unwrap();
+    run_accept_ownership(&mut app, &def, &random).unwrap_err_contains("Unauthorized");
+    run_accept_ownership(&mut app, &def, &new_owner).unwrap();
+
+    assert_eq!(_qy_factory_config(&app, &def).owner, new_owner);
+    assert_eq!(qy_ownership_proposal(&app, &def).unwrap(), None);
+
+    // Propagate the new owner to the existing flambes, paginated
+    run_propagate_owner(&mut app, &def, &def.owner, None, None).unwrap_err_contains("Unauthorized");
+
+    let res = run_propagate_owner(&mut app, &def, &new_owner, None, Some(2)).unwrap();
+    let last = res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "last").unwrap().value.clone();
+
+    let updated = flambes.iter().filter(|flambe| qy_flambe_config(&app, flambe).unwrap().owner == new_owner).count();
+    assert_eq!(updated, 2);
+
+    run_propagate_owner(&mut app, &def, &new_owner, Some(last), Some(2)).unwrap();
+
+    for flambe in &flambes {
+        assert_eq!(qy_flambe_config(&app, flambe).unwrap().owner, new_owner);
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn migration() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let factory_code_id = app.wrap().query_wasm_contract_info(&factory).unwrap().code_id;
+
+    let version = cw2::query_contract_info(&app.wrap(), &factory).unwrap();
+    assert_eq!(version.contract, "flambe-factory");
+
+    // Factory migration
+    app.migrate_contract(def.owner.clone(), factory.clone(), &MigrateMsg::MigrateConfig { auto_graduation: None, guardian: None, keeper_bounty: Some(2_u128.into_decimal()) }, factory_code_id).unwrap_err_contains("Keeper bounty can't be greater then 1");
+    app.migrate_contract(def.owner.clone(), factory.clone(), &MigrateMsg::MigrateConfig { auto_graduation: Some(true), guardian: None, keeper_bounty: Some("0.1".into_decimal()) }, factory_code_id).unwrap();
+
+    let config = _qy_factory_config(&app, &def);
+    assert!(config.auto_graduation);
+    assert_eq!(config.keeper_bounty, "0.1".into_decimal());
+
+    app.migrate_contract(def.owner.clone(), factory.clone(), &MigrateMsg::RebuildIndexes {}, factory_code_id).unwrap();
+
+    // Flambes migration
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    for subdenom in ["aaa", "bbb", "ccc"] {
+        run_create_flambe(&mut app, &def, creator.clone(), subdenom.to_string(), 0, create_input(subdenom), vec![creation_fees(&def)]).unwrap();
+    }
+
+    let flambes = ["aaa", "bbb", "ccc"].map(|subdenom| qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/{subdenom}"))).unwrap().flambe_address);
+
+    for flambe in &flambes {
+        assert_eq!(app.wrap().query_wasm_contract_info(flambe).unwrap().admin, Some(factory.to_string()));
+        assert_eq!(cw2::query_contract_info(&app.wrap(), flambe).unwrap().contract, "flambe");
+    }
+
+    // Flambes created before the factory became their admin are administrated by the owner
+    app.execute(factory.clone(), WasmMsg::UpdateAdmin { contract_addr: flambes[2].to_string(), admin: def.owner.to_string() }.into()).unwrap();
+
+    let new_code_id = store_flambe_code(&mut app);
+    let msg = FlambeMigrateMsg::MigrateConfig { auto_graduation: None, keeper_bounty: Some("0.2".into_decimal()) };
+
+    run_migrate_flambes(&mut app, &def, &creator, new_code_id, msg.clone(), None, None).unwrap_err_contains("Unauthorized");
+
+    let res = run_migrate_flambes(&mut app, &def, &def.owner, new_code_id, msg.clone(), None, Some(2)).unwrap();
+    let last = res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "last").unwrap().value.clone();
+
+    let migrated = flambes.iter().filter(|flambe| app.wrap().query_wasm_contract_info(*flambe).unwrap().code_id == new_code_id).count();
+    assert_eq!(migrated, 2);
+
+    // The migrate msg is forwarded to the flambes
+    assert_eq!(qy_flambe_config(&app, &flambes[0]).unwrap().keeper_bounty, "0.2".into_decimal());
+
+    // New flambes use the migrated code
+    assert_eq!(_qy_factory_config(&app, &def).flambe_code_id, new_code_id);
+
+    // The flambe administrated by the owner is skipped
+    let res = run_migrate_flambes(&mut app, &def, &def.owner, new_code_id, msg.clone(), Some(last.clone()), Some(2)).unwrap();
+    assert_eq!(res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "skipped").unwrap().value, "1");
+    assert_ne!(app.wrap().query_wasm_contract_info(&flambes[2]).unwrap().code_id, new_code_id);
+
+    // Until the owner moves it to the factory
+    app.execute(def.owner.clone(), WasmMsg::UpdateAdmin { contract_addr: flambes[2].to_string(), admin: factory.to_string() }.into()).unwrap();
+
+    // The code of the new flambes is only set from the first page
+    let newer_code_id = store_flambe_code(&mut app);
+    run_migrate_flambes(&mut app, &def, &def.owner, newer_code_id, msg, Some(last), Some(2)).unwrap();
+
+    assert_eq!(app.wrap().query_wasm_contract_info(&flambes[2]).unwrap().code_id, newer_code_id);
+    assert_eq!(qy_flambe_config(&app, &flambes[2]).unwrap().keeper_bounty, "0.2".into_decimal());
+    assert_eq!(_qy_factory_config(&app, &def).flambe_code_id, new_code_id);
+}
+
+#[test]
+#[rustfmt::skip]
+fn swap_exact_out() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(2_000u128.into_decimal()));
+
+    // Buy: same output of t1 for 1_000 osmo, the reverse rounds in favour of the curve
+    let amount_out = Uint128::new(9_802_950_787);
+    let reverse = qy_simulate_reverse(&app, &flambe, &token, amount_out).unwrap();
+
+    assert_eq!(reverse.offer_amount.denom, osmo.info.inner());
+    assert!(reverse.offer_amount.amount <= Uint128::new(1_000_000_000) && reverse.offer_amount.amount > Uint128::new(999_999_000));
+    assert!(qy_simulate(&app, &flambe, &osmo, reverse.offer_amount.amount).unwrap().return_amount.amount >= amount_out);
+
+    run_swap_exact_out(&mut app, &def, &user, &flambe, amount_out, reverse.offer_amount.amount - Uint128::one(), osmo.to_asset(1_500u128.into_decimal())).unwrap_err_contains("Slippage Error");
+    run_swap_exact_out(&mut app, &def, &user, &flambe, amount_out, Uint128::MAX, osmo.to_asset(999u128.into_decimal())).unwrap_err_contains("Insufficient Funds");
+    run_swap_exact_out(&mut app, &def, &user, &flambe, Uint128::new(1_000_000_000_000), Uint128::MAX, osmo.to_asset(1_500u128.into_decimal())).unwrap_err_contains("Invalid amount out");
+
+    let fee_before = app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_raw();
+
+    run_swap_exact_out(&mut app, &def, &user, &flambe, amount_out, Uint128::MAX, osmo.to_asset(1_500u128.into_decimal())).unwrap();
+
+    // The overpayment is refunded
+    assert_eq!(app.qy_balance(&user, &token).unwrap().amount_raw(), amount_out);
+    assert_eq!(app.qy_balance(&user, &osmo).unwrap().amount_raw(), Uint128::new(2_000_000_000) - reverse.offer_amount.amount);
+    assert_eq!(app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_raw(), fee_before + reverse.swap_fee.amount);
+    assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), reverse.offer_amount.amount - reverse.swap_fee.amount);
+
+    // Sell: receive exactly 100 osmo, the fee is taken from the output
+    let amount_out = Uint128::new(100_000_000);
+    let reverse = qy_simulate_reverse(&app, &flambe, &osmo, amount_out).unwrap();
+
+    assert_eq!(reverse.offer_amount.denom, token.info.inner());
+    assert!(qy_simulate(&app, &flambe, &token, reverse.offer_amount.amount).unwrap().return_amount.amount >= amount_out);
+
+    let osmo_before = app.qy_balance(&user, &osmo).unwrap().amount_raw();
+
+    run_swap_exact_out(&mut app, &def, &user, &flambe, amount_out, Uint128::MAX, token.to_asset(9_000u128.into_decimal())).unwrap();
+
+    assert_eq!(app.qy_balance(&user, &osmo).unwrap().amount_raw(), osmo_before + amount_out);
+    assert_eq!(app.qy_balance(&user, &token).unwrap().amount_raw(), Uint128::new(9_802_950_787) - reverse.offer_amount.amount);
+
+    // Cookies are rewarded on the fee actually paid
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(500_000, cookie.info.inner())]).unwrap();
+
+    let amount_out = Uint128::new(100_000);
+    let reverse = qy_simulate_reverse(&app, &flambe, &token, amount_out).unwrap();
+
+    run_swap_exact_out(&mut app, &def, &user, &flambe, amount_out, Uint128::MAX, osmo.to_asset(1u128.into_decimal())).unwrap();
+
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), reverse.swap_fee.amount * def.cookie_ratio);
+}
+
+#[test]
+#[rustfmt::skip]
+fn trades_history() {
+    let mut def = Def::default();
+    def.flambe_settings[0].trades_retention = 3;
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    app.mint(&user_1, osmo.to_asset(1_000u128.into_decimal()));
+    app.mint(&user_2, osmo.to_asset(1_000u128.into_decimal()));
+
+    assert_eq!(qy_trades(&app, &flambe, None, None).unwrap(), vec![]);
+
+    let buy = parse_swap_output_from_response(run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+
+    let trades = qy_trades(&app, &flambe, None, None).unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].id, 0);
+    assert_eq!(trades[0].user, user_1);
+    assert_eq!(trades[0].side, TradeSide::Buy);
+    assert_eq!(trades[0].offer_amount, buy.input.amount_raw());
+    assert_eq!(trades[0].return_amount, buy.output.amount_raw());
+    assert_eq!(trades[0].fee_amount, buy.fee.amount_raw());
+    assert_eq!(trades[0].price, qy_flambe_info(&app, &flambe).unwrap().price);
+    assert_eq!(trades[0].timestamp, app.block_info().time.seconds());
+
+    run_swap(&mut app, &def, &user_2, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap();
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, buy.output.clone_with_amount(100u128.into_decimal())).unwrap();
+    run_swap_exact_out(&mut app, &def, &user_1, &flambe, 1_000_000_u128, Uint128::MAX, osmo.to_asset(1u128.into_decimal())).unwrap();
+
+    // Only the last 3 trades are retained, from the most recent
+    let trades = qy_trades(&app, &flambe, None, None).unwrap();
+    assert_eq!(trades.iter().map(|trade| trade.id).collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert_eq!(trades[1].side, TradeSide::Sell);
+    assert_eq!(trades[0].return_amount, Uint128::new(1_000_000));
+    assert_eq!(trades[0].price, qy_flambe_info(&app, &flambe).unwrap().price);
+
+    assert_eq!(qy_trades(&app, &flambe, Some(2), Some(1)).unwrap().iter().map(|trade| trade.id).collect::<Vec<_>>(), vec![1]);
+
+    assert_eq!(qy_trades_by_user(&app, &flambe, &user_1).unwrap().iter().map(|trade| trade.id).collect::<Vec<_>>(), vec![3, 2]);
+    assert_eq!(qy_trades_by_user(&app, &flambe, &user_2).unwrap().iter().map(|trade| trade.id).collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+#[rustfmt::skip]
+fn candles() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    // Start of a day
+    let t0 = 1_699_920_000;
+    app.update_block(|block| block.time = Timestamp::from_seconds(t0));
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(1_000u128.into_decimal()));
+
+    assert_eq!(qy_candles(&app, &flambe, CandleResolution::M1, 0, t0, None).unwrap(), vec![]);
+
+    app.update_block(|block| block.time = Timestamp::from_seconds(t0 + 10));
+    let buy = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+
+    app.update_block(|block| block.time = Timestamp::from_seconds(t0 + 30));
+    let sell = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, buy.output.clone_with_amount(100u128.into_decimal())).unwrap());
+
+    app.update_block(|block| block.time = Timestamp::from_seconds(t0 + 200));
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(50u128.into_decimal())).unwrap();
+
+    let sell_volume = sell.output.amount_raw() + sell.fee.amount_raw();
+
+    let prices = qy_trades(&app, &flambe, None, None).unwrap().into_iter().rev().map(|trade| trade.price).collect::<Vec<_>>();
+    let initial_price = def.flambe_settings[0].initial_price;
+
+    // The last bucket is capped to the block time, the gaps are filled from the last close
+    let candles = qy_candles(&app, &flambe, CandleResolution::M1, t0, t0 + 3_600, None).unwrap();
+    assert_eq!(candles.iter().map(|candle| candle.start).collect::<Vec<_>>(), vec![t0, t0 + 60, t0 + 120, t0 + 180]);
+
+    assert_eq!(candles[0].open, initial_price);
+    assert_eq!(candles[0].high, prices[0]);
+    assert_eq!(candles[0].low, initial_price);
+    assert_eq!(candles[0].close, prices[1]);
+    assert_eq!(candles[0].volume, buy.input.amount_raw() + sell_volume);
+
+    for candle in &candles[1..3] {
+        assert_eq!((candle.open, candle.high, candle.low, candle.close, candle.volume), (prices[1], prices[1], prices[1], prices[1], Uint128::zero()));
+    }
+
+    assert_eq!(candles[3].open, prices[1]);
+    assert_eq!(candles[3].close, prices[2]);
+    assert_eq!(candles[3].volume, Uint128::new(50_000_000));
+
+    assert_eq!(qy_candles(&app, &flambe, CandleResolution::M1, t0, t0 + 3_600, Some(2)).unwrap(), candles[..2].to_vec());
+    assert_eq!(qy_candles(&app, &flambe, CandleResolution::M1, t0 + 70, t0 + 3_600, None).unwrap(), candles[1..].to_vec());
+
+    // Without previous trades the candles start from the first one
+    assert_eq!(qy_candles(&app, &flambe, CandleResolution::M1, t0 - 3_600, t0, None).unwrap(), candles[..1].to_vec());
+
+    for resolution in [CandleResolution::M15, CandleResolution::H1, CandleResolution::D1] {
+        let candles = qy_candles(&app, &flambe, resolution, t0, t0 + 3_600, None).unwrap();
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].start, t0);
+        assert_eq!((candles[0].open, candles[0].close), (initial_price, prices[2]));
+        assert_eq!(candles[0].volume, buy.input.amount_raw() + sell_volume + Uint128::new(50_000_000));
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn twap() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let t0 = app.block_info().time.seconds();
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(10_000u128.into_decimal()));
+
+    let initial_price = def.flambe_settings[0].initial_price;
+
+    qy_twap(&app, &flambe, 0).unwrap_err_contains("Invalid twap window");
+    qy_twap(&app, &flambe, 8 * 86_400).unwrap_err_contains("Invalid twap window");
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    assert_eq!(qy_twap(&app, &flambe, 100).unwrap().price, initial_price);
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap();
+    let p1 = qy_flambe_info(&app, &flambe).unwrap().price;
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(200u128.into_decimal())).unwrap();
+    let p2 = qy_flambe_info(&app, &flambe).unwrap().price;
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let twap = qy_twap(&app, &flambe, 300).unwrap();
+    assert_eq!((twap.start, twap.end), (t0, t0 + 300));
+    assert_eq!(twap.price, (initial_price + p1 + p2) * Decimal::from_ratio(100_u128, 1_u128) / Decimal::from_ratio(300_u128, 1_u128));
+
+    // The window starts between two observations
+    let twap = qy_twap(&app, &flambe, 150).unwrap();
+    assert_eq!(twap.price, (p1 * Decimal::from_ratio(50_u128, 1_u128) + p2 * Decimal::from_ratio(100_u128, 1_u128)) / Decimal::from_ratio(150_u128, 1_u128));
+
+    qy_twap(&app, &flambe, 301).unwrap_err_contains("Twap window exceeds the price history");
+
+    // A swap doesn't move the twap in the same block
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(5_000u128.into_decimal())).unwrap();
+    assert!(qy_flambe_info(&app, &flambe).unwrap().price > p2);
+    assert_eq!(qy_twap(&app, &flambe, 100).unwrap().price, p2);
+}
+
+#[test]
+#[rustfmt::skip]
+fn holders() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().holders, 0);
+    assert_eq!(qy_holders(&app, &flambe, None, None).unwrap(), vec![]);
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    let user_3 = app.generate_addr("user_3");
+
+    let mut bought = vec![];
+
+    for (user, amount) in [(&user_1, 100u128), (&user_2, 300), (&user_3, 200)] {
+        app.mint(user, osmo.to_asset(1_000u128.into_decimal()));
+        let buy = parse_swap_output_from_response(run_swap(&mut app, &def, user, &flambe, 0_u128, osmo.to_asset(amount.into_decimal())).unwrap());
+        bought.push(buy.output);
+    }
+
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().holders, 3);
+
+    let holders = qy_holders(&app, &flambe, None, None).unwrap();
+    assert_eq!(holders.iter().map(|holder| holder.address.clone()).collect::<Vec<_>>(), vec![user_2.clone(), user_3.clone(), user_1.clone()]);
+    assert_eq!(holders[0].balance, bought[1].amount_raw());
+
+    let page = qy_holders(&app, &flambe, Some(1), Some((holders[0].balance, user_2.to_string()))).unwrap();
+    assert_eq!(page, vec![holders[1].clone()]);
+
+    // A partial sell reduces the balance
+    run_swap(&mut app, &def, &user_2, &flambe, 0_u128, bought[1].clone_with_amount(bought[1].amount_raw() - bought[2].amount_raw() + Uint128::one())).unwrap();
+    assert_eq!(qy_holders(&app, &flambe, Some(1), None).unwrap()[0].address, user_3);
+
+    // Selling everything removes the holder
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, bought[0].clone()).unwrap();
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().holders, 2);
+    assert_eq!(qy_holders(&app, &flambe, None, None).unwrap().iter().map(|holder| holder.address.clone()).collect::<Vec<_>>(), vec![user_3, user_2]);
+}
+
+#[test]
+#[rustfmt::skip]
+fn launch_caps() {
+    let mut def = Def::default();
+    def.flambe_settings[0].max_buy_per_tx = Some(Uint128::new(200_000_000));
+    def.flambe_settings[0].max_holding = Some("0.001".into_decimal());
+    def.flambe_settings[0].launch_window = 60;
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(300u128.into_decimal())).unwrap_err_contains("Max Buy Per Tx Exceeded");
+
+    let buy = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(90u128.into_decimal())).unwrap());
+
+    // 0.1% of the initial supply
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(50u128.into_decimal())).unwrap_err_contains("Max Holding Exceeded");
+    run_swap_exact_out(&mut app, &def, &user, &flambe, 1_000_000_000_u128 - buy.output.amount_raw().u128() + 1, Uint128::MAX, osmo.to_asset(150u128.into_decimal())).unwrap_err_contains("Max Holding Exceeded");
+    run_swap_exact_out(&mut app, &def, &user, &flambe, 1_000_000_000_u128 - buy.output.amount_raw().u128(), Uint128::MAX, osmo.to_asset(150u128.into_decimal())).unwrap();
+
+    // Sells are never capped
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, buy.output.clone()).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(1_000u128.into_decimal())).unwrap();
+}
+
+#[test]
+#[rustfmt::skip]
+fn linear_curve() {
+    let mut def = Def::default();
+    def.flambe_settings[0].curve = CurveType::Linear { final_price: "0.3".into_decimal() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        auto_graduation: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let setting = def.flambe_settings[0].clone();
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().price, setting.initial_price);
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    let offer = osmo.to_asset(10_000u128.into_decimal());
+    let simulation = qy_simulate(&app, &flambe, &osmo, offer.amount_raw()).unwrap();
+    let buy = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, offer).unwrap());
+    assert_eq!(buy.output.amount_raw(), simulation.return_amount.amount);
+
+    // The price grows linearly with the sold supply
+    let sold = setting.initial_supply - qy_flambe_info(&app, &flambe).unwrap().main_amount;
+    assert_eq!(sold, buy.output.amount_raw());
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().price, setting.initial_price + "0.2".into_decimal() * Decimal::from_ratio(sold, 1_u128) / Decimal::from_ratio(setting.initial_supply, 1_u128));
+
+    // Selling everything back returns less then the offer
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, buy.output.clone()).unwrap();
+    assert!(app.qy_balance(&user, &osmo).unwrap().amount_precisioned().unwrap() < 60_000u128.into_decimal());
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().main_amount, setting.initial_supply);
+
+    // The graduation deploys the liquidity at the curve price
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(55_000u128.into_decimal())).unwrap();
+
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+    assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), Uint128::zero());
+    assert_eq!(app.qy_balance(&flambe, &token).unwrap().amount_raw(), Uint128::zero());
+}
+
+#[test]
+#[rustfmt::skip]
+fn bounded_curve_reserve() {
+    let mut def = Def::default();
+    def.flambe_settings[0].curve = CurveType::Linear { final_price: "0.3".into_decimal() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    // Selling the whole supply along the curve costs 1_000_000 * (0.1 + 0.3) / 2 = 200_000
+    let max_reserve = osmo.to_asset(200_000u128.into_decimal()).amount_raw();
+
+    let mut flambe_settings = def.flambe_settings.clone();
+    flambe_settings[0].threshold = max_reserve + Uint128::one();
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(flambe_settings.clone()),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Threshold can't be greater then the curve reserve 200000000000");
+
+    flambe_settings[0].threshold = max_reserve;
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(flambe_settings),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(400_000u128.into_decimal()));
+
+    // The buy is capped to the remaining supply and the unused offer is refunded
+    let offer = osmo.to_asset(300_000u128.into_decimal());
+    let res = run_swap(&mut app, &def, &user, &flambe, 0_u128, offer.clone()).unwrap();
+    let refund = res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "refund_amount").unwrap().value.parse::<u128>().unwrap();
+
+    let buy = parse_swap_output_from_response(res);
+
+    assert_eq!(buy.output.amount_raw(), def.flambe_settings[0].initial_supply);
+    assert_eq!(buy.input.amount_raw() - buy.fee.amount_raw(), max_reserve);
+    assert_eq!(buy.input.amount_raw() + Uint128::new(refund), offer.amount_raw());
+    assert_eq!(app.qy_balance(&user, &osmo).unwrap().amount_raw(), osmo.to_asset(100_000u128.into_decimal()).amount_raw() + Uint128::new(refund));
+
+    // The reserve reaches the threshold
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::PENDING);
+}
+
+#[test]
+#[rustfmt::skip]
+fn deadline() {
+    let mut def = Def {
+        keeper_bounty: "0.1".into_decimal(),
+        fee_split: FeeSplit { protocol: "0.8".into_decimal(), burn: "0.2".into_decimal(), ..Default::default() },
+        ..Default::default()
+    };
+    def.flambe_settings[0].deadline = Some(3_600);
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    let keeper = app.generate_addr("keeper");
+    app.mint(&user_1, osmo.to_asset(1_000u128.into_decimal()));
+    app.mint(&user_2, osmo.to_asset(1_000u128.into_decimal()));
+
+    let buy_1 = parse_swap_output_from_response(run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+    let buy_2 = parse_swap_output_from_response(run_swap(&mut app, &def, &user_2, &flambe, 0_u128, osmo.to_asset(200u128.into_decimal())).unwrap());
+
+    run_fail_flambe(&mut app, &keeper, &flambe).unwrap_err_contains("Deadline Not Reached");
+    run_refund(&mut app, &user_1, &flambe, buy_1.output.clone()).unwrap_err_contains("Status not in Failed");
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3_600));
+
+    // Only the sells are allowed after the deadline
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap_err_contains("Flambè Expired");
+
+    let bounty = qy_flambe_config(&app, &flambe).unwrap().accrued_bounty;
+    assert!(bounty > Uint128::zero());
+
+    run_fail_flambe(&mut app, &keeper, &flambe).unwrap();
+    run_fail_flambe(&mut app, &keeper, &flambe).unwrap_err_contains("Pump Closed");
+
+    assert_eq!(qy_flambe_config(&app, &flambe).unwrap().status, FlambeStatus::FAILED);
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::FAILED);
+    assert_eq!(app.qy_balance(&keeper, &osmo).unwrap().amount_raw(), bounty);
+
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap_err_contains("Flambè Expired");
+
+    // Selling back along the curve
+    let half = buy_1.output.clone_with_amount(buy_1.output.amount_raw() / Uint128::new(2));
+    run_swap(&mut app, &def, &user_1, &flambe, 0_u128, half.clone()).unwrap();
+
+    // The burned tokens are not part of the sold supply
+    let burned = qy_flambe_config(&app, &flambe).unwrap().burned;
+    assert!(burned > Uint128::zero());
+    assert_eq!(app.qy_balance(&def.burner, &token).unwrap().amount_raw(), burned);
+
+    // Pro-rata refund of the pair reserve
+    let info = qy_flambe_info(&app, &flambe).unwrap();
+    let sold = def.flambe_settings[0].initial_supply - info.main_amount - burned;
+    let expected = info.pair_amount.multiply_ratio(buy_2.output.amount_raw(), sold);
+
+    run_refund(&mut app, &user_2, &flambe, buy_2.output.clone()).unwrap();
+    assert_eq!(app.qy_balance(&user_2, &osmo).unwrap().amount_raw(), Uint128::new(800_000_000) + expected);
+
+    // The last refund receives all the reserve
+    run_refund(&mut app, &user_1, &flambe, buy_1.output.clone_with_amount(buy_1.output.amount_raw() - half.amount_raw())).unwrap();
+    assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), Uint128::zero());
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().main_amount, def.flambe_settings[0].initial_supply - burned);
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().holders, 0);
+}
+
+#[test]
+#[rustfmt::skip]
+fn graduation_balancer() {
+    let mut def = Def { auto_graduation: true, ..Default::default() };
+    def.flambe_settings[0].graduation_target = GraduationTarget::OsmosisBalancer { swap_fee: "0.003".into_decimal() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+    assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), Uint128::zero());
+    assert_eq!(app.qy_balance(&flambe, &token).unwrap().amount_raw(), Uint128::zero());
+
+    // The LP shares are burned
+    let lp = AssetInfoPrecisioned::native("gamm/pool/1", 18);
+    assert_eq!(app.qy_balance(&flambe, &lp).unwrap().amount_raw(), Uint128::zero());
+    assert_eq!(app.qy_balance(&def.burner, &lp).unwrap().amount_raw(), Uint128::new(BALANCER_INITIAL_SHARES));
+}
+
+#[test]
+#[rustfmt::skip]
+fn graduation_astroport() {
+    let mut def = Def { auto_graduation: true, ..Default::default() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let astroport_factory = instantiate_mock_astroport(&mut app, def.owner.clone());
+
+    let pcl_params = ConcentratedPoolParams {
+        amp: "40".into_decimal(),
+        gamma: "0.000145".into_decimal(),
+        mid_fee: "0.0026".into_decimal(),
+        out_fee: "0.045".into_decimal(),
+        fee_gamma: "0.00023".into_decimal(),
+        repeg_profit_threshold: "0.000002".into_decimal(),
+        min_price_scale_delta: "0.000146".into_decimal(),
+        price_scale: Decimal::one(),
+        ma_half_time: 600,
+        track_asset_balances: None,
+        fee_share: None,
+    };
+
+    let mut xyk_setting = def.flambe_settings[0].clone();
+    xyk_setting.graduation_target = GraduationTarget::AstroportXyk { factory: astroport_factory.to_string() };
+
+    let mut pcl_setting = def.flambe_settings[0].clone();
+    pcl_setting.graduation_target = GraduationTarget::AstroportPcl { factory: astroport_factory.to_string(), params: pcl_params.clone() };
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(vec![xyk_setting, pcl_setting]),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(120_000u128.into_decimal()));
+
+    for (index, symbol) in [(0, "xyk"), (1, "pcl")] {
+        run_create_flambe(&mut app, &def, creator.clone(), symbol.to_string(), index, create_input(symbol), vec![creation_fees(&def)]).unwrap();
+
+        let token = AssetInfoPrecisioned::native(format!("factory/{factory}/{symbol}"), 6);
+        let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+        run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(45_000u128.into_decimal())).unwrap();
+
+        let price = qy_flambe_info(&app, &flambe).unwrap().price;
+
+        run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(15_000u128.into_decimal())).unwrap();
+
+        assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+        assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), Uint128::zero());
+        assert_eq!(app.qy_balance(&flambe, &token).unwrap().amount_raw(), Uint128::zero());
+
+        let pair: PairInfo = app.wrap().query_wasm_smart(&astroport_factory, &AstroportFactoryQueryMsg::Pair {
+            asset_infos: vec![
+                AstroportAssetInfo::NativeToken { denom: token.info.inner() },
+                AstroportAssetInfo::NativeToken { denom: "uosmo".to_string() },
+            ],
+        }).unwrap();
+
+        // The liquidity is deployed at the graduation price and the LP is minted to the burner
+        let main_reserve = app.qy_balance(&pair.contract_addr, &token).unwrap().amount_raw();
+        let pair_reserve = app.qy_balance(&pair.contract_addr, &osmo).unwrap().amount_raw();
+        assert!(pair_reserve > def.flambe_settings[0].threshold);
+        assert!(Decimal::from_ratio(pair_reserve, main_reserve) > price);
+
+        let burner_share: Uint128 = app.wrap().query_wasm_smart(&pair.contract_addr, &MockPairQueryMsg::Share { address: def.burner.to_string() }).unwrap();
+        assert!(burner_share > Uint128::zero());
+
+        let params: Option<ConcentratedPoolParams> = app.wrap().query_wasm_smart(&pair.contract_addr, &MockPairQueryMsg::Params {}).unwrap();
+
+        match symbol {
+            "xyk" => {
+                assert_eq!(pair.pair_type, PairType::Xyk {});
+                assert_eq!(params, None);
+            }
+            _ => {
+                assert_eq!(pair.pair_type, PairType::Custom("concentrated".to_string()));
+                let params = params.unwrap();
+                assert_eq!(params.amp, pcl_params.amp);
+                assert!(params.price_scale > price);
+                assert!(pair_reserve - main_reserve.mul_floor(params.price_scale) <= Uint128::new(10));
+            }
+        }
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn graduation_astroport_existing_pair() {
+    let mut def = Def { auto_graduation: true, ..Default::default() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let astroport_factory = instantiate_mock_astroport(&mut app, def.owner.clone());
+
+    let mut xyk_setting = def.flambe_settings[0].clone();
+    xyk_setting.graduation_target = GraduationTarget::AstroportXyk { factory: astroport_factory.to_string() };
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(vec![xyk_setting]),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(180_000u128.into_decimal()));
+
+    let attacker = app.generate_addr("attacker");
+    app.mint(&attacker, osmo.to_asset(1_000u128.into_decimal()));
+
+    let mut pool_prices = vec![];
+
+    // The pair is empty, seeded with the main token overpriced and underpriced
+    for (symbol, seed_price) in [("aaa", None), ("bbb", Some(Decimal::one())), ("ccc", Some(Decimal::permille(1)))] {
+        run_create_flambe(&mut app, &def, creator.clone(), symbol.to_string(), 0, create_input(symbol), vec![creation_fees(&def)]).unwrap();
+
+        let token = AssetInfoPrecisioned::native(format!("factory/{factory}/{symbol}"), 6);
+        let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+        let asset_infos = vec![
+            AstroportAssetInfo::NativeToken { denom: token.info.inner() },
+            AstroportAssetInfo::NativeToken { denom: "uosmo".to_string() },
+        ];
+
+        // The pair is created before the graduation
+        app.execute_contract(attacker.clone(), astroport_factory.clone(), &AstroportFactoryExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: asset_infos.clone(),
+            init_params: None,
+        }, &[]).unwrap();
+
+        let pair: PairInfo = app.wrap().query_wasm_smart(&astroport_factory, &AstroportFactoryQueryMsg::Pair { asset_infos }).unwrap();
+
+        run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(45_000u128.into_decimal())).unwrap();
+
+        if let Some(seed_price) = seed_price {
+            run_swap(&mut app, &def, &attacker, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap();
+
+            let bought = app.qy_balance(&attacker, &token).unwrap().amount_raw();
+
+            let mut funds = vec![Coin::new(bought.u128(), token.info.inner()), Coin::new((bought * seed_price).u128(), "uosmo")];
+            funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+            app.execute_contract(attacker.clone(), pair.contract_addr.clone(), &AstroportPairExecuteMsg::ProvideLiquidity {
+                assets: funds.iter().map(|coin| AstroportAsset { info: AstroportAssetInfo::NativeToken { denom: coin.denom.clone() }, amount: coin.amount }).collect(),
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+            }, &funds).unwrap();
+        }
+
+        let price = qy_flambe_info(&app, &flambe).unwrap().price;
+
+        run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(15_000u128.into_decimal())).unwrap();
+
+        assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+        assert_eq!(app.qy_balance(&flambe, &osmo).unwrap().amount_raw(), Uint128::zero());
+        assert_eq!(app.qy_balance(&flambe, &token).unwrap().amount_raw(), Uint128::zero());
+
+        // The liquidity is provided into the existing pair, brought to the graduation price
+        let main_reserve = app.qy_balance(&pair.contract_addr, &token).unwrap().amount_raw();
+        let pair_reserve = app.qy_balance(&pair.contract_addr, &osmo).unwrap().amount_raw();
+        assert!(pair_reserve > def.flambe_settings[0].threshold);
+        assert!(Decimal::from_ratio(pair_reserve, main_reserve) > price);
+
+        let burner_share: Uint128 = app.wrap().query_wasm_smart(&pair.contract_addr, &MockPairQueryMsg::Share { address: def.burner.to_string() }).unwrap();
+        assert!(burner_share > Uint128::zero());
+
+        pool_prices.push(Decimal::from_ratio(pair_reserve, main_reserve));
+    }
+
+    // The commission of the rebalancing swap stays in the seeded pairs
+    for pool_price in &pool_prices[1..] {
+        assert!(pool_price.abs_diff(pool_prices[0]) < pool_prices[0] * Decimal::percent(5));
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn position_lock() {
+    let mut def = Def { auto_graduation: true, ..Default::default() };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    // Not graduated yet
+    qy_position_lock(&app, &flambe).unwrap_err();
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+
+    let lock = qy_position_lock(&app, &flambe).unwrap();
+    assert_eq!(lock.owner, flambe.to_string());
+    assert_eq!(lock.admin, None);
+    assert!(lock.locked);
+
+    // The only position of the pool can't be transferred, even by the flambe
+    app.execute(flambe.clone(), MsgTransferPositions {
+        position_ids: vec![lock.position_id],
+        sender: flambe.to_string(),
+        new_owner: def.burner.to_string(),
+    }.into()).unwrap_err_contains("last position in pool");
+
+    // Closed flambes are skipped by the migration
+    let code_id = app.wrap().query_wasm_contract_info(&flambe).unwrap().code_id;
+    let new_code_id = store_flambe_code(&mut app);
+
+    let res = run_migrate_flambes(&mut app, &def, &def.owner, new_code_id, FlambeMigrateMsg::Version {}, None, None).unwrap();
+    assert_eq!(res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "migrated").unwrap().value, "0");
+    assert_eq!(app.wrap().query_wasm_contract_info(&flambe).unwrap().code_id, code_id);
+
+    app.migrate_contract(factory.clone(), flambe.clone(), &FlambeMigrateMsg::Version {}, new_code_id).unwrap_err();
+
+    // A flambe administrated by the owner can't be locked by the factory
+    run_create_flambe(&mut app, &def, creator.clone(), "bbb".to_string(), 0, create_input("bbb"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/bbb"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    app.execute(factory.clone(), WasmMsg::UpdateAdmin { contract_addr: flambe.to_string(), admin: def.owner.to_string() }.into()).unwrap();
+
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    let res = run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+    assert_eq!(res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "position_lock").unwrap().value, "incomplete");
+
+    let lock = qy_position_lock(&app, &flambe).unwrap();
+    assert_eq!(lock.owner, flambe.to_string());
+    assert_eq!(lock.admin, Some(def.owner.to_string()));
+    assert!(!lock.locked);
+}
+
+#[test]
+#[rustfmt::skip]
+fn collect_fees() {
+    let mut def = Def {
+        auto_graduation: true,
+        lp_fees_split: LpFeesSplit { creator: "0.5".into_decimal(), protocol: "0.3".into_decimal(), burn: "0.2".into_decimal() },
+        ..Default::default()
+    };
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    // The split must sum to 1
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        lp_fees_split: Some(LpFeesSplit { creator: "0.5".into_decimal(), protocol: "0.3".into_decimal(), burn: "0.3".into_decimal() }),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Lp fees split must sum to 1");
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let keeper = app.generate_addr("keeper");
+
+    // Only graduated CL flambes have a position
+    run_collect_fees(&mut app, &keeper, &flambe).unwrap_err_contains("No CL position to collect fees from");
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(60_000u128.into_decimal()));
+
+    // Emulate a flambe graduated before the CL ids were stored, still administrated by the owner
+    app.execute(factory.clone(), WasmMsg::UpdateAdmin { contract_addr: flambe.to_string(), admin: def.owner.to_string() }.into()).unwrap();
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+    assert_eq!(qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().status, FlambeStatus::CLOSED);
+
+    let lock = qy_position_lock(&app, &flambe).unwrap();
+
+    let namespace = to_length_prefixed_nested(&[b"wasm", format!("contract_data/{flambe}").as_bytes()]);
+    for key in [b"cl_pool_id".as_slice(), b"cl_position_id".as_slice()] {
+        app.storage_mut().remove(&[namespace.as_slice(), key].concat());
+    }
+
+    run_collect_fees(&mut app, &keeper, &flambe).unwrap_err_contains("No CL position to collect fees from");
+
+    // The ids are backfilled from the positions of the flambe
+    let code_id = app.wrap().query_wasm_contract_info(&flambe).unwrap().code_id;
+    let res = app.migrate_contract(def.owner.clone(), flambe.clone(), &FlambeMigrateMsg::BackfillPosition {}, code_id).unwrap();
+    assert_eq!(res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == "position_id").unwrap().value, lock.position_id.to_string());
+    assert_eq!(qy_position_lock(&app, &flambe).unwrap(), lock);
+
+    run_collect_fees(&mut app, &keeper, &flambe).unwrap_err_contains("No fees to collect");
+
+    let pool_id = qy_position_lock(&app, &flambe).unwrap().pool_id;
+    let time = app.block_info().time.seconds();
+
+    MockGamm::use_db(app.storage_mut(), |db, _| {
+        db.set_pool_rewards(pool_id, time, vec![Coin::new(100, "uosmo")], vec![Coin::new(10, "uion")]).unwrap();
+    })
+    .unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(1_000));
+
+    let balances = |app: &OsmosisApp, denom: &str| [&creator, &def.fee_collector, &def.burner].map(|addr| app.wrap().query_balance(addr, denom).unwrap().amount.u128());
+
+    let osmo_before = balances(&app, "uosmo");
+    let ion_before = balances(&app, "uion");
+
+    run_collect_fees(&mut app, &keeper, &flambe).unwrap();
+
+    let osmo_after = balances(&app, "uosmo");
+    let ion_after = balances(&app, "uion");
+
+    // Creator, protocol and burn
+    assert_eq!([0, 1, 2].map(|i| osmo_after[i] - osmo_before[i]), [50_000, 30_000, 20_000]);
+    assert_eq!([0, 1, 2].map(|i| ion_after[i] - ion_before[i]), [5_000, 3_000, 2_000]);
+    assert_eq!(app.wrap().query_balance(&flambe, "uion").unwrap().amount, Uint128::zero());
+
+    // Everything claimable was collected
+    run_collect_fees(&mut app, &keeper, &flambe).unwrap_err_contains("No fees to collect");
+}
+
+#[test]
+#[rustfmt::skip]
+fn cookie_emission() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let start = app.block_info().time.seconds();
+
+    // Without a schedule the rewards are only capped to the factory balance
+    let emission = qy_cookie_emission(&app, &def).unwrap();
+    assert_eq!(emission.epoch, None);
+    assert_eq!(emission.buy_ratio, def.cookie_ratio);
+    assert_eq!(emission.sell_ratio, def.cookie_sell_ratio);
+    assert_eq!(emission.remaining_budget, Uint128::zero());
+
+    let schedule = CookieEmission { start_time: start, epoch_duration: 100, epoch_budget: Uint128::new(100_000), halving_epochs: 1, end_time: start + 200 };
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        cookie_emission: Some(CookieEmission { epoch_duration: 0, ..schedule.clone() }),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Epoch duration can't be 0");
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        cookie_emission: Some(schedule.clone()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(500_000, cookie.info.inner())]).unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let emission = qy_cookie_emission(&app, &def).unwrap();
+    assert_eq!(emission.epoch, Some(0));
+    assert_eq!(emission.epoch_budget, Some(Uint128::new(100_000)));
+    assert_eq!(emission.remaining_budget, Uint128::new(100_000));
+    assert_eq!(emission.end_time, Some(start + 200));
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(1_000u128.into_decimal()));
+
+    // Buy
+    let amount_in = Uint128::new(500_000);
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(amount_in)).unwrap();
+
+    let buy_reward = amount_in * def.swap_fee * def.cookie_ratio;
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), buy_reward);
+
+    // Sell, rewarded on the fee paid in pair denom
+    let amount_in = app.qy_balance(&user, &token).unwrap().amount_raw() / Uint128::new(2);
+    let simulate = qy_simulate(&app, &flambe, &token, amount_in).unwrap();
+
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, token.to_asset(amount_in)).unwrap();
+
+    let sell_reward = simulate.swap_fee.amount * def.cookie_sell_ratio;
+    assert!(sell_reward > Uint128::zero());
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), buy_reward + sell_reward);
+    assert_eq!(qy_cookie_emission(&app, &def).unwrap().remaining_budget, Uint128::new(100_000) - buy_reward - sell_reward);
+
+    // The epoch budget caps the rewards
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap();
+
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), Uint128::new(100_000));
+    assert_eq!(qy_cookie_emission(&app, &def).unwrap().remaining_budget, Uint128::zero());
+
+    // Updating the schedule doesn't restore the budget of the running epoch
+    for schedule in [schedule.clone(), CookieEmission { epoch_duration: 50, end_time: start + 100, ..schedule.clone() }, schedule.clone()] {
+        _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+            cookie_emission: Some(schedule),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(qy_cookie_emission(&app, &def).unwrap().remaining_budget, Uint128::zero());
+    }
+
+    // Next epoch, budget and ratios are halved
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let emission = qy_cookie_emission(&app, &def).unwrap();
+    assert_eq!(emission.epoch, Some(1));
+    assert_eq!(emission.buy_ratio, def.cookie_ratio * "0.5".into_decimal());
+    assert_eq!(emission.sell_ratio, def.cookie_sell_ratio * "0.5".into_decimal());
+    assert_eq!(emission.remaining_budget, Uint128::new(50_000));
+
+    let amount_in = Uint128::new(500_000);
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(amount_in)).unwrap();
+
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), Uint128::new(100_000) + amount_in * def.swap_fee * emission.buy_ratio);
+
+    // No rewards after the end of the emission
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let emission = qy_cookie_emission(&app, &def).unwrap();
+    assert_eq!(emission.epoch, None);
+    assert_eq!(emission.remaining_budget, Uint128::zero());
+
+    let cookies_before = app.qy_balance(&user, &cookie).unwrap().amount_raw();
+    run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(1u128.into_decimal())).unwrap();
+
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), cookies_before);
+}
+
+#[test]
+#[rustfmt::skip]
+fn fee_split() {
+    let mut def = Def::default();
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+    let stakers = app.generate_addr("cookie_staking");
+
+    let split = FeeSplit { creator: "0.4".into_decimal(), protocol: "0.3".into_decimal(), burn: "0.2".into_decimal(), stakers: "0.1".into_decimal() };
+
+    // The stakers share requires the cookie staking
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        fee_split: Some(split.clone()),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Cookie staking is required for the stakers fee share");
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        cookie_staking: Some(stakers.to_string()),
+        fee_split: Some(FeeSplit { protocol: "0.4".into_decimal(), ..split.clone() }),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Fee split must sum to 1");
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        cookie_staking: Some(stakers.to_string()),
+        fee_split: Some(split.clone()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/aaa"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    assert_eq!(qy_flambe_config(&app, &flambe).unwrap().fee_split, split);
+
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(1_000u128.into_decimal()));
+
+    let collector_before = app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_raw();
+    let info_before = qy_flambe_info(&app, &flambe).unwrap();
+
+    let swap = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+    let fee = swap.fee.amount_raw();
+
+    assert_eq!(app.qy_balance(&def.fee_collector, &osmo).unwrap().amount_raw(), collector_before + fee * "0.3".into_decimal());
+    assert_eq!(app.qy_balance(&stakers, &osmo).unwrap().amount_raw(), fee * "0.1".into_decimal());
+    assert_eq!(qy_flambe_config(&app, &flambe).unwrap().accrued_creator_fees, fee * "0.4".into_decimal());
+
+    // The burn share stays in the reserve and the bought tokens are sent to the burner
+    let burned = app.qy_balance(&def.burner, &token).unwrap().amount_raw();
+    assert!(burned > Uint128::zero());
+
+    let info = qy_flambe_info(&app, &flambe).unwrap();
+    assert_eq!(info.pair_amount, info_before.pair_amount + swap.input.amount_raw() - fee + fee * "0.2".into_decimal());
+    assert_eq!(info.main_amount, info_before.main_amount - swap.output.amount_raw() - burned);
+
+    // Only the creator can claim the accrued fees
+    run_claim_creator_fees(&mut app, &user, &flambe).unwrap_err_contains("Unauthorized");
+
+    let creator_before = app.qy_balance(&creator, &osmo).unwrap().amount_raw();
+
+    run_claim_creator_fees(&mut app, &creator, &flambe).unwrap();
+    run_claim_creator_fees(&mut app, &creator, &flambe).unwrap_err_contains("No creator fees to claim");
+
+    assert_eq!(app.qy_balance(&creator, &osmo).unwrap().amount_raw(), creator_before + fee * "0.4".into_decimal());
+    assert_eq!(qy_flambe_info(&app, &flambe).unwrap().pair_amount, info.pair_amount);
+
+    // The flambe setting overrides the factory split
+    let mut flambe_settings = def.flambe_settings.clone();
+    flambe_settings[0].fee_split = Some(FeeSplit { creator: Decimal::one(), protocol: Decimal::zero(), burn: Decimal::zero(), stakers: Decimal::zero() });
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(flambe_settings),
+        ..Default::default()
+    })
+    .unwrap();
+
+    run_create_flambe(&mut app, &def, creator.clone(), "bbb".to_string(), 0, create_input("bbb"), vec![creation_fees(&def)]).unwrap();
+
+    let token = AssetInfoPrecisioned::native(format!("factory/{factory}/bbb"), 6);
+    let flambe = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(token.info.inner())).unwrap().flambe_address;
+
+    let swap = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &flambe, 0_u128, osmo.to_asset(100u128.into_decimal())).unwrap());
+
+    assert_eq!(qy_flambe_config(&app, &flambe).unwrap().accrued_creator_fees, swap.fee.amount_raw());
+}
+
+#[test]
+#[rustfmt::skip]
+fn fee_schedule() {
+    let mut def = Def::default();
+    def.flambe_settings[0].fee_schedule = Some(FeeSchedule { initial_fee: "0.1".into_decimal(), duration: 1_000, decay: FeeDecay::Linear });
+
+    let mut step_setting = def.flambe_settings[0].clone();
+    step_setting.fee_schedule = Some(FeeSchedule { initial_fee: "0.09".into_decimal(), duration: 1_000, decay: FeeDecay::Step { steps: 4 } });
+    def.flambe_settings.push(step_setting.clone());
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    // The schedule can't start below the swap fee
+    let mut flambe_settings = def.flambe_settings.clone();
+    flambe_settings[1].fee_schedule = Some(FeeSchedule { initial_fee: "0.005".into_decimal(), duration: 1_000, decay: FeeDecay::Linear });
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        flambe_settings: Some(flambe_settings),
+        ..Default::default()
+    })
+    .unwrap_err_contains("Initial fee can't be lower then the swap fee");
+
+    let cookie = AssetInfoPrecisioned::native(_qy_factory_config(&app, &def).cookie_token.denom, 6);
+    app.send_tokens(def.owner.clone(), factory.clone(), &[Coin::new(1_000_000, cookie.info.inner())]).unwrap();
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+    run_create_flambe(&mut app, &def, creator.clone(), "bbb".to_string(), 1, create_input("bbb"), vec![creation_fees(&def)]).unwrap();
+
+    let linear = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/aaa"))).unwrap().flambe_address;
+    let step = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/bbb"))).unwrap().flambe_address;
+
+    let amount = Uint128::new(1_000_000);
+    let fee_at = |app: &OsmosisApp, flambe| qy_simulate(app, flambe, &osmo, amount).unwrap().swap_fee.amount;
+
+    assert_eq!(fee_at(&app, &linear), amount * "0.1".into_decimal());
+    assert_eq!(fee_at(&app, &step), amount * "0.09".into_decimal());
+
+    // Linear decay, the step one is still on the first step
+    app.update_block(|block| block.time = block.time.plus_seconds(200));
+
+    assert_eq!(fee_at(&app, &linear), amount * "0.082".into_decimal());
+    assert_eq!(fee_at(&app, &step), amount * "0.09".into_decimal());
+
+    app.update_block(|block| block.time = block.time.plus_seconds(300));
+
+    assert_eq!(fee_at(&app, &linear), amount * "0.055".into_decimal());
+    assert_eq!(fee_at(&app, &step), amount * "0.05".into_decimal());
+
+    // The swaps and the cookie rewards follow the schedule
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(100u128.into_decimal()));
+
+    let swap = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &linear, 0_u128, osmo.to_asset(amount)).unwrap());
+
+    assert_eq!(swap.fee.amount_raw(), amount * "0.055".into_decimal());
+    assert_eq!(app.qy_balance(&user, &cookie).unwrap().amount_raw(), swap.fee.amount_raw() * def.cookie_ratio);
+
+    // Down to the swap fee after the duration
+    app.update_block(|block| block.time = block.time.plus_seconds(500));
+
+    assert_eq!(fee_at(&app, &linear), amount * def.swap_fee);
+    assert_eq!(fee_at(&app, &step), amount * def.swap_fee);
+}
+
+#[test]
+#[rustfmt::skip]
+fn propagate_config() {
+    let mut def = Def::default();
+    def.flambe_settings[0].deadline = Some(3_600);
+
+    let osmo = AssetInfoPrecisioned::native("uosmo", 6);
+
+    let mut app = startup(&mut def);
+
+    let factory = def.factory_address.unclone();
+
+    let creator = app.generate_addr("creator");
+    let keeper = app.generate_addr("keeper");
+    app.mint(&creator, osmo.to_asset(10_000u128.into_decimal()));
+
+    // Failed flambes still sell along the curve
+    run_create_flambe(&mut app, &def, creator.clone(), "aaa".to_string(), 0, create_input("aaa"), vec![creation_fees(&def)]).unwrap();
+    let failed = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/aaa"))).unwrap().flambe_address;
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3_600));
+    run_fail_flambe(&mut app, &keeper, &failed).unwrap();
+
+    for subdenom in ["bbb", "ccc", "ddd", "eee"] {
+        run_create_flambe(&mut app, &def, creator.clone(), subdenom.to_string(), 0, create_input(subdenom), vec![creation_fees(&def)]).unwrap();
+    }
+
+    let open = ["bbb", "ccc"].map(|subdenom| qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/{subdenom}"))).unwrap().flambe_address);
+    let pending = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/ddd"))).unwrap().flambe_address;
+    let legacy = qy_factory_flambe(&app, &def, FlambeFilter::ByTokenDenom(format!("factory/{factory}/eee"))).unwrap().flambe_address;
+
+    let whale = app.generate_addr("whale");
+    app.mint(&whale, osmo.to_asset(60_000u128.into_decimal()));
+    run_swap(&mut app, &def, &whale, &pending, 0_u128, osmo.to_asset(60_000u128.into_decimal())).unwrap();
+    assert_eq!(qy_flambe_config(&app, &pending).unwrap().status, FlambeStatus::PENDING);
+
+    // Emulate a flambe running an older code
+    let legacy_code_id = store_flambe_code(&mut app);
+    app.migrate_contract(factory.clone(), legacy.clone(), &FlambeMigrateMsg::Version {}, legacy_code_id).unwrap();
+
+    let new_fee_collector = app.generate_addr("new_fee_collector");
+    let new_burner = app.generate_addr("new_burner");
+    let new_swap_fee = "0.02".into_decimal();
+
+    _update_flambe_factory_config(&mut app, &def, UpdateConfigMsg {
+        burner: Some(new_burner.to_string()),
+        fee_collector: Some(new_fee_collector.to_string()),
+        swap_fee: Some(new_swap_fee),
+        ..Default::default()
+    })
+    .unwrap();
+
+    // Only the factory can update a flambe
+    app.execute_contract(creator.clone(), open[0].clone(), &ratatouille_pkg::flambe::msgs::ExecuteMsg::UpdateConfig { burner_addr: None, fee_collector: None, swap_fee: Some(new_swap_fee) }, &[])
+        .unwrap_err_contains("Unauthorized");
+
+    let random = app.generate_addr("random");
+    run_propagate_config(&mut app, &def, &random, vec![PropagatedField::SwapFee], None, None).unwrap_err_contains("Unauthorized");
+    run_propagate_config(&mut app, &def, &def.owner, vec![], None, None).unwrap_err_contains("Invalid Empty Update");
+
+    // First page, only the swap fee and the fee collector
+    let res = run_propagate_config(&mut app, &def, &def.owner, vec![PropagatedField::SwapFee, PropagatedField::FeeCollector], None, Some(2)).unwrap();
+    let attribute = |key: &str| res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == key).map(|attr| attr.value.clone());
+
+    assert_eq!(attribute("updated").unwrap(), "2");
+    assert_eq!(attribute("skipped"), None);
+    assert_eq!(attribute("swap_fee").unwrap(), new_swap_fee.to_string());
+    assert_eq!(attribute("burner"), None);
+    let last = attribute("last").unwrap();
+
+    for flambe in [&failed, &open[0]] {
+        let config = qy_flambe_config(&app, flambe).unwrap();
+        assert_eq!(config.swap_fee, new_swap_fee);
+        assert_eq!(config.fee_collector, new_fee_collector);
+        assert_eq!(config.burner_addr, def.burner);
+    }
+
+    assert_eq!(qy_flambe_config(&app, &open[1]).unwrap().swap_fee, def.swap_fee);
+
+    // Second page, the pending flambe keeps its swap fee and the legacy one is skipped
+    let res = run_propagate_config(&mut app, &def, &def.owner, vec![PropagatedField::SwapFee, PropagatedField::FeeCollector, PropagatedField::Burner], Some(last), Some(3)).unwrap();
+    let attribute = |key: &str| res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == key).map(|attr| attr.value.clone());
+
+    assert_eq!(attribute("updated").unwrap(), "2");
+    assert_eq!(attribute("skipped").unwrap(), legacy.to_string());
+
+    let config = qy_flambe_config(&app, &open[1]).unwrap();
+    assert_eq!(config.swap_fee, new_swap_fee);
+    assert_eq!(config.fee_collector, new_fee_collector);
+    assert_eq!(config.burner_addr, new_burner);
+
+    let config = qy_flambe_config(&app, &pending).unwrap();
+    assert_eq!(config.swap_fee, def.swap_fee);
+    assert_eq!(config.fee_collector, new_fee_collector);
+    assert_eq!(config.burner_addr, new_burner);
+
+    let config = qy_flambe_config(&app, &legacy).unwrap();
+    assert_eq!(config.swap_fee, def.swap_fee);
+    assert_eq!(config.fee_collector, def.fee_collector);
+
+    // The swaps are charged with the propagated fee
+    let user = app.generate_addr("user");
+    app.mint(&user, osmo.to_asset(100u128.into_decimal()));
+
+    let amount = Uint128::new(1_000_000);
+    let swap = parse_swap_output_from_response(run_swap(&mut app, &def, &user, &open[1], 0_u128, osmo.to_asset(amount)).unwrap());
+    assert_eq!(swap.fee.amount_raw(), amount * new_swap_fee);
+}
@@ -1,5 +1,5 @@
 #[cfg(test)]
-mod helpers;
+pub mod helpers;
 #[cfg(test)]
 mod integration_test;
 
@@ -1,9 +1,18 @@
 #[cfg(test)]
+mod cookie_staking;
+#[cfg(test)]
 mod flambe;
 #[cfg(test)]
 mod flambe_factory;
+#[cfg(test)]
+mod ratatouille;
+#[cfg(test)]
+mod ratatouille_factory;
 
 #[cfg(test)]
 mod helpers;
 
+#[cfg(test)]
+mod mock_astroport;
+#[cfg(test)]
 mod mock_gamm;
@@ -0,0 +1,421 @@
+use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::factory::{ExecuteMsg as FactoryExecuteMsg, PairType, QueryMsg as FactoryQueryMsg};
+use astroport::pair::{ExecuteMsg as PairExecuteMsg, PoolResponse, SimulationResponse};
+use astroport::pair_concentrated::ConcentratedPoolParams;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, Isqrt,
+    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128, Uint256,
+    WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+use rhaki_cw_plus::multi_test::helper::{
+    create_code, create_code_with_reply, cw_multi_test::Executor,
+};
+
+use crate::helpers::OsmosisApp;
+
+const PAIR_CODE_ID: Item<u64> = Item::new("pair_code_id");
+const PAIRS: Map<String, PairInfo> = Map::new("pairs");
+const TMP_PAIR: Item<(Vec<AssetInfo>, PairType)> = Item::new("tmp_pair");
+
+const PAIR_INFO: Item<PairInfo> = Item::new("pair_info");
+const POOL_PARAMS: Item<Option<ConcentratedPoolParams>> = Item::new("pool_params");
+const TOTAL_SHARE: Item<Uint128> = Item::new("total_share");
+const SHARES: Map<String, Uint128> = Map::new("shares");
+
+/// Commission of the mock pairs, as the Astroport xyk default
+const SWAP_COMMISSION_PERMILLE: u64 = 3;
+
+#[cw_serde]
+pub struct MockFactoryInstantiateMsg {
+    pub pair_code_id: u64,
+}
+
+#[cw_serde]
+pub struct MockPairInstantiateMsg {
+    pub asset_infos: Vec<AssetInfo>,
+    pub pair_type: PairType,
+    pub init_params: Option<Binary>,
+}
+
+#[cw_serde]
+pub enum MockPairQueryMsg {
+    Pair {},
+    Pool {},
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    /// Concentrated params, if the pair is a PCL
+    Params {},
+    Share {
+        address: String,
+    },
+}
+
+/// Store the mock factory and pair codes, returns the address of the factory
+pub fn instantiate_mock_astroport(app: &mut OsmosisApp, owner: Addr) -> Addr {
+    let pair_code_id = app.store_code(create_code(pair_instantiate, pair_execute, pair_query));
+
+    let factory_code_id = app.store_code(create_code_with_reply(
+        factory_instantiate,
+        factory_execute,
+        factory_query,
+        factory_reply,
+    ));
+
+    app.instantiate_contract(
+        factory_code_id,
+        owner,
+        &MockFactoryInstantiateMsg { pair_code_id },
+        &[],
+        "Astroport Factory",
+        None,
+    )
+    .unwrap()
+}
+
+fn pair_key(asset_infos: &[AssetInfo]) -> String {
+    let mut denoms: Vec<String> = asset_infos.iter().map(|val| val.to_string()).collect();
+    denoms.sort();
+    denoms.join("-")
+}
+
+fn factory_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockFactoryInstantiateMsg,
+) -> StdResult<Response> {
+    PAIR_CODE_ID.save(deps.storage, &msg.pair_code_id)?;
+    Ok(Response::new())
+}
+
+fn factory_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: FactoryExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        FactoryExecuteMsg::CreatePair {
+            pair_type,
+            asset_infos,
+            init_params,
+        } => {
+            if PAIRS.has(deps.storage, pair_key(&asset_infos)) {
+                return Err(StdError::generic_err("Pair was already created"));
+            }
+
+            TMP_PAIR.save(deps.storage, &(asset_infos.clone(), pair_type.clone()))?;
+
+            let msg = WasmMsg::Instantiate {
+                admin: None,
+                code_id: PAIR_CODE_ID.load(deps.storage)?,
+                msg: to_json_binary(&MockPairInstantiateMsg {
+                    asset_infos,
+                    pair_type,
+                    init_params,
+                })?,
+                funds: vec![],
+                label: "Astroport pair".to_string(),
+            };
+
+            Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, 1)))
+        }
+        _ => Err(StdError::generic_err("Unsupported msg")),
+    }
+}
+
+fn factory_reply(deps: DepsMut, _env: Env, reply: Reply) -> StdResult<Response> {
+    let SubMsgResult::Ok(result) = reply.result else {
+        return Err(StdError::generic_err("Unexpected error on reply"));
+    };
+
+    let contract_addr = result
+        .events
+        .iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "_contract_address")
+        .ok_or(StdError::generic_err("Pair address not found"))?
+        .value
+        .clone();
+
+    let contract_addr = deps.api.addr_validate(&contract_addr)?;
+
+    let (asset_infos, pair_type) = TMP_PAIR.load(deps.storage)?;
+    TMP_PAIR.remove(deps.storage);
+
+    PAIRS.save(
+        deps.storage,
+        pair_key(&asset_infos),
+        &PairInfo {
+            asset_infos,
+            liquidity_token: contract_addr.clone(),
+            contract_addr,
+            pair_type,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+fn factory_query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> StdResult<Binary> {
+    match msg {
+        FactoryQueryMsg::Pair { asset_infos } => {
+            to_json_binary(&PAIRS.load(deps.storage, pair_key(&asset_infos))?)
+        }
+        _ => Err(StdError::generic_err("Unsupported query")),
+    }
+}
+
+fn pair_instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: MockPairInstantiateMsg,
+) -> StdResult<Response> {
+    let pool_params = match (&msg.pair_type, msg.init_params) {
+        (PairType::Custom(pair_type), Some(init_params)) if pair_type == "concentrated" => {
+            Some(from_json::<ConcentratedPoolParams>(init_params)?)
+        }
+        (PairType::Xyk {}, None) => None,
+        _ => return Err(StdError::generic_err("Invalid init params")),
+    };
+
+    PAIR_INFO.save(
+        deps.storage,
+        &PairInfo {
+            asset_infos: msg.asset_infos,
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: env.contract.address,
+            pair_type: msg.pair_type,
+        },
+    )?;
+
+    POOL_PARAMS.save(deps.storage, &pool_params)?;
+    TOTAL_SHARE.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new())
+}
+
+fn pair_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: PairExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        PairExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            receiver,
+            ..
+        } => {
+            let pair_info = PAIR_INFO.load(deps.storage)?;
+
+            let mut deposits = vec![];
+            let mut reserves = vec![];
+
+            for asset_info in &pair_info.asset_infos {
+                let AssetInfo::NativeToken { denom } = asset_info else {
+                    return Err(StdError::generic_err("Only native tokens are supported"));
+                };
+
+                let amount = assets
+                    .iter()
+                    .find(|asset: &&Asset| asset.info == *asset_info)
+                    .map(|asset| asset.amount)
+                    .unwrap_or_default();
+
+                let sent = info
+                    .funds
+                    .iter()
+                    .find(|coin| coin.denom == *denom)
+                    .map(|coin| coin.amount)
+                    .unwrap_or_default();
+
+                if amount.is_zero() || amount != sent {
+                    return Err(StdError::generic_err(format!("Invalid deposit of {denom}")));
+                }
+
+                let balance = deps
+                    .querier
+                    .query_balance(&env.contract.address, denom)?
+                    .amount;
+
+                deposits.push(amount);
+                reserves.push(balance - amount);
+            }
+
+            let total_share = TOTAL_SHARE.load(deps.storage)?;
+
+            // Same check of the deposit ratio against the reserves done by the Astroport pairs
+            if let (Some(tolerance), false) = (slippage_tolerance, total_share.is_zero()) {
+                let one_minus_tolerance = Decimal::one() - tolerance;
+
+                if Decimal::from_ratio(deposits[0], deposits[1]) * one_minus_tolerance
+                    > Decimal::from_ratio(reserves[0], reserves[1])
+                    || Decimal::from_ratio(deposits[1], deposits[0]) * one_minus_tolerance
+                        > Decimal::from_ratio(reserves[1], reserves[0])
+                {
+                    return Err(StdError::generic_err(
+                        "Operation exceeds max splippage tolerance",
+                    ));
+                }
+            }
+
+            let share = if total_share.is_zero() {
+                Uint128::try_from(
+                    (Uint256::from(deposits[0]) * Uint256::from(deposits[1])).isqrt(),
+                )?
+            } else {
+                std::cmp::min(
+                    deposits[0].multiply_ratio(total_share, reserves[0]),
+                    deposits[1].multiply_ratio(total_share, reserves[1]),
+                )
+            };
+
+            let receiver = receiver.unwrap_or(info.sender.to_string());
+
+            SHARES.update(deps.storage, receiver.clone(), |val| -> StdResult<_> {
+                Ok(val.unwrap_or_default() + share)
+            })?;
+            TOTAL_SHARE.save(deps.storage, &(total_share + share))?;
+
+            Ok(Response::new()
+                .add_attribute("action", "provide_liquidity")
+                .add_attribute("receiver", receiver)
+                .add_attribute("share", share))
+        }
+        PairExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread,
+            to,
+            ..
+        } => {
+            let AssetInfo::NativeToken { denom } = &offer_asset.info else {
+                return Err(StdError::generic_err("Only native tokens are supported"));
+            };
+
+            if info.funds.len() != 1
+                || info.funds[0].denom != *denom
+                || info.funds[0].amount != offer_asset.amount
+            {
+                return Err(StdError::generic_err("Invalid offer"));
+            }
+
+            let (ask_denom, return_amount) =
+                simulate_swap(deps.as_ref(), &env, &offer_asset, true)?;
+
+            // Same spread check done by the Astroport pairs against the belief price
+            if let (Some(belief_price), Some(max_spread)) = (belief_price, max_spread) {
+                let expected_return = offer_asset.amount * (Decimal::one() / belief_price);
+
+                if return_amount < expected_return
+                    && Decimal::from_ratio(expected_return - return_amount, expected_return)
+                        > max_spread
+                {
+                    return Err(StdError::generic_err("Operation exceeds max spread limit"));
+                }
+            }
+
+            Ok(Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: to.unwrap_or(info.sender.to_string()),
+                    amount: vec![Coin::new(return_amount.u128(), ask_denom)],
+                })
+                .add_attribute("action", "swap")
+                .add_attribute("return_amount", return_amount))
+        }
+        _ => Err(StdError::generic_err("Unsupported msg")),
+    }
+}
+
+/// Constant product swap, with the commission kept in the pool
+fn simulate_swap(
+    deps: Deps,
+    env: &Env,
+    offer_asset: &Asset,
+    offer_received: bool,
+) -> StdResult<(String, Uint128)> {
+    let pair_info = PAIR_INFO.load(deps.storage)?;
+
+    let mut offer_reserve = Uint128::zero();
+    let mut ask = None;
+
+    for asset_info in &pair_info.asset_infos {
+        let AssetInfo::NativeToken { denom } = asset_info else {
+            return Err(StdError::generic_err("Only native tokens are supported"));
+        };
+
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount;
+
+        if *asset_info == offer_asset.info {
+            offer_reserve = if offer_received {
+                balance - offer_asset.amount
+            } else {
+                balance
+            };
+        } else {
+            ask = Some((denom.clone(), balance));
+        }
+    }
+
+    let (ask_denom, ask_reserve) = ask.ok_or(StdError::generic_err("Invalid offer asset"))?;
+
+    let return_amount =
+        ask_reserve.multiply_ratio(offer_asset.amount, offer_reserve + offer_asset.amount);
+
+    Ok((
+        ask_denom,
+        return_amount - return_amount * Decimal::permille(SWAP_COMMISSION_PERMILLE),
+    ))
+}
+
+fn pair_query(deps: Deps, env: Env, msg: MockPairQueryMsg) -> StdResult<Binary> {
+    match msg {
+        MockPairQueryMsg::Pair {} => to_json_binary(&PAIR_INFO.load(deps.storage)?),
+        MockPairQueryMsg::Pool {} => {
+            let pair_info = PAIR_INFO.load(deps.storage)?;
+
+            let assets = pair_info
+                .asset_infos
+                .iter()
+                .map(|info| {
+                    Ok(Asset {
+                        info: info.clone(),
+                        amount: deps
+                            .querier
+                            .query_balance(&env.contract.address, info.to_string())?
+                            .amount,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+
+            to_json_binary(&PoolResponse {
+                assets,
+                total_share: TOTAL_SHARE.load(deps.storage)?,
+            })
+        }
+        MockPairQueryMsg::Simulation { offer_asset, .. } => {
+            let (_, return_amount) = simulate_swap(deps, &env, &offer_asset, false)?;
+
+            to_json_binary(&SimulationResponse {
+                return_amount,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            })
+        }
+        MockPairQueryMsg::Params {} => to_json_binary(&POOL_PARAMS.load(deps.storage)?),
+        MockPairQueryMsg::Share { address } => {
+            to_json_binary(&SHARES.may_load(deps.storage, address)?.unwrap_or_default())
+        }
+    }
+}
@@ -24,6 +24,10 @@ use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 use osmosis_std::types::osmosis::concentratedliquidity::poolmodel::concentrated::v1beta1::MsgCreateConcentratedPool;
 use osmosis_std::types::osmosis::concentratedliquidity::poolmodel::concentrated::v1beta1::MsgCreateConcentratedPoolResponse;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::FullPositionBreakdown;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCollectIncentives;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCollectIncentivesResponse;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCollectSpreadRewards;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCollectSpreadRewardsResponse;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCreatePosition;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCreatePositionResponse;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgTransferPositions;
@@ -32,6 +36,10 @@ use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgWithdrawPosi
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::Position as ProtoPosition;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::PositionByIdRequest;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::PositionByIdResponse;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::UserPositionsRequest;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::UserPositionsResponse;
+use osmosis_std::types::osmosis::gamm::poolmodels::balancer::v1beta1::MsgCreateBalancerPool;
+use osmosis_std::types::osmosis::gamm::poolmodels::balancer::v1beta1::MsgCreateBalancerPoolResponse;
 use osmosis_std::types::osmosis::poolmanager::v1beta1::Params;
 use osmosis_std::types::osmosis::poolmanager::v1beta1::ParamsResponse;
 use prost::Message;
@@ -40,16 +48,18 @@ use rhaki_cw_plus::math::IntoUint;
 use rhaki_cw_plus::multi_test::helper::cw_multi_test::addons::MockApiBech32;
 use rhaki_cw_plus::multi_test::helper::cw_multi_test::error::AnyResult;
 use rhaki_cw_plus::multi_test::helper::cw_multi_test::AppResponse;
+use rhaki_cw_plus::multi_test::helper::cw_multi_test::BankSudo;
+use rhaki_cw_plus::multi_test::helper::cw_multi_test::SudoMsg;
 use rhaki_cw_plus::multi_test::multi_stargate_module::Itemable;
 use rhaki_cw_plus::multi_test::multi_stargate_module::StargateApplication;
 use rhaki_cw_plus::multi_test::multi_stargate_module::StargateUrls;
 use rhaki_cw_plus::multi_test::router::RouterWrapper;
-use rhaki_cw_plus::{urls, Stargate};
 use rhaki_cw_plus::storage::interfaces::ItemInterface;
 use rhaki_cw_plus::strum_macros;
 use rhaki_cw_plus::traits::IntoAddr;
 use rhaki_cw_plus::traits::IntoBinary;
 use rhaki_cw_plus::traits::IntoStdResult;
+use rhaki_cw_plus::{urls, Stargate};
 use std::cell::RefCell;
 use std::cmp::max;
 use std::cmp::min;
@@ -71,6 +81,7 @@ pub struct MockGamm {
 
 #[urls]
 #[rustfmt::skip]
+#[allow(clippy::enum_variant_names)]
 pub enum MockGammaMsgUrls {
     #[strum(serialize = "/osmosis.concentratedliquidity.poolmodel.concentrated.v1beta1.MsgCreateConcentratedPool")]
     MsgCreateConcentratedPool,
@@ -80,14 +91,25 @@ pub enum MockGammaMsgUrls {
     MsgTransferPositions,
     #[strum(serialize = "/osmosis.concentratedliquidity.v1beta1.MsgWithdrawPosition")]
     MsgWithdrawPosition,
+    #[strum(serialize = "/osmosis.gamm.poolmodels.balancer.v1beta1.MsgCreateBalancerPool")]
+    MsgCreateBalancerPool,
+    #[strum(serialize = "/osmosis.concentratedliquidity.v1beta1.MsgCollectSpreadRewards")]
+    MsgCollectSpreadRewards,
+    #[strum(serialize = "/osmosis.concentratedliquidity.v1beta1.MsgCollectIncentives")]
+    MsgCollectIncentives,
 }
 
+/// Shares minted to the creator of a balancer pool
+pub const BALANCER_INITIAL_SHARES: u128 = 100_000_000_000_000_000_000;
+
 #[urls]
 pub enum MockGammaQueryUrls {
     #[strum(serialize = "/osmosis.poolmanager.v1beta1.Query/Params")]
     ParamRequest,
     #[strum(serialize = "/osmosis.concentratedliquidity.v1beta1.Query/PositionById")]
     PositionById,
+    #[strum(serialize = "/osmosis.concentratedliquidity.v1beta1.Query/UserPositions")]
+    UserPositions,
 }
 
 impl StargateApplication for MockGamm {
@@ -115,6 +137,15 @@ impl StargateApplication for MockGamm {
             MockGammaMsgUrls::MsgWithdrawPosition => {
                 self.run_msg_withdraw_position(router, sender, data)
             }
+            MockGammaMsgUrls::MsgCreateBalancerPool => {
+                self.run_create_balancer_pool(block, router, sender, data)
+            }
+            MockGammaMsgUrls::MsgCollectSpreadRewards => {
+                self.run_collect_spread_rewards(block, router, sender, data)
+            }
+            MockGammaMsgUrls::MsgCollectIncentives => {
+                self.run_collect_incentives(block, router, sender, data)
+            }
         }
     }
 
@@ -132,11 +163,35 @@ impl StargateApplication for MockGamm {
                 params: Some(self.param.clone()),
             })?),
             MockGammaQueryUrls::PositionById => self.qy_position_by_id(block, data),
+            MockGammaQueryUrls::UserPositions => self.qy_user_positions(block, data),
         }
     }
 }
 
+#[allow(clippy::field_reassign_with_default)]
 impl MockGamm {
+    /// Set the rewards accrued each second by the positions of `pool_id`, starting from `time`
+    pub fn set_pool_rewards(
+        &mut self,
+        pool_id: u64,
+        time: u64,
+        spread_reward: Vec<Coin>,
+        incentives: Vec<Coin>,
+    ) -> AnyResult<()> {
+        let pool = self
+            .pools
+            .get_mut(&pool_id)
+            .ok_or(anyhow!("Pool not found: {}", pool_id))?;
+
+        pool.shanpshot_incentive_setted = time;
+        pool.incentives.spread_reward =
+            RewardsPerSeconds(spread_reward.into_iter().map(RewardPerSeconds).collect());
+        pool.incentives.incentives =
+            RewardsPerSeconds(incentives.into_iter().map(RewardPerSeconds).collect());
+
+        Ok(())
+    }
+
     fn load_pool(&self, pool_id: u64) -> AnyResult<Pool> {
         self.pools
             .get(&pool_id)
@@ -196,6 +251,78 @@ impl MockGamm {
         Ok(res)
     }
 
+    fn run_create_balancer_pool(
+        &mut self,
+        block: &BlockInfo,
+        router: &RouterWrapper,
+        sender: Addr,
+        data: Binary,
+    ) -> AnyResult<AppResponse> {
+        let msg = MsgCreateBalancerPool::decode(data.as_slice())?;
+
+        if sender != msg.sender {
+            bail!("Unauthorized sender");
+        }
+
+        let coins = msg
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                asset
+                    .token
+                    .as_ref()
+                    .map(|token| token.into_coin())
+                    .ok_or(anyhow!("Pool asset without token"))
+            })
+            .collect::<AnyResult<Vec<Coin>>>()?;
+
+        if coins.len() != 2 {
+            bail!("Only two assets balancer pools are supported");
+        }
+
+        self.last_pool_id += 1;
+
+        let pool = Pool::new(
+            self.last_pool_id,
+            MockApiBech32::new("osmo").addr_make(&format!("osmosis_pool_{}", self.last_pool_id)),
+            coins[0].denom.clone(),
+            coins[1].denom.clone(),
+            block,
+        );
+
+        // Transfer tokens
+        router.execute(
+            sender.clone(),
+            CosmosMsg::<Empty>::Bank(BankMsg::Send {
+                to_address: pool.pool_addr.to_string(),
+                amount: coins,
+            }),
+        )?;
+
+        // Mint the shares
+        router.sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: sender.to_string(),
+            amount: vec![Coin::new(
+                BALANCER_INITIAL_SHARES,
+                format!("gamm/pool/{}", self.last_pool_id),
+            )],
+        }))?;
+
+        self.pools.insert(self.last_pool_id, pool);
+
+        let mut res = AppResponse::default();
+
+        res.data = Some(
+            MsgCreateBalancerPoolResponse {
+                pool_id: self.last_pool_id,
+            }
+            .to_proto_bytes()
+            .into(),
+        );
+
+        Ok(res)
+    }
+
     fn run_create_position(
         &mut self,
         data: Binary,
@@ -251,6 +378,7 @@ impl MockGamm {
             owner: sender.clone(),
             join_time: block.time,
             last_time_claimed_rewards: block.time.seconds(),
+            last_time_claimed_incentives: block.time.seconds(),
         };
 
         self.positions
@@ -291,6 +419,99 @@ impl MockGamm {
         Ok(res)
     }
 
+    fn run_collect_spread_rewards(
+        &mut self,
+        block: &BlockInfo,
+        router: &RouterWrapper,
+        sender: Addr,
+        data: Binary,
+    ) -> AnyResult<AppResponse> {
+        let msg = MsgCollectSpreadRewards::decode(data.as_slice())?;
+
+        let mut collected = vec![];
+
+        for position_id in msg.position_ids {
+            let position = self.load_position(position_id)?;
+            let pool = self.load_pool(position.pool_id)?;
+
+            if sender != position.owner {
+                bail!("Unauthorized sender");
+            }
+
+            let time_passed = block.time.seconds()
+                - max(
+                    pool.shanpshot_incentive_setted,
+                    position.last_time_claimed_rewards,
+                );
+
+            collected.extend(pool.incentives.spread_reward.compute_rewards(time_passed));
+
+            self.load_position_mut(position_id)?
+                .last_time_claimed_rewards = block.time.seconds();
+        }
+
+        let collected = mint_rewards(router, &sender, collected)?;
+
+        let mut res = AppResponse::default();
+
+        res.data = Some(
+            MsgCollectSpreadRewardsResponse {
+                collected_spread_rewards: cosmwasm_to_proto_coins(collected),
+            }
+            .to_proto_bytes()
+            .into(),
+        );
+
+        Ok(res)
+    }
+
+    fn run_collect_incentives(
+        &mut self,
+        block: &BlockInfo,
+        router: &RouterWrapper,
+        sender: Addr,
+        data: Binary,
+    ) -> AnyResult<AppResponse> {
+        let msg = MsgCollectIncentives::decode(data.as_slice())?;
+
+        let mut collected = vec![];
+
+        for position_id in msg.position_ids {
+            let position = self.load_position(position_id)?;
+            let pool = self.load_pool(position.pool_id)?;
+
+            if sender != position.owner {
+                bail!("Unauthorized sender");
+            }
+
+            let time_passed = block.time.seconds()
+                - max(
+                    pool.shanpshot_incentive_setted,
+                    position.last_time_claimed_incentives,
+                );
+
+            collected.extend(pool.incentives.incentives.compute_rewards(time_passed));
+
+            self.load_position_mut(position_id)?
+                .last_time_claimed_incentives = block.time.seconds();
+        }
+
+        let collected = mint_rewards(router, &sender, collected)?;
+
+        let mut res = AppResponse::default();
+
+        res.data = Some(
+            MsgCollectIncentivesResponse {
+                collected_incentives: cosmwasm_to_proto_coins(collected),
+                forfeited_incentives: vec![],
+            }
+            .to_proto_bytes()
+            .into(),
+        );
+
+        Ok(res)
+    }
+
     fn run_transfer_positions(
         &mut self,
         api: &dyn Api,
@@ -305,6 +526,24 @@ impl MockGamm {
 
         let new_owner = msg.new_owner.into_addr(api)?;
 
+        for position_id in &msg.position_ids {
+            let pool_id = self.load_position(*position_id)?.pool_id;
+
+            let pool_positions = self
+                .positions
+                .values()
+                .filter(|position| position.pool_id == pool_id)
+                .count();
+
+            if pool_positions <= 1 {
+                bail!(
+                    "cannot transfer position {} because it is the last position in pool {}",
+                    position_id,
+                    pool_id
+                );
+            }
+        }
+
         for position_id in msg.position_ids {
             let position = self.load_position_mut(position_id)?;
             position.owner = new_owner.clone();
@@ -382,6 +621,38 @@ impl MockGamm {
 
         let position = self.load_position(msg.position_id)?;
 
+        Ok(PositionByIdResponse {
+            position: Some(self.position_breakdown(block, &position)?),
+        }
+        .into_binary()?)
+    }
+
+    /// Positions owned by `address`, filtered by pool if `pool_id` is not zero
+    fn qy_user_positions(&self, block: &BlockInfo, data: Binary) -> AnyResult<Binary> {
+        let msg = UserPositionsRequest::decode(data.as_slice())?;
+
+        let positions = self
+            .positions
+            .values()
+            .filter(|position| {
+                position.owner.as_str() == msg.address
+                    && (msg.pool_id == 0 || position.pool_id == msg.pool_id)
+            })
+            .map(|position| self.position_breakdown(block, position))
+            .collect::<AnyResult<Vec<_>>>()?;
+
+        Ok(UserPositionsResponse {
+            positions,
+            pagination: None,
+        }
+        .into_binary()?)
+    }
+
+    fn position_breakdown(
+        &self,
+        block: &BlockInfo,
+        position: &Position,
+    ) -> AnyResult<FullPositionBreakdown> {
         let pool = self.load_pool(position.pool_id)?;
 
         let time_passed = block.time.seconds()
@@ -390,34 +661,41 @@ impl MockGamm {
                 position.last_time_claimed_rewards,
             );
 
-        Ok(PositionByIdResponse {
-            position: Some(FullPositionBreakdown {
-                position: Some(ProtoPosition {
-                    position_id: position.position_id,
-                    address: position.owner.to_string(),
-                    pool_id: position.pool_id,
-                    lower_tick: position.lower_tick,
-                    upper_tick: position.upper_tick,
-                    join_time: Some(ProtoTimestamp {
-                        seconds: position.join_time.seconds() as i64,
-                        nanos: position.join_time.nanos() as i32,
-                    }),
-                    liquidity: position.liquidity.into_go_big_dec()?,
+        let time_passed_incentives = block.time.seconds()
+            - max(
+                pool.shanpshot_incentive_setted,
+                position.last_time_claimed_incentives,
+            );
+
+        Ok(FullPositionBreakdown {
+            position: Some(ProtoPosition {
+                position_id: position.position_id,
+                address: position.owner.to_string(),
+                pool_id: position.pool_id,
+                lower_tick: position.lower_tick,
+                upper_tick: position.upper_tick,
+                join_time: Some(ProtoTimestamp {
+                    seconds: position.join_time.seconds() as i64,
+                    nanos: position.join_time.nanos() as i32,
                 }),
-                asset0: position.amount0,
-                asset1: position.amount1,
-                claimable_spread_rewards: cosmwasm_to_proto_coins(
-                    pool.incentives.spread_reward.compute_rewards(time_passed),
-                ),
-                claimable_incentives: cosmwasm_to_proto_coins(
-                    pool.incentives.incentives.compute_rewards(time_passed),
-                ),
-                forfeited_incentives: cosmwasm_to_proto_coins(
-                    pool.incentives.forfeited.compute_rewards(time_passed),
-                ),
+                liquidity: position.liquidity.into_go_big_dec()?,
             }),
-        }
-        .into_binary()?)
+            asset0: position.amount0.clone(),
+            asset1: position.amount1.clone(),
+            claimable_spread_rewards: cosmwasm_to_proto_coins(
+                pool.incentives.spread_reward.compute_rewards(time_passed),
+            ),
+            claimable_incentives: cosmwasm_to_proto_coins(
+                pool.incentives
+                    .incentives
+                    .compute_rewards(time_passed_incentives),
+            ),
+            forfeited_incentives: cosmwasm_to_proto_coins(
+                pool.incentives
+                    .forfeited
+                    .compute_rewards(time_passed_incentives),
+            ),
+        })
     }
 }
 
@@ -503,8 +781,27 @@ pub struct Position {
     pub owner: Addr,
     pub join_time: Timestamp,
     pub last_time_claimed_rewards: u64,
+    pub last_time_claimed_incentives: u64,
+}
+
+/// Rewards are minted, as the mock pools don't accrue swap fees
+fn mint_rewards(router: &RouterWrapper, to: &Addr, rewards: Vec<Coin>) -> AnyResult<Vec<Coin>> {
+    let rewards: Vec<Coin> = rewards
+        .into_iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+
+    if !rewards.is_empty() {
+        router.sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: to.to_string(),
+            amount: rewards.clone(),
+        }))?;
+    }
+
+    Ok(rewards)
 }
 
+#[allow(clippy::wrong_self_convention)]
 trait IntoGoBigDec {
     fn into_go_big_dec(&self) -> AnyResult<String>;
 }
@@ -515,6 +812,7 @@ impl IntoGoBigDec for Decimal {
     }
 }
 
+#[allow(clippy::wrong_self_convention)]
 pub trait FromGoBigDec {
     fn from_go_big_dec(self) -> StdResult<Decimal>;
 }
@@ -531,9 +829,11 @@ where
     }
 }
 
+#[allow(clippy::wrong_self_convention)]
 trait ProtoCoinExt {
     fn into_coin(&self) -> Coin;
     fn amount(&self) -> Uint128;
+    #[allow(dead_code)]
     fn clone_with_amount(&self, amount: Uint128) -> ProtoCoin;
 }
 
@@ -0,0 +1,225 @@
+use cosmwasm_std::{
+    Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use ratatouille_pkg::{
+    ratatouille::{
+        definitions::{GameInfoResponse, LeaderboardEntry, PlayerBurnResponse},
+        msgs::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    },
+    ratatouille_factory::msgs::ExecuteMsg as FactoryExecuteMsg,
+};
+use rhaki_cw_plus::{
+    asset::{AssetInfoPrecisioned, AssetPrecisioned},
+    math::IntoDecimal,
+    multi_test::helper::{
+        anyhow::Error as AnyError,
+        build_bech32_app, create_code,
+        cw_multi_test::{AppResponse, Executor},
+        AppExt, Bech32App, Bench32AppExt,
+    },
+};
+
+use crate::helpers::AppExt2;
+
+pub const CHAIN_PREFIX: &str = "osmo";
+
+pub const START_TIME: u64 = 1_700_000_000;
+
+pub type AppResult<T> = anyhow::Result<T>;
+
+pub struct Def {
+    pub owner: Addr,
+    pub creator: Addr,
+    pub burner: Addr,
+    pub ticket: AssetInfoPrecisioned,
+    pub ticket_price: Uint128,
+    pub burn_target: Uint128,
+    pub duration: u64,
+    pub prize: AssetPrecisioned,
+    pub factory_address: Option<Addr>,
+    pub game_address: Option<Addr>,
+}
+
+impl Default for Def {
+    fn default() -> Self {
+        let app = build_bech32_app(CHAIN_PREFIX);
+        let ticket = AssetInfoPrecisioned::native("uosmo", 6);
+        Def {
+            owner: app.generate_addr("owner"),
+            creator: app.generate_addr("creator"),
+            burner: app.generate_addr("burner"),
+            ticket_price: Uint128::new(1_000_000),
+            burn_target: Uint128::new(10_000_000),
+            duration: 86_400,
+            prize: ticket.to_asset(100_u128.into_decimal()),
+            ticket,
+            factory_address: None,
+            game_address: None,
+        }
+    }
+}
+
+// --- Mock factory, accept every `UpdateGame` sent by the game ---
+
+fn mock_factory_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn mock_factory_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: FactoryExecuteMsg,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn mock_factory_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+pub fn startup(def: &mut Def) -> Bech32App {
+    let mut app = build_bech32_app(CHAIN_PREFIX);
+
+    app.set_block_time(START_TIME);
+
+    let factory_code_id = app.store_code(create_code(
+        mock_factory_instantiate,
+        mock_factory_execute,
+        mock_factory_query,
+    ));
+
+    let game_code_id = app.store_code(create_code(
+        ratatouille::contract::instantiate,
+        ratatouille::contract::execute,
+        ratatouille::contract::query,
+    ));
+
+    let factory_address = app
+        .instantiate_contract(
+            factory_code_id,
+            def.owner.clone(),
+            &Empty {},
+            &[],
+            "Mock Ratatouille Factory",
+            None,
+        )
+        .unwrap();
+
+    app.mint(def.creator.clone(), def.prize.clone());
+
+    let game_address = app
+        .instantiate_contract(
+            game_code_id,
+            def.creator.clone(),
+            &InstantiateMsg {
+                owner: def.owner.to_string(),
+                game_id: Uint128::one(),
+                creator: def.creator.to_string(),
+                factory_address: factory_address.to_string(),
+                burner_address: def.burner.to_string(),
+                burn_target: def.burn_target,
+                ticket_price: def.ticket_price,
+                ticket_denom: def.ticket.info.inner(),
+                duration: def.duration,
+            },
+            &[def.prize.clone().try_into().unwrap()],
+            "Ratatouille",
+            Some(def.owner.to_string()),
+        )
+        .unwrap();
+
+    def.factory_address = Some(factory_address);
+    def.game_address = Some(game_address);
+
+    app
+}
+
+pub fn run_play(
+    app: &mut Bech32App,
+    def: &Def,
+    sender: &Addr,
+    tickets: AssetPrecisioned,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.game_address.clone().unwrap(),
+        &ExecuteMsg::Play {},
+        &[tickets.try_into().unwrap()],
+    )
+}
+
+pub fn run_end_game(
+    app: &mut Bech32App,
+    def: &Def,
+    sender: &Addr,
+    winner: &Addr,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.game_address.clone().unwrap(),
+        &ExecuteMsg::EndGame {
+            winner: winner.to_string(),
+        },
+        &[],
+    )
+}
+
+pub fn run_claim(app: &mut Bech32App, def: &Def, sender: &Addr) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.game_address.clone().unwrap(),
+        &ExecuteMsg::Claim {},
+        &[],
+    )
+}
+
+pub fn run_refund(app: &mut Bech32App, def: &Def, sender: &Addr) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.game_address.clone().unwrap(),
+        &ExecuteMsg::Refund {},
+        &[],
+    )
+}
+
+pub fn qy_game_info(app: &Bech32App, def: &Def) -> AppResult<GameInfoResponse> {
+    Ok(app
+        .wrap()
+        .query_wasm_smart(def.game_address.clone().unwrap(), &QueryMsg::GameInfo {})?)
+}
+
+pub fn qy_winner(app: &Bech32App, def: &Def) -> AppResult<PlayerBurnResponse> {
+    Ok(app
+        .wrap()
+        .query_wasm_smart(def.game_address.clone().unwrap(), &QueryMsg::Winner {})?)
+}
+
+pub fn qy_player_burned_amount(
+    app: &Bech32App,
+    def: &Def,
+    player: &Addr,
+) -> AppResult<PlayerBurnResponse> {
+    Ok(app.wrap().query_wasm_smart(
+        def.game_address.clone().unwrap(),
+        &QueryMsg::PlayerBurnedAmount {
+            player: player.to_string(),
+        },
+    )?)
+}
+
+pub fn qy_leaderboard(
+    app: &Bech32App,
+    def: &Def,
+    limit: Option<u32>,
+) -> AppResult<Vec<LeaderboardEntry>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.game_address.clone().unwrap(),
+        &QueryMsg::Leaderboard { limit },
+    )?)
+}
@@ -0,0 +1,129 @@
+use cosmwasm_std::{Decimal, Uint128};
+use ratatouille_pkg::ratatouille::definitions::Status;
+use rhaki_cw_plus::{
+    math::IntoDecimal,
+    multi_test::helper::{AppExt, Bench32AppExt, UnwrapError},
+};
+
+use crate::helpers::AppExt2;
+
+use super::helpers::{
+    qy_game_info, qy_leaderboard, qy_player_burned_amount, qy_winner, run_claim, run_end_game,
+    run_play, run_refund, startup, Def, START_TIME,
+};
+
+#[test]
+#[rustfmt::skip]
+fn play_end_and_claim() {
+    let mut def = Def::default();
+
+    let mut app = startup(&mut def);
+
+    let info = qy_game_info(&app, &def).unwrap();
+    assert_eq!(info.status, Status::OPEN.to_string());
+    assert_eq!(info.end_date, START_TIME + def.duration);
+    assert_eq!(info.prize, def.prize.amount_raw());
+    assert_eq!(info.total_burned, Uint128::zero());
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    let user_3 = app.generate_addr("user_3");
+
+    app.mint(&user_1, def.ticket.to_asset(100_u128.into_decimal()));
+    app.mint(&user_2, def.ticket.to_asset(100_u128.into_decimal()));
+    app.mint(&user_3, def.ticket.to_asset(100_u128.into_decimal()));
+
+    // Only multiple of ticket_price are accepted
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset("1.5".into_decimal())).unwrap_err_contains("Invalid Ticket Amount");
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(Decimal::zero())).unwrap_err();
+
+    // Not enough burned to end the game
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(2_u128.into_decimal())).unwrap();
+    run_end_game(&mut app, &def, &def.owner, &user_1).unwrap_err_contains("Burn Target not reached");
+
+    run_play(&mut app, &def, &user_2, def.ticket.to_asset(5_u128.into_decimal())).unwrap();
+    run_play(&mut app, &def, &user_3, def.ticket.to_asset(1_u128.into_decimal())).unwrap();
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(2_u128.into_decimal())).unwrap();
+
+    assert_eq!(qy_player_burned_amount(&app, &def, &user_1).unwrap().burned, Uint128::new(4_000_000));
+    assert_eq!(qy_game_info(&app, &def).unwrap().total_burned, Uint128::new(10_000_000));
+
+    // Leaderboard is sorted by burned amount
+    let leaderboard = qy_leaderboard(&app, &def, None).unwrap();
+    assert_eq!(leaderboard.iter().map(|val| val.player.clone()).collect::<Vec<_>>(), vec![user_2.clone(), user_1.clone(), user_3.clone()]);
+    assert_eq!(leaderboard.iter().map(|val| val.burned).collect::<Vec<_>>(), vec![Uint128::new(5_000_000), Uint128::new(4_000_000), Uint128::new(1_000_000)]);
+    assert_eq!(qy_leaderboard(&app, &def, Some(1)).unwrap().len(), 1);
+
+    // Refund is not possible when the game is still open
+    run_refund(&mut app, &def, &user_1).unwrap_err_contains("Game not Expired");
+
+    let random = app.generate_addr("random");
+
+    run_end_game(&mut app, &def, &random, &user_2).unwrap_err_contains("Unauthorized");
+    run_end_game(&mut app, &def, &def.owner, &random).unwrap_err_contains("Not a Player");
+    run_end_game(&mut app, &def, &def.owner, &user_2).unwrap();
+
+    let info = qy_game_info(&app, &def).unwrap();
+    assert_eq!(info.status, Status::CLOSED.to_string());
+    assert_eq!(info.winner, Some(user_2.to_string()));
+    assert_eq!(qy_winner(&app, &def).unwrap().burned, Uint128::new(5_000_000));
+
+    // All tickets are burned
+    assert_eq!(app.qy_balance(&def.burner, &def.ticket).unwrap().amount_raw(), Uint128::new(10_000_000));
+
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(1_u128.into_decimal())).unwrap_err_contains("Game Closed");
+    run_end_game(&mut app, &def, &def.owner, &user_1).unwrap_err_contains("Game Closed");
+
+    run_claim(&mut app, &def, &user_1).unwrap_err_contains("Unauthorized");
+    run_claim(&mut app, &def, &user_2).unwrap();
+    run_claim(&mut app, &def, &user_2).unwrap_err_contains("Prize already Claimed");
+
+    assert_eq!(app.qy_balance(&user_2, &def.ticket).unwrap().amount_precisioned().unwrap(), 195_u128.into_decimal());
+    assert_eq!(app.qy_balance(def.game_address.as_ref().unwrap(), &def.ticket).unwrap().amount_raw(), Uint128::zero());
+}
+
+#[test]
+#[rustfmt::skip]
+fn unfulfilled_refund() {
+    let mut def = Def::default();
+
+    let mut app = startup(&mut def);
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+
+    app.mint(&user_1, def.ticket.to_asset(100_u128.into_decimal()));
+    app.mint(&user_2, def.ticket.to_asset(100_u128.into_decimal()));
+
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(3_u128.into_decimal())).unwrap();
+    run_play(&mut app, &def, &user_2, def.ticket.to_asset(4_u128.into_decimal())).unwrap();
+
+    app.set_block_time(START_TIME + def.duration);
+
+    assert_eq!(qy_game_info(&app, &def).unwrap().status, Status::UNFULFILLED.to_string());
+
+    run_play(&mut app, &def, &user_1, def.ticket.to_asset(3_u128.into_decimal())).unwrap_err_contains("Game Expired");
+    run_end_game(&mut app, &def, &def.owner, &user_1).unwrap_err_contains("Burn Target not reached");
+
+    let random = app.generate_addr("random");
+    run_refund(&mut app, &def, &random).unwrap_err_contains("Nothing to Refund");
+
+    run_refund(&mut app, &def, &user_1).unwrap();
+    run_refund(&mut app, &def, &user_1).unwrap_err_contains("Nothing to Refund");
+    run_refund(&mut app, &def, &user_2).unwrap();
+
+    assert_eq!(app.qy_balance(&user_1, &def.ticket).unwrap().amount_precisioned().unwrap(), 100_u128.into_decimal());
+    assert_eq!(app.qy_balance(&user_2, &def.ticket).unwrap().amount_precisioned().unwrap(), 100_u128.into_decimal());
+    assert_eq!(qy_leaderboard(&app, &def, None).unwrap().len(), 0);
+
+    // The prize goes back to the creator
+    run_refund(&mut app, &def, &def.creator).unwrap();
+    run_refund(&mut app, &def, &def.creator).unwrap_err_contains("Nothing to Refund");
+
+    assert_eq!(app.qy_balance(&def.creator, &def.ticket).unwrap().amount_raw(), def.prize.amount_raw());
+    assert_eq!(app.qy_balance(def.game_address.as_ref().unwrap(), &def.ticket).unwrap().amount_raw(), Uint128::zero());
+
+    let info = qy_game_info(&app, &def).unwrap();
+    assert_eq!(info.status, Status::UNFULFILLED.to_string());
+    assert_eq!(info.total_burned, Uint128::zero());
+}
@@ -0,0 +1,216 @@
+use cosmwasm_std::{Addr, Uint128};
+use ratatouille_pkg::{
+    ratatouille::msgs::ExecuteMsg as GameExecuteMsg,
+    ratatouille_factory::{
+        definitions::{GameInfo, PositionInfo},
+        msgs::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    },
+};
+use rhaki_cw_plus::{
+    asset::{AssetInfoPrecisioned, AssetPrecisioned},
+    math::IntoDecimal,
+    multi_test::helper::{
+        anyhow::Error as AnyError,
+        build_bech32_app, create_code,
+        cw_multi_test::{AppResponse, Executor},
+        Bech32App, Bench32AppExt,
+    },
+};
+
+use crate::helpers::AppExt2;
+
+pub const CHAIN_PREFIX: &str = "osmo";
+
+pub const START_TIME: u64 = 1_700_000_000;
+
+pub type AppResult<T> = anyhow::Result<T>;
+
+pub struct Def {
+    pub owner: Addr,
+    pub burner: Addr,
+    pub denom: AssetInfoPrecisioned,
+    pub game_fee: AssetPrecisioned,
+    pub ticket_price: Uint128,
+    pub game_code_id: Option<u64>,
+    pub factory_address: Option<Addr>,
+}
+
+impl Default for Def {
+    fn default() -> Self {
+        let app = build_bech32_app(CHAIN_PREFIX);
+        let denom = AssetInfoPrecisioned::native("uosmo", 6);
+        Def {
+            owner: app.generate_addr("owner"),
+            burner: app.generate_addr("burner"),
+            game_fee: denom.to_asset(5_u128.into_decimal()),
+            denom,
+            ticket_price: Uint128::new(1_000_000),
+            game_code_id: None,
+            factory_address: None,
+        }
+    }
+}
+
+pub fn startup(def: &mut Def) -> Bech32App {
+    let mut app = build_bech32_app(CHAIN_PREFIX);
+
+    app.set_block_time(START_TIME);
+
+    let factory_code_id = app.store_code(create_code(
+        ratatouille_factory::contract::instantiate,
+        ratatouille_factory::contract::execute,
+        ratatouille_factory::contract::query,
+    ));
+
+    let game_code_id = app.store_code(create_code(
+        ratatouille::contract::instantiate,
+        ratatouille::contract::execute,
+        ratatouille::contract::query,
+    ));
+
+    def.game_code_id = Some(game_code_id);
+
+    let factory_address = app
+        .instantiate_contract(
+            factory_code_id,
+            def.owner.clone(),
+            &InstantiateMsg {
+                owner: def.owner.to_string(),
+                burner_address: def.burner.to_string(),
+                game_fee: def.game_fee.amount_raw(),
+                denom_fee: def.denom.info.inner(),
+                ticket_price: def.ticket_price,
+                game_code_id,
+            },
+            &[],
+            "Ratatouille Factory",
+            Some(def.owner.to_string()),
+        )
+        .unwrap();
+
+    def.factory_address = Some(factory_address);
+
+    app
+}
+
+pub fn run_create_game(
+    app: &mut Bech32App,
+    def: &Def,
+    sender: &Addr,
+    burn_target: impl Into<Uint128>,
+    end_date: u64,
+    funds: Vec<AssetPrecisioned>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        def.factory_address.clone().unwrap(),
+        &ExecuteMsg::CreateGame {
+            burn_target: burn_target.into(),
+            end_date,
+        },
+        &funds
+            .into_iter()
+            .map(|val| val.try_into().unwrap())
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn run_game(
+    app: &mut Bech32App,
+    sender: &Addr,
+    game: &Addr,
+    msg: GameExecuteMsg,
+    funds: Vec<AssetPrecisioned>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        game.clone(),
+        &msg,
+        &funds
+            .into_iter()
+            .map(|val| val.try_into().unwrap())
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn qy_game_info(app: &Bech32App, def: &Def, game: &Addr) -> AppResult<GameInfo> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::GameInfo {
+            address: game.to_string(),
+        },
+    )?)
+}
+
+pub fn qy_all_games(
+    app: &Bech32App,
+    def: &Def,
+    start_after: Option<&Addr>,
+    limit: Option<u32>,
+) -> AppResult<Vec<GameInfo>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::AllGames {
+            start_after: start_after.map(|val| val.to_string()),
+            limit,
+        },
+    )?)
+}
+
+pub fn qy_games_by_creator(app: &Bech32App, def: &Def, creator: &Addr) -> AppResult<Vec<GameInfo>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::GamesByCreator {
+            creator: creator.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)
+}
+
+pub fn qy_games_by_status(
+    app: &Bech32App,
+    def: &Def,
+    status: impl Into<String>,
+) -> AppResult<Vec<GameInfo>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::GamesByStatus {
+            status: status.into(),
+            start_after: None,
+            limit: None,
+        },
+    )?)
+}
+
+pub fn qy_games_by_player(
+    app: &Bech32App,
+    def: &Def,
+    player: &Addr,
+) -> AppResult<Vec<PositionInfo>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::GamesByPlayer {
+            player: player.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?)
+}
+
+pub fn qy_players_by_game(
+    app: &Bech32App,
+    def: &Def,
+    game: &Addr,
+    start_after: Option<&Addr>,
+    limit: Option<u32>,
+) -> AppResult<Vec<PositionInfo>> {
+    Ok(app.wrap().query_wasm_smart(
+        def.factory_address.clone().unwrap(),
+        &QueryMsg::PlayersByGame {
+            game: game.to_string(),
+            start_after: start_after.map(|val| val.to_string()),
+            limit,
+        },
+    )?)
+}
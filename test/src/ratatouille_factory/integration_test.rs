@@ -0,0 +1,132 @@
+use cosmwasm_std::{Addr, Uint128};
+use ratatouille_pkg::{
+    ratatouille::{definitions::Status, msgs::ExecuteMsg as GameExecuteMsg},
+    ratatouille_factory::msgs::ExecuteMsg,
+};
+use rhaki_cw_plus::{
+    math::IntoDecimal,
+    multi_test::helper::{
+        cw_multi_test::{AppResponse, Executor},
+        AppExt, Bench32AppExt, UnwrapError,
+    },
+    traits::IntoAddr,
+};
+
+use crate::helpers::AppExt2;
+
+use super::helpers::{
+    qy_all_games, qy_game_info, qy_games_by_creator, qy_games_by_player, qy_games_by_status,
+    qy_players_by_game, run_create_game, run_game, startup, Def, START_TIME,
+};
+
+fn game_addr_from_response(response: &AppResponse) -> Addr {
+    response
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "game_addr")
+        .map(|attr| attr.value.into_unchecked_addr())
+        .unwrap()
+}
+
+#[test]
+#[rustfmt::skip]
+fn create_and_index_games() {
+    let mut def = Def::default();
+
+    let mut app = startup(&mut def);
+
+    let creator = app.generate_addr("creator");
+    app.mint(&creator, def.denom.to_asset(1_000_u128.into_decimal()));
+
+    let end_date = START_TIME + 86_400;
+
+    run_create_game(&mut app, &def, &creator, 10_000_000_u128, START_TIME, vec![def.denom.to_asset(105_u128.into_decimal())]).unwrap_err_contains("Invalid End Date");
+    run_create_game(&mut app, &def, &creator, 10_000_000_u128, end_date, vec![def.denom.to_asset(3_u128.into_decimal())]).unwrap_err_contains("Insufficient Fee");
+    run_create_game(&mut app, &def, &creator, 10_000_000_u128, end_date, vec![def.game_fee.clone()]).unwrap_err_contains("Invalid Prize");
+
+    let res = run_create_game(&mut app, &def, &creator, 10_000_000_u128, end_date, vec![def.denom.to_asset(105_u128.into_decimal())]).unwrap();
+    let game_1 = game_addr_from_response(&res);
+
+    let res = run_create_game(&mut app, &def, &creator, 10_000_000_u128, end_date, vec![def.denom.to_asset(55_u128.into_decimal())]).unwrap();
+    let game_2 = game_addr_from_response(&res);
+
+    // The game fee is sent to the burner, the prize to the game
+    assert_eq!(app.qy_balance(&def.burner, &def.denom).unwrap().amount_precisioned().unwrap(), 10_u128.into_decimal());
+    assert_eq!(app.qy_balance(&game_1, &def.denom).unwrap().amount_precisioned().unwrap(), 100_u128.into_decimal());
+    assert_eq!(app.qy_balance(&game_2, &def.denom).unwrap().amount_precisioned().unwrap(), 50_u128.into_decimal());
+
+    let info = qy_game_info(&app, &def, &game_1).unwrap();
+    assert_eq!(info.game_id, Uint128::zero());
+    assert_eq!(info.prize, Uint128::new(100_000_000));
+    assert_eq!(info.status, Status::OPEN.to_string());
+    assert_eq!(qy_game_info(&app, &def, &game_2).unwrap().game_id, Uint128::one());
+
+    assert_eq!(qy_all_games(&app, &def, None, None).unwrap().len(), 2);
+    assert_eq!(qy_all_games(&app, &def, None, Some(1)).unwrap().len(), 1);
+    assert_eq!(qy_games_by_creator(&app, &def, &creator).unwrap().len(), 2);
+    assert_eq!(qy_games_by_status(&app, &def, Status::OPEN.to_string()).unwrap().len(), 2);
+
+    // Only games can update the index
+    app.execute_contract(
+        creator.clone(),
+        def.factory_address.clone().unwrap(),
+        &ExecuteMsg::UpdateGame { player: creator.clone(), new_burn_amount: Some(Uint128::one()), winner: None },
+        &[],
+    )
+    .unwrap_err_contains("Unauthorized");
+
+    let user_1 = app.generate_addr("user_1");
+    let user_2 = app.generate_addr("user_2");
+    app.mint(&user_1, def.denom.to_asset(100_u128.into_decimal()));
+    app.mint(&user_2, def.denom.to_asset(100_u128.into_decimal()));
+
+    run_game(&mut app, &user_1, &game_1, GameExecuteMsg::Play {}, vec![def.denom.to_asset(3_u128.into_decimal())]).unwrap();
+    run_game(&mut app, &user_2, &game_1, GameExecuteMsg::Play {}, vec![def.denom.to_asset(4_u128.into_decimal())]).unwrap();
+    run_game(&mut app, &user_2, &game_1, GameExecuteMsg::Play {}, vec![def.denom.to_asset(4_u128.into_decimal())]).unwrap();
+    run_game(&mut app, &user_1, &game_2, GameExecuteMsg::Play {}, vec![def.denom.to_asset(2_u128.into_decimal())]).unwrap();
+
+    assert_eq!(qy_game_info(&app, &def, &game_1).unwrap().current_burn, Uint128::new(11_000_000));
+    assert_eq!(qy_game_info(&app, &def, &game_2).unwrap().current_burn, Uint128::new(2_000_000));
+
+    let players = qy_players_by_game(&app, &def, &game_1, None, None).unwrap();
+    assert_eq!(players.len(), 2);
+    assert_eq!(players.iter().find(|val| val.player == user_2).unwrap().burn_amount, Uint128::new(8_000_000));
+
+    let first_page = qy_players_by_game(&app, &def, &game_1, None, Some(1)).unwrap();
+    let second_page = qy_players_by_game(&app, &def, &game_1, Some(&first_page[0].player), Some(1)).unwrap();
+    assert_eq!(second_page.len(), 1);
+    assert_ne!(first_page[0].player, second_page[0].player);
+
+    assert_eq!(qy_games_by_player(&app, &def, &user_1).unwrap().len(), 2);
+    assert_eq!(qy_games_by_player(&app, &def, &user_2).unwrap().len(), 1);
+
+    // End game 1
+    run_game(&mut app, &def.owner, &game_1, GameExecuteMsg::EndGame { winner: user_2.to_string() }, vec![]).unwrap();
+
+    let info = qy_game_info(&app, &def, &game_1).unwrap();
+    assert_eq!(info.status, Status::CLOSED.to_string());
+    assert_eq!(info.winner, Some(user_2.clone()));
+
+    let position = qy_games_by_player(&app, &def, &user_2).unwrap();
+    assert!(position[0].winner);
+
+    assert_eq!(qy_games_by_status(&app, &def, Status::CLOSED.to_string()).unwrap().len(), 1);
+
+    // Game 2 doesn't reach the burn target
+    app.set_block_time(end_date);
+
+    assert_eq!(qy_game_info(&app, &def, &game_2).unwrap().status, Status::UNFULFILLED.to_string());
+
+    // Indexed by the computed status before the game notifies the factory
+    assert_eq!(qy_games_by_status(&app, &def, Status::UNFULFILLED.to_string()).unwrap()[0].address, game_2);
+    assert_eq!(qy_games_by_status(&app, &def, Status::OPEN.to_string()).unwrap().len(), 0);
+
+    run_game(&mut app, &user_1, &game_2, GameExecuteMsg::Refund {}, vec![]).unwrap();
+
+    let info = qy_game_info(&app, &def, &game_2).unwrap();
+    assert_eq!(info.current_burn, Uint128::zero());
+    assert_eq!(qy_games_by_status(&app, &def, Status::UNFULFILLED.to_string()).unwrap().len(), 1);
+    assert_eq!(qy_games_by_status(&app, &def, Status::OPEN.to_string()).unwrap().len(), 0);
+    assert_eq!(qy_games_by_player(&app, &def, &user_1).unwrap().len(), 1);
+}